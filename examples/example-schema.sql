@@ -133,12 +133,12 @@ CREATE TABLE type_coverage (
     medium_blob MEDIUMBLOB,                         -- byte[]
     long_blob LONGBLOB,                             -- byte[]
     single_bit BIT(1),                              -- Boolean
-    multi_bit BIT(8),                               -- byte[]
+    multi_bit BIT(8),                               -- Integer (unsigned, fits width)
     char_val CHAR(10),                              -- String
     tiny_text TINYTEXT,                             -- String
     medium_text MEDIUMTEXT,                         -- String
     long_text LONGTEXT,                             -- String
-    set_val SET('A', 'B', 'C'),                     -- String
+    set_val SET('A', 'B', 'C'),                     -- Set (flags type)
     INDEX idx_tiny_int (tiny_int),
     INDEX idx_event_time (event_time)
 );
@@ -286,3 +286,39 @@ CREATE TABLE order_item_details (
         REFERENCES order_items(order_id, product_id, variant_id) ON DELETE CASCADE,
     INDEX idx_detail_type (detail_type)
 );
+
+-- ============================================================
+-- UUID storage forms (uuid feature, uuid_columns = "*_uuid")
+-- ============================================================
+
+CREATE TABLE uuid_records (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    external_uuid BINARY(16) NOT NULL UNIQUE,      -- uuid::Uuid
+    parent_uuid BINARY(16),                        -- Option<uuid::Uuid>
+    label_id CHAR(36),                             -- String (text form)
+    INDEX idx_parent_uuid (parent_uuid)
+);
+
+-- ============================================================
+-- Typed JSON (json_types override)
+-- ============================================================
+
+CREATE TABLE json_documents (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    payload JSON NOT NULL,                         -- rdbi::Json<crate::JsonPayload>
+    meta JSON                                      -- Option<serde_json::Value>
+);
+
+-- ============================================================
+-- Spatial types (geo_types = true)
+-- ============================================================
+
+CREATE TABLE delivery_zones (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    depot POINT NOT NULL,                          -- geo_types::Point<f64>
+    area POLYGON,                                  -- Option<geo_types::Polygon<f64>>
+    route LINESTRING,                              -- Option<geo_types::LineString<f64>>
+    stops MULTIPOINT,                              -- Option<geo_types::MultiPoint<f64>>
+    shape GEOMETRY                                 -- Option<geo_types::Geometry<f64>>
+);
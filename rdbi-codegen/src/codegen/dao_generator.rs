@@ -13,7 +13,7 @@ use super::naming::{
     escape_field_name, generate_delete_by_method_name, generate_find_by_list_method_name,
     generate_find_by_method_name, generate_update_by_method_name, pluralize, to_struct_name,
 };
-use super::type_resolver::TypeResolver;
+use super::type_resolver::{TypeMapping, TypeResolver};
 
 /// Priority levels for method signature deduplication
 const PRIORITY_PRIMARY_KEY: u8 = 1;
@@ -46,6 +46,7 @@ impl MethodSignature {
 
 /// Generate DAO files for all tables
 pub fn generate_daos(tables: &[TableMetadata], config: &CodegenConfig) -> Result<()> {
+    let types = &TypeMapping::from_config(config);
     let output_dir = &config.output_dao_dir;
     fs::create_dir_all(output_dir)?;
 
@@ -66,14 +67,19 @@ pub fn generate_daos(tables: &[TableMetadata], config: &CodegenConfig) -> Result
 
     // Generate each DAO file
     for table in tables {
-        generate_dao_file(table, output_dir, &config.models_module)?;
+        generate_dao_file(table, output_dir, &config.models_module, types)?;
     }
 
     Ok(())
 }
 
 /// Generate a single DAO file for a table
-fn generate_dao_file(table: &TableMetadata, output_dir: &Path, models_module: &str) -> Result<()> {
+fn generate_dao_file(
+    table: &TableMetadata,
+    output_dir: &Path,
+    models_module: &str,
+    types: &TypeMapping,
+) -> Result<()> {
     let struct_name = to_struct_name(&table.name);
     let file_name = format!("{}.rs", heck::AsSnakeCase(&table.name));
     debug!("Generating DAO for {} -> {}", struct_name, file_name);
@@ -89,7 +95,7 @@ fn generate_dao_file(table: &TableMetadata, output_dir: &Path, models_module: &s
     let mut needs_decimal = false;
 
     for col in &table.columns {
-        let rust_type = TypeResolver::resolve(col, &table.name);
+        let rust_type = TypeResolver::resolve_with(col, &table.name, types);
         if rust_type.needs_chrono() {
             needs_chrono = true;
         }
@@ -98,17 +104,17 @@ fn generate_dao_file(table: &TableMetadata, output_dir: &Path, models_module: &s
         }
     }
 
-    // Check if we have enum columns for imports
-    let has_enums = table.columns.iter().any(|c| c.is_enum());
+    // Check if we have enum or set columns for imports
+    let has_enums = table.columns.iter().any(|c| c.is_enum() || c.is_set());
 
     // Generate imports
     code.push_str("use rdbi::{Pool, Query, Result};\n");
     code.push_str(&format!("use crate::{}::{};\n", models_module, struct_name));
 
     if has_enums {
-        // Import enum types from the same struct module
+        // Import enum and set types from the same struct module
         for col in &table.columns {
-            if col.is_enum() {
+            if col.is_enum() || col.is_set() {
                 let enum_name = super::naming::to_enum_name(&table.name, &col.name);
                 code.push_str(&format!("use crate::{}::{};\n", models_module, enum_name));
             }
@@ -143,14 +149,20 @@ fn generate_dao_file(table: &TableMetadata, output_dir: &Path, models_module: &s
             &column_map,
             &struct_name,
             &select_columns,
+            types,
         ));
     }
 
     // Generate insert methods
-    code.push_str(&generate_insert_methods(table, &struct_name, &column_map));
+    code.push_str(&generate_insert_methods(
+        table,
+        &struct_name,
+        &column_map,
+        types,
+    ));
 
     // Generate insert_plain method (individual params)
-    code.push_str(&generate_insert_plain_method(table, &column_map));
+    code.push_str(&generate_insert_plain_method(table, &column_map, types));
 
     // Generate batch insert method
     code.push_str(&generate_insert_all_method(table, &struct_name));
@@ -160,9 +172,14 @@ fn generate_dao_file(table: &TableMetadata, output_dir: &Path, models_module: &s
 
     // Generate update methods
     if table.primary_key.is_some() {
-        code.push_str(&generate_update_methods(table, &struct_name, &column_map));
+        code.push_str(&generate_update_methods(
+            table,
+            &struct_name,
+            &column_map,
+            types,
+        ));
         // Generate update_plain method (individual params)
-        code.push_str(&generate_update_plain_method(table, &column_map));
+        code.push_str(&generate_update_plain_method(table, &column_map, types));
     }
 
     // Generate index-aware findBy methods
@@ -178,6 +195,7 @@ fn generate_dao_file(table: &TableMetadata, output_dir: &Path, models_module: &s
             &column_map,
             &struct_name,
             &select_columns,
+            types,
         ));
     }
 
@@ -187,6 +205,7 @@ fn generate_dao_file(table: &TableMetadata, output_dir: &Path, models_module: &s
         &column_map,
         &struct_name,
         &select_columns,
+        types,
     ));
 
     // Generate composite enum list methods (e.g., find_by_user_id_and_device_types)
@@ -195,6 +214,7 @@ fn generate_dao_file(table: &TableMetadata, output_dir: &Path, models_module: &s
         &column_map,
         &struct_name,
         &select_columns,
+        types,
     ));
 
     // Generate pagination methods
@@ -234,12 +254,13 @@ fn build_params(
     columns: &[String],
     column_map: &HashMap<&str, &ColumnMetadata>,
     table_name: &str,
+    types: &TypeMapping,
 ) -> String {
     columns
         .iter()
         .map(|c| {
             let col = column_map.get(c.as_str()).unwrap();
-            let rust_type = TypeResolver::resolve(col, table_name);
+            let rust_type = TypeResolver::resolve_with(col, table_name, types);
             let param_type = rust_type.to_param_type_string();
             format!("{}: {}", escape_field_name(c), param_type)
         })
@@ -260,10 +281,19 @@ fn generate_bind_section(columns: &[String]) -> String {
 fn generate_find_all(table: &TableMetadata, struct_name: &str, select_columns: &str) -> String {
     format!(
         r#"/// Find all records
+///
+/// Fails with `rdbi::Error::TooManyRows` if the table holds more rows than the pool's `max_rows`.
 pub async fn find_all<P: Pool>(pool: &P) -> Result<Vec<{struct_name}>> {{
 Query::new("SELECT {select_columns} FROM `{table_name}`")
 .fetch_all(pool).await
 }}
+
+/// Find all records, failing with `rdbi::Error::TooManyRows` if there are more than `max_rows`
+pub async fn find_all_limited<P: Pool>(pool: &P, max_rows: u64) -> Result<Vec<{struct_name}>> {{
+Query::new("SELECT {select_columns} FROM `{table_name}`")
+.max_rows(max_rows)
+.fetch_all(pool).await
+}}
 "#,
         struct_name = struct_name,
         select_columns = select_columns,
@@ -291,11 +321,12 @@ fn generate_pk_methods(
     column_map: &HashMap<&str, &ColumnMetadata>,
     struct_name: &str,
     select_columns: &str,
+    types: &TypeMapping,
 ) -> String {
     let mut code = String::new();
 
     let method_name = generate_find_by_method_name(&pk.columns);
-    let params = build_params(&pk.columns, column_map, &table.name);
+    let params = build_params(&pk.columns, column_map, &table.name, types);
     let where_clause = build_where_clause(&pk.columns);
     let bind_section = generate_bind_section(&pk.columns);
 
@@ -342,6 +373,7 @@ fn generate_insert_methods(
     table: &TableMetadata,
     struct_name: &str,
     _column_map: &HashMap<&str, &ColumnMetadata>,
+    types: &TypeMapping,
 ) -> String {
     let mut code = String::new();
 
@@ -372,7 +404,7 @@ fn generate_insert_methods(
         .iter()
         .map(|c| {
             let field = escape_field_name(&c.name);
-            let rust_type = TypeResolver::resolve(c, &table.name);
+            let rust_type = TypeResolver::resolve_with(c, &table.name, types);
             if rust_type.is_copy() {
                 format!(".bind(entity.{})", field)
             } else {
@@ -405,6 +437,7 @@ Query::new("INSERT INTO `{table_name}` ({column_list}) VALUES ({placeholders})")
 fn generate_insert_plain_method(
     table: &TableMetadata,
     column_map: &HashMap<&str, &ColumnMetadata>,
+    types: &TypeMapping,
 ) -> String {
     // Get non-auto-increment columns for insert
     let insert_columns: Vec<&ColumnMetadata> = table
@@ -418,7 +451,7 @@ fn generate_insert_plain_method(
     }
 
     let column_names: Vec<String> = insert_columns.iter().map(|c| c.name.clone()).collect();
-    let params = build_params(&column_names, column_map, &table.name);
+    let params = build_params(&column_names, column_map, &table.name, types);
 
     let column_list = insert_columns
         .iter()
@@ -481,7 +514,7 @@ fn has_unique_index(table: &TableMetadata) -> bool {
     table.indexes.iter().any(|idx| idx.unique)
 }
 
-/// Generate upsert method (INSERT ... ON DUPLICATE KEY UPDATE)
+/// Generate upsert method (INSERT ... ON DUPLICATE KEY UPDATE) using BatchUpsert
 fn generate_upsert_method(table: &TableMetadata, struct_name: &str) -> String {
     // Only generate if table has primary key or unique index
     if table.primary_key.is_none() && !has_unique_index(table) {
@@ -518,53 +551,24 @@ fn generate_upsert_method(table: &TableMetadata, struct_name: &str) -> String {
         return String::new();
     }
 
-    let column_list = insert_columns
-        .iter()
-        .map(|c| format!("`{}`", c.name))
-        .collect::<Vec<_>>()
-        .join(", ");
-
-    let placeholders = insert_columns
-        .iter()
-        .map(|_| "?")
-        .collect::<Vec<_>>()
-        .join(", ");
-
-    let update_clause = update_columns
+    let update_column_list = update_columns
         .iter()
-        .map(|c| format!("`{name}` = VALUES(`{name}`)", name = c.name))
+        .map(|c| format!("\"{}\"", c.name))
         .collect::<Vec<_>>()
         .join(", ");
 
-    let bind_fields = insert_columns
-        .iter()
-        .map(|c| {
-            let field = escape_field_name(&c.name);
-            let rust_type = TypeResolver::resolve(c, &table.name);
-            if rust_type.is_copy() {
-                format!(".bind(entity.{})", field)
-            } else {
-                format!(".bind(&entity.{})", field)
-            }
-        })
-        .collect::<Vec<_>>()
-        .join("\n");
-
     format!(
         r#"/// Upsert a record (insert or update on duplicate key)
 /// Returns rows_affected: 1 if inserted, 2 if updated
 pub async fn upsert<P: Pool>(pool: &P, entity: &{struct_name}) -> Result<u64> {{
-Query::new("INSERT INTO `{table_name}` ({column_list}) VALUES ({placeholders}) ON DUPLICATE KEY UPDATE {update_clause}")
-{bind_fields}
+rdbi::BatchUpsert::new("{table_name}", std::slice::from_ref(entity))
+.update_columns(vec![{update_column_list}])
 .execute(pool).await.map(|r| r.rows_affected)
 }}
 "#,
         struct_name = struct_name,
         table_name = table.name,
-        column_list = column_list,
-        placeholders = placeholders,
-        update_clause = update_clause,
-        bind_fields = bind_fields,
+        update_column_list = update_column_list,
     )
 }
 
@@ -573,6 +577,7 @@ fn generate_update_methods(
     table: &TableMetadata,
     struct_name: &str,
     column_map: &HashMap<&str, &ColumnMetadata>,
+    types: &TypeMapping,
 ) -> String {
     let mut code = String::new();
 
@@ -602,7 +607,7 @@ fn generate_update_methods(
         .iter()
         .map(|c| {
             let field = escape_field_name(&c.name);
-            let rust_type = TypeResolver::resolve(c, &table.name);
+            let rust_type = TypeResolver::resolve_with(c, &table.name, types);
             if rust_type.is_copy() {
                 format!(".bind(entity.{})", field)
             } else {
@@ -612,7 +617,7 @@ fn generate_update_methods(
         .chain(pk.columns.iter().map(|c| {
             let field = escape_field_name(c);
             let col = column_map.get(c.as_str()).unwrap();
-            let rust_type = TypeResolver::resolve(col, &table.name);
+            let rust_type = TypeResolver::resolve_with(col, &table.name, types);
             if rust_type.is_copy() {
                 format!(".bind(entity.{})", field)
             } else {
@@ -643,6 +648,7 @@ Query::new("UPDATE `{table_name}` SET {set_clause} WHERE {where_clause}")
 fn generate_update_plain_method(
     table: &TableMetadata,
     column_map: &HashMap<&str, &ColumnMetadata>,
+    types: &TypeMapping,
 ) -> String {
     let pk = table.primary_key.as_ref().unwrap();
 
@@ -661,9 +667,9 @@ fn generate_update_plain_method(
     let method_name = generate_update_by_method_name(&pk.columns);
 
     // Build params: PK columns first, then update columns
-    let pk_params = build_params(&pk.columns, column_map, &table.name);
+    let pk_params = build_params(&pk.columns, column_map, &table.name, types);
     let update_column_names: Vec<String> = update_columns.iter().map(|c| c.name.clone()).collect();
-    let update_params = build_params(&update_column_names, column_map, &table.name);
+    let update_params = build_params(&update_column_names, column_map, &table.name, types);
     let all_params = format!("{}, {}", pk_params, update_params);
 
     let set_clause = update_columns
@@ -755,8 +761,9 @@ fn generate_find_by_method(
     column_map: &HashMap<&str, &ColumnMetadata>,
     struct_name: &str,
     select_columns: &str,
+    types: &TypeMapping,
 ) -> String {
-    let params = build_params(&sig.columns, column_map, &table.name);
+    let params = build_params(&sig.columns, column_map, &table.name, types);
 
     let (return_type, fetch_method) = if sig.is_unique {
         (format!("Option<{}>", struct_name), "fetch_optional")
@@ -897,6 +904,7 @@ fn generate_find_by_list_methods(
     column_map: &HashMap<&str, &ColumnMetadata>,
     struct_name: &str,
     select_columns: &str,
+    types: &TypeMapping,
 ) -> String {
     let mut code = String::new();
     let mut processed: HashSet<String> = HashSet::new();
@@ -911,6 +919,7 @@ fn generate_find_by_list_methods(
                 column_map,
                 struct_name,
                 select_columns,
+                types,
             ));
             processed.insert(col.clone());
         }
@@ -927,6 +936,7 @@ fn generate_find_by_list_methods(
                     column_map,
                     struct_name,
                     select_columns,
+                    types,
                 ));
                 processed.insert(col.clone());
             }
@@ -943,11 +953,12 @@ fn generate_single_find_by_list(
     column_map: &HashMap<&str, &ColumnMetadata>,
     struct_name: &str,
     select_columns: &str,
+    types: &TypeMapping,
 ) -> String {
     let method_name = generate_find_by_list_method_name(column_name);
     let param_name = pluralize(&escape_field_name(column_name));
     let column = column_map.get(column_name).unwrap();
-    let rust_type = TypeResolver::resolve(column, &table.name);
+    let rust_type = TypeResolver::resolve_with(column, &table.name, types);
 
     // Get the inner type (unwrap Option if nullable)
     let inner_type = rust_type.inner_type().to_type_string();
@@ -984,6 +995,7 @@ fn generate_composite_enum_list_methods(
     column_map: &HashMap<&str, &ColumnMetadata>,
     struct_name: &str,
     select_columns: &str,
+    types: &TypeMapping,
 ) -> String {
     let mut code = String::new();
 
@@ -1024,6 +1036,7 @@ fn generate_composite_enum_list_methods(
             column_map,
             struct_name,
             select_columns,
+            types,
         ));
     }
 
@@ -1038,6 +1051,7 @@ fn generate_composite_enum_list_method(
     column_map: &HashMap<&str, &ColumnMetadata>,
     struct_name: &str,
     select_columns: &str,
+    types: &TypeMapping,
 ) -> String {
     // Build method name: pluralize enum column names
     let method_name = generate_composite_enum_method_name(columns, enum_columns);
@@ -1047,7 +1061,7 @@ fn generate_composite_enum_list_method(
 
     for col_name in columns {
         let col = column_map.get(col_name.as_str()).unwrap();
-        let rust_type = TypeResolver::resolve(col, &table.name);
+        let rust_type = TypeResolver::resolve_with(col, &table.name, types);
         let is_enum = enum_columns.contains(col_name.as_str());
 
         if is_enum {
@@ -1235,6 +1249,7 @@ mod tests {
                     is_auto_increment: true,
                     is_unsigned: false,
                     enum_values: None,
+                    set_values: None,
                     comment: None,
                 },
                 ColumnMetadata {
@@ -1245,6 +1260,7 @@ mod tests {
                     is_auto_increment: false,
                     is_unsigned: false,
                     enum_values: None,
+                    set_values: None,
                     comment: None,
                 },
                 ColumnMetadata {
@@ -1255,6 +1271,7 @@ mod tests {
                     is_auto_increment: false,
                     is_unsigned: false,
                     enum_values: Some(vec!["ACTIVE".to_string(), "INACTIVE".to_string()]),
+                    set_values: None,
                     comment: None,
                 },
             ],
@@ -1323,13 +1340,12 @@ mod tests {
 
         // Should contain upsert function
         assert!(code.contains("pub async fn upsert"));
-        // Should contain ON DUPLICATE KEY UPDATE
-        assert!(code.contains("ON DUPLICATE KEY UPDATE"));
-        // Should NOT update the PK column (id)
-        assert!(!code.contains("`id` = VALUES(`id`)"));
-        // Should update non-PK columns
-        assert!(code.contains("`email` = VALUES(`email`)"));
-        assert!(code.contains("`status` = VALUES(`status`)"));
+        // Should delegate to BatchUpsert so the syntax follows the server version
+        assert!(code.contains("rdbi::BatchUpsert::new(\"users\", std::slice::from_ref(entity))"));
+        assert!(!code.contains("VALUES(`"));
+        // Should update non-PK columns only
+        assert!(code.contains(".update_columns(vec![\"email\", \"status\"])"));
+        assert!(!code.contains("\"id\""));
     }
 
     #[test]
@@ -1343,6 +1359,27 @@ mod tests {
         assert!(code.is_empty());
     }
 
+    #[test]
+    fn test_generate_find_all() {
+        let table = make_table();
+        let select_columns = build_select_columns(&table);
+        let code = generate_find_all(&table, "Users", &select_columns);
+
+        let (find_all, find_all_limited) = code.split_once("\n\n").unwrap();
+
+        // Goes through Query::fetch_all with the pool's max_rows in place
+        assert!(find_all.contains("pub async fn find_all<P: Pool>(pool: &P) -> Result<Vec<Users>>"));
+        assert!(find_all.contains("\n.fetch_all(pool).await\n"));
+        assert!(!find_all.contains(".unlimited_rows()"));
+        assert!(!find_all.contains(".max_rows("));
+
+        // The per-call limit is forwarded to the query
+        assert!(find_all_limited.contains(
+            "pub async fn find_all_limited<P: Pool>(pool: &P, max_rows: u64) -> Result<Vec<Users>>"
+        ));
+        assert!(find_all_limited.contains("\n.max_rows(max_rows)\n.fetch_all(pool).await\n"));
+    }
+
     #[test]
     fn test_generate_insert_all_method() {
         let table = make_table();
@@ -1,6 +1,6 @@
 //! Naming utilities for code generation
 
-use heck::{ToPascalCase, ToSnakeCase};
+use heck::{ToPascalCase, ToShoutySnakeCase, ToSnakeCase};
 
 /// Convert a table name to a struct name (PascalCase)
 pub fn to_struct_name(table_name: &str) -> String {
@@ -65,6 +65,21 @@ pub fn to_enum_variant(value: &str) -> String {
     value.to_pascal_case()
 }
 
+/// Convert a SET member to a flag constant name (SCREAMING_SNAKE_CASE)
+/// e.g., "free-shipping" -> "FREE_SHIPPING"
+///
+/// Members with no identifier characters become `EMPTY`, and a leading
+/// digit gets a `_` prefix, e.g. "10" -> "_10".
+pub fn to_set_flag(value: &str) -> String {
+    let value = value.trim_matches('\'').trim_matches('"');
+    let flag = value.to_shouty_snake_case();
+    match flag.chars().next() {
+        None => "EMPTY".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("_{}", flag),
+        Some(_) => flag,
+    }
+}
+
 /// Pluralize a word using English grammar rules
 pub fn pluralize(word: &str) -> String {
     if word.is_empty() {
@@ -245,6 +260,17 @@ mod tests {
         assert_eq!(to_enum_variant("PendingReview"), "PendingReview");
     }
 
+    #[test]
+    fn test_to_set_flag() {
+        assert_eq!(to_set_flag("A"), "A");
+        assert_eq!(to_set_flag("'sale'"), "SALE");
+        assert_eq!(to_set_flag("free-shipping"), "FREE_SHIPPING");
+        assert_eq!(to_set_flag("GiftWrap"), "GIFT_WRAP");
+        assert_eq!(to_set_flag("''"), "EMPTY");
+        assert_eq!(to_set_flag("--"), "EMPTY");
+        assert_eq!(to_set_flag("10"), "_10");
+    }
+
     #[test]
     fn test_pluralize() {
         // Basic -s
@@ -9,11 +9,14 @@ use tracing::debug;
 use crate::config::CodegenConfig;
 use crate::parser::{ColumnMetadata, TableMetadata};
 
-use super::naming::{escape_field_name, to_enum_name, to_enum_variant, to_struct_name};
-use super::type_resolver::TypeResolver;
+use super::naming::{
+    escape_field_name, to_enum_name, to_enum_variant, to_set_flag, to_struct_name,
+};
+use super::type_resolver::{TypeMapping, TypeResolver};
 
 /// Generate struct files for all tables
 pub fn generate_structs(tables: &[TableMetadata], config: &CodegenConfig) -> Result<()> {
+    let types = &TypeMapping::from_config(config);
     let output_dir = &config.output_structs_dir;
     fs::create_dir_all(output_dir)?;
 
@@ -37,7 +40,7 @@ pub fn generate_structs(tables: &[TableMetadata], config: &CodegenConfig) -> Res
 
     // Generate each struct file
     for table in tables {
-        generate_struct_file(table, output_dir)?;
+        generate_struct_file(table, output_dir, types)?;
     }
 
     Ok(())
@@ -78,7 +81,11 @@ Self { items, total_count, current_page, total_pages, page_size, has_next }
 }
 
 /// Generate a single struct file for a table
-fn generate_struct_file(table: &TableMetadata, output_dir: &Path) -> Result<()> {
+fn generate_struct_file(
+    table: &TableMetadata,
+    output_dir: &Path,
+    types: &TypeMapping,
+) -> Result<()> {
     let struct_name = to_struct_name(&table.name);
     let file_name = format!("{}.rs", heck::AsSnakeCase(&table.name));
     debug!("Generating struct {} -> {}", struct_name, file_name);
@@ -105,6 +112,14 @@ fn generate_struct_file(table: &TableMetadata, output_dir: &Path) -> Result<()>
         }
     }
 
+    // Then flags types for SET columns
+    for col in &table.columns {
+        if let Some(values) = &col.set_values {
+            code.push_str(&generate_set(&table.name, col, values));
+            code.push('\n');
+        }
+    }
+
     // Generate struct documentation
     code.push_str(&format!("/// Database table: `{}`\n", table.name));
     if let Some(comment) = &table.comment {
@@ -120,7 +135,7 @@ fn generate_struct_file(table: &TableMetadata, output_dir: &Path) -> Result<()>
     // Generate fields
     for col in &table.columns {
         let field_name = escape_field_name(&col.name);
-        let rust_type = TypeResolver::resolve(col, &table.name);
+        let rust_type = TypeResolver::resolve_with(col, &table.name, types);
 
         // Add field documentation
         code.push_str(&format!("/// Column: `{}`", col.name));
@@ -268,6 +283,128 @@ fn generate_enum(table_name: &str, column: &ColumnMetadata, values: &[String]) -
     code
 }
 
+/// Generate a flags type for a SET column
+///
+/// Each member becomes an associated constant holding its bit (MySQL stores
+/// SET values as a bitmask in member order). The value is read and written as
+/// the comma-separated member list, and serialized the same way.
+fn generate_set(table_name: &str, column: &ColumnMetadata, values: &[String]) -> String {
+    let type_name = to_enum_name(table_name, &column.name);
+    let mut code = String::new();
+
+    // MEMBERS is the lookup table declared next to the flags
+    let mut used_flags: HashSet<String> = HashSet::from(["MEMBERS".to_string()]);
+    let mut flag_mappings: Vec<(String, String)> = Vec::new();
+    for value in values {
+        let flag = to_set_flag(value);
+        let final_flag = if used_flags.contains(&flag) {
+            let mut counter = 2;
+            loop {
+                let new_flag = format!("{}_{}", flag, counter);
+                if !used_flags.contains(&new_flag) {
+                    break new_flag;
+                }
+                counter += 1;
+            }
+        } else {
+            flag
+        };
+        used_flags.insert(final_flag.clone());
+        let clean_value = value.trim_matches('\'').trim_matches('"');
+        flag_mappings.push((final_flag, clean_value.to_string()));
+    }
+
+    code.push_str(&format!("/// Set for `{}.{}`\n", table_name, column.name));
+    code.push_str(
+        "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]\n",
+    );
+    code.push_str("#[serde(into = \"String\", try_from = \"String\")]\n");
+    code.push_str(&format!("pub struct {}(u64);\n\n", type_name));
+
+    // Flags and set operations
+    code.push_str(&format!("impl {} {{\n", type_name));
+    for (bit, (flag, _)) in flag_mappings.iter().enumerate() {
+        code.push_str(&format!("pub const {}: Self = Self(1 << {});\n", flag, bit));
+    }
+    code.push_str(&format!(
+        "const MEMBERS: [(&'static str, Self); {}] = [",
+        flag_mappings.len()
+    ));
+    for (flag, db_value) in &flag_mappings {
+        code.push_str(&format!("(\"{}\", Self::{}), ", db_value, flag));
+    }
+    code.push_str("];\n\n");
+    code.push_str("/// The empty set\n");
+    code.push_str("pub const fn empty() -> Self {\nSelf(0)\n}\n\n");
+    code.push_str("/// The bitmask MySQL stores for this set\n");
+    code.push_str("pub const fn bits(&self) -> u64 {\nself.0\n}\n\n");
+    code.push_str("pub const fn is_empty(&self) -> bool {\nself.0 == 0\n}\n\n");
+    code.push_str(
+        "pub const fn contains(&self, other: Self) -> bool {\nself.0 & other.0 == other.0\n}\n\n",
+    );
+    code.push_str("pub fn insert(&mut self, other: Self) {\nself.0 |= other.0;\n}\n\n");
+    code.push_str("pub fn remove(&mut self, other: Self) {\nself.0 &= !other.0;\n}\n");
+    code.push_str("}\n\n");
+
+    code.push_str(&format!("impl std::ops::BitOr for {} {{\n", type_name));
+    code.push_str("type Output = Self;\n");
+    code.push_str("fn bitor(self, rhs: Self) -> Self {\nSelf(self.0 | rhs.0)\n}\n}\n\n");
+    code.push_str(&format!(
+        "impl std::ops::BitOrAssign for {} {{\n",
+        type_name
+    ));
+    code.push_str("fn bitor_assign(&mut self, rhs: Self) {\nself.0 |= rhs.0;\n}\n}\n\n");
+
+    // Text form: comma-separated members, in declaration order
+    code.push_str(&format!("impl std::fmt::Display for {} {{\n", type_name));
+    code.push_str("fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n");
+    code.push_str("let mut first = true;\n");
+    code.push_str("for (name, flag) in Self::MEMBERS {\n");
+    code.push_str("if self.contains(flag) {\n");
+    code.push_str("if !first {\nf.write_str(\",\")?;\n}\n");
+    code.push_str("f.write_str(name)?;\nfirst = false;\n}\n}\nOk(())\n}\n}\n\n");
+
+    code.push_str(&format!("impl std::str::FromStr for {} {{\n", type_name));
+    code.push_str("type Err = rdbi::Error;\n");
+    code.push_str("fn from_str(s: &str) -> rdbi::Result<Self> {\n");
+    code.push_str("let mut set = Self::empty();\n");
+    code.push_str("for member in s.split(',').filter(|m| !m.is_empty()) {\n");
+    code.push_str("match Self::MEMBERS.iter().find(|(name, _)| *name == member) {\n");
+    code.push_str("Some((_, flag)) => set.insert(*flag),\n");
+    code.push_str(&format!(
+        "None => return Err(rdbi::Error::TypeConversion {{ expected: \"{}\", actual: member.to_string() }}),\n",
+        type_name
+    ));
+    code.push_str("}\n}\nOk(set)\n}\n}\n\n");
+
+    code.push_str(&format!("impl From<{}> for String {{\n", type_name));
+    code.push_str(&format!(
+        "fn from(set: {}) -> Self {{\nset.to_string()\n}}\n}}\n\n",
+        type_name
+    ));
+    code.push_str(&format!("impl TryFrom<String> for {} {{\n", type_name));
+    code.push_str("type Error = rdbi::Error;\n");
+    code.push_str("fn try_from(s: String) -> rdbi::Result<Self> {\ns.parse()\n}\n}\n\n");
+
+    // FromValue implementation
+    code.push_str(&format!("impl rdbi::FromValue for {} {{\n", type_name));
+    code.push_str("fn from_value(value: rdbi::Value) -> rdbi::Result<Self> {\n");
+    code.push_str("match value {\n");
+    code.push_str("rdbi::Value::String(s) => s.parse(),\n");
+    code.push_str(&format!(
+        "_ => Err(rdbi::Error::TypeConversion {{ expected: \"{}\", actual: value.type_name().to_string() }}),\n",
+        type_name
+    ));
+    code.push_str("}\n}\n}\n\n");
+
+    // ToValue implementation
+    code.push_str(&format!("impl rdbi::ToValue for {} {{\n", type_name));
+    code.push_str("fn to_value(&self) -> rdbi::Value {\n");
+    code.push_str("rdbi::Value::String(self.to_string())\n}\n}\n");
+
+    code
+}
+
 /// Get index information for a column
 fn get_index_info(table: &TableMetadata, column_name: &str) -> Vec<String> {
     let mut info = Vec::new();
@@ -312,6 +449,7 @@ mod tests {
                     is_auto_increment: true,
                     is_unsigned: false,
                     enum_values: None,
+                    set_values: None,
                     comment: None,
                 },
                 ColumnMetadata {
@@ -322,6 +460,7 @@ mod tests {
                     is_auto_increment: false,
                     is_unsigned: false,
                     enum_values: None,
+                    set_values: None,
                     comment: None,
                 },
                 ColumnMetadata {
@@ -336,6 +475,7 @@ mod tests {
                         "INACTIVE".to_string(),
                         "PENDING".to_string(),
                     ]),
+                    set_values: None,
                     comment: None,
                 },
             ],
@@ -371,6 +511,7 @@ mod tests {
             is_auto_increment: false,
             is_unsigned: false,
             enum_values: Some(vec!["ACTIVE".to_string(), "INACTIVE".to_string()]),
+            set_values: None,
             comment: None,
         };
 
@@ -382,4 +523,54 @@ mod tests {
         assert!(code.contains("impl rdbi::FromValue for UsersStatus"));
         assert!(code.contains("impl rdbi::ToValue for UsersStatus"));
     }
+
+    #[test]
+    fn test_generate_set() {
+        let col = ColumnMetadata {
+            name: "tags".to_string(),
+            data_type: "SET".to_string(),
+            nullable: true,
+            default_value: None,
+            is_auto_increment: false,
+            is_unsigned: false,
+            enum_values: None,
+            set_values: Some(vec!["new".to_string(), "on-sale".to_string()]),
+            comment: None,
+        };
+
+        let code = generate_set("items", &col, col.set_values.as_ref().unwrap());
+        assert!(code.contains("pub struct ItemsTags(u64);"));
+        assert!(code.contains("pub const NEW: Self = Self(1 << 0);"));
+        assert!(code.contains("pub const ON_SALE: Self = Self(1 << 1);"));
+        assert!(code.contains("(\"on-sale\", Self::ON_SALE)"));
+        assert!(code.contains("impl rdbi::FromValue for ItemsTags"));
+        assert!(code.contains("impl rdbi::ToValue for ItemsTags"));
+    }
+
+    #[test]
+    fn test_generate_set_reserved_and_non_identifier_members() {
+        let col = ColumnMetadata {
+            name: "tags".to_string(),
+            data_type: "SET".to_string(),
+            nullable: true,
+            default_value: None,
+            is_auto_increment: false,
+            is_unsigned: false,
+            enum_values: None,
+            set_values: Some(vec![
+                "members".to_string(),
+                "".to_string(),
+                "10".to_string(),
+            ]),
+            comment: None,
+        };
+
+        let code = generate_set("items", &col, col.set_values.as_ref().unwrap());
+        assert!(code.contains("pub const MEMBERS_2: Self = Self(1 << 0);"));
+        assert!(code.contains("pub const EMPTY: Self = Self(1 << 1);"));
+        assert!(code.contains("pub const _10: Self = Self(1 << 2);"));
+        assert!(code.contains("(\"members\", Self::MEMBERS_2)"));
+        assert!(code.contains("(\"\", Self::EMPTY)"));
+        assert_eq!(code.matches("const MEMBERS:").count(), 1);
+    }
 }
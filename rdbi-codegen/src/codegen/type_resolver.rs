@@ -1,6 +1,9 @@
 //! MySQL to Rust type mapping
 
+use std::collections::BTreeMap;
+
 use super::naming;
+use crate::config::{CodegenConfig, TimeLibrary};
 use crate::parser::ColumnMetadata;
 
 /// Represents a Rust type for code generation
@@ -23,9 +26,27 @@ pub enum RustType {
     NaiveDate,
     NaiveDateTime,
     NaiveTime,
+    /// UTC timestamp (`chrono::DateTime<chrono::Utc>`)
+    DateTimeUtc,
+    /// `time::Date`
+    TimeDate,
+    /// `time::PrimitiveDateTime`
+    TimePrimitiveDateTime,
+    /// `time::Time`
+    TimeTime,
+    /// `time::OffsetDateTime`
+    TimeOffsetDateTime,
     Json,
+    /// UUID stored as `BINARY(16)` (`uuid::Uuid`)
+    Uuid,
+    /// JSON decoded into a user type (`rdbi::Json<T>`), with the type path
+    TypedJson(String),
+    /// Spatial value (`geo_types::<kind><f64>`), with the geo-types kind, e.g. `Point`
+    Geo(String),
     /// Custom enum type with the enum name
     Enum(String),
+    /// Flags type for a SET column, with the type name
+    Set(String),
     /// Optional wrapper
     Option(Box<RustType>),
 }
@@ -51,20 +72,36 @@ impl RustType {
             RustType::NaiveDate => "chrono::NaiveDate".to_string(),
             RustType::NaiveDateTime => "chrono::NaiveDateTime".to_string(),
             RustType::NaiveTime => "chrono::NaiveTime".to_string(),
+            RustType::DateTimeUtc => "chrono::DateTime<chrono::Utc>".to_string(),
+            RustType::TimeDate => "time::Date".to_string(),
+            RustType::TimePrimitiveDateTime => "time::PrimitiveDateTime".to_string(),
+            RustType::TimeTime => "time::Time".to_string(),
+            RustType::TimeOffsetDateTime => "time::OffsetDateTime".to_string(),
             RustType::Json => "serde_json::Value".to_string(),
-            RustType::Enum(name) => name.clone(),
+            RustType::Uuid => "uuid::Uuid".to_string(),
+            RustType::TypedJson(path) => format!("rdbi::Json<{}>", path),
+            RustType::Geo(kind) => format!("geo_types::{}<f64>", kind),
+            RustType::Enum(name) | RustType::Set(name) => name.clone(),
             RustType::Option(inner) => format!("Option<{}>", inner.to_type_string()),
         }
     }
 
-    /// Get the type string for function parameters (use references for strings)
+    /// Get the type string for function parameters (use references for strings and UUIDs)
     pub fn to_param_type_string(&self) -> String {
         match self {
             RustType::String => "&str".to_string(),
             RustType::Bytes => "&[u8]".to_string(),
+            RustType::Uuid => "&uuid::Uuid".to_string(),
+            RustType::TypedJson(_) => format!("&{}", self.to_type_string()),
+            RustType::Geo(_) if !self.is_copy() => format!("&{}", self.to_type_string()),
             RustType::Option(inner) => match inner.as_ref() {
                 RustType::String => "Option<&str>".to_string(),
                 RustType::Bytes => "Option<&[u8]>".to_string(),
+                RustType::Uuid => "Option<&uuid::Uuid>".to_string(),
+                RustType::TypedJson(_) => format!("Option<&{}>", inner.to_type_string()),
+                RustType::Geo(_) if !inner.is_copy() => {
+                    format!("Option<&{}>", inner.to_type_string())
+                }
                 _ => format!("Option<{}>", inner.to_type_string()),
             },
             _ => self.to_type_string(),
@@ -74,7 +111,10 @@ impl RustType {
     /// Check if this type needs the chrono crate
     pub fn needs_chrono(&self) -> bool {
         match self {
-            RustType::NaiveDate | RustType::NaiveDateTime | RustType::NaiveTime => true,
+            RustType::NaiveDate
+            | RustType::NaiveDateTime
+            | RustType::NaiveTime
+            | RustType::DateTimeUtc => true,
             RustType::Option(inner) => inner.needs_chrono(),
             _ => false,
         }
@@ -113,24 +153,106 @@ impl RustType {
 
     /// Check if this type implements Copy
     ///
-    /// Only String, Bytes (Vec<u8>), and Json (serde_json::Value) are non-Copy.
-    /// All other types (primitives, enums, chrono dates, Decimal) implement Copy.
+    /// Only String, Bytes (Vec<u8>), Json (serde_json::Value), typed JSON and
+    /// geometries other than points are non-Copy. All other types (primitives,
+    /// enums, dates, Decimal, Uuid) implement Copy.
     pub fn is_copy(&self) -> bool {
         match self {
-            RustType::String | RustType::Bytes | RustType::Json => false,
+            RustType::String | RustType::Bytes | RustType::Json | RustType::TypedJson(_) => false,
+            RustType::Geo(kind) => kind == "Point",
             RustType::Option(inner) => inner.is_copy(),
             _ => true,
         }
     }
 }
 
+/// Configurable type mapping options
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TypeMapping {
+    /// Map TIMESTAMP columns to `chrono::DateTime<chrono::Utc>` instead of `NaiveDateTime`
+    pub timestamp_utc: bool,
+    /// Date/time library for DATE, DATETIME, TIMESTAMP and TIME columns
+    pub time_library: TimeLibrary,
+    /// `BINARY(16)` columns to map to `uuid::Uuid`, as `column` or `table.column`
+    /// patterns where `*` matches any run of characters (e.g. `*_uuid`)
+    pub uuid_columns: Vec<String>,
+    /// JSON columns to decode into a user type, keyed by `table.column`
+    pub json_types: BTreeMap<String, String>,
+    /// Map spatial columns to `geo-types` geometries instead of `Vec<u8>`
+    pub geo_types: bool,
+}
+
+impl TypeMapping {
+    /// Build the type mapping from the codegen configuration
+    pub fn from_config(config: &CodegenConfig) -> Self {
+        Self {
+            timestamp_utc: config.timestamp_utc,
+            time_library: config.time_library,
+            uuid_columns: split_list(&config.uuid_columns),
+            json_types: config.json_types.clone(),
+            geo_types: config.geo_types,
+        }
+    }
+
+    /// Check whether a column is configured as a UUID column
+    fn is_uuid_column(&self, table_name: &str, column_name: &str) -> bool {
+        self.uuid_columns
+            .iter()
+            .any(|pattern| match pattern.split_once('.') {
+                Some((table, column)) => {
+                    glob_match(table, table_name) && glob_match(column, column_name)
+                }
+                None => glob_match(pattern, column_name),
+            })
+    }
+}
+
+/// Split a comma-separated config list, dropping empty entries
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Match a name against a pattern where `*` matches any run of characters
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*` in the pattern
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
 /// Resolve MySQL data types to Rust types
 pub struct TypeResolver;
 
 impl TypeResolver {
-    /// Get the Rust type for a column
+    /// Get the Rust type for a column using the default type mapping
     pub fn resolve(column: &ColumnMetadata, table_name: &str) -> RustType {
-        let base_type = Self::resolve_base_type(column, table_name);
+        Self::resolve_with(column, table_name, &TypeMapping::default())
+    }
+
+    /// Get the Rust type for a column using the given type mapping
+    pub fn resolve_with(
+        column: &ColumnMetadata,
+        table_name: &str,
+        types: &TypeMapping,
+    ) -> RustType {
+        let base_type = Self::resolve_base_type(column, table_name, types);
 
         if column.nullable {
             RustType::Option(Box::new(base_type))
@@ -140,7 +262,11 @@ impl TypeResolver {
     }
 
     /// Resolve the base type (without Option wrapper)
-    fn resolve_base_type(column: &ColumnMetadata, table_name: &str) -> RustType {
+    fn resolve_base_type(
+        column: &ColumnMetadata,
+        table_name: &str,
+        types: &TypeMapping,
+    ) -> RustType {
         let data_type = column.data_type.to_uppercase();
 
         // Check for enum first
@@ -149,6 +275,11 @@ impl TypeResolver {
             return RustType::Enum(enum_name);
         }
 
+        // SET columns get a flags type, named like enums
+        if column.is_set() {
+            return RustType::Set(naming::to_enum_name(table_name, &column.name));
+        }
+
         // Parse the data type
         let data_type_lower = data_type.to_lowercase();
 
@@ -210,46 +341,113 @@ impl TypeResolver {
             return RustType::String;
         }
 
+        // UUID columns
+        if data_type_lower.replace(' ', "") == "binary(16)"
+            && types.is_uuid_column(table_name, &column.name)
+        {
+            return RustType::Uuid;
+        }
+
+        // BIT(n) -> smallest unsigned integer holding n bits
+        if let Some(width) = Self::bit_width(&data_type_lower) {
+            return match width {
+                0..=8 => RustType::U8,
+                9..=16 => RustType::U16,
+                17..=32 => RustType::U32,
+                _ => RustType::U64,
+            };
+        }
+
         // Binary types
         if data_type_lower.starts_with("binary")
             || data_type_lower.starts_with("varbinary")
             || data_type_lower.contains("blob")
-            || (data_type_lower.starts_with("bit") && !Self::is_bit_1(&data_type_lower))
         {
             return RustType::Bytes;
         }
 
         // Date/time types
-        if data_type_lower == "date" {
-            return RustType::NaiveDate;
-        }
-        if data_type_lower.starts_with("datetime") || data_type_lower.starts_with("timestamp") {
-            return RustType::NaiveDateTime;
-        }
-        if data_type_lower == "time" {
-            return RustType::NaiveTime;
+        if let Some(rust_type) = Self::resolve_temporal(&data_type_lower, types) {
+            return rust_type;
         }
 
         // JSON type
         if data_type_lower == "json" {
-            return RustType::Json;
+            let key = format!("{}.{}", table_name, column.name);
+            return match types.json_types.get(&key) {
+                Some(path) => RustType::TypedJson(path.clone()),
+                None => RustType::Json,
+            };
         }
 
-        // Spatial types -> bytes
-        if data_type_lower.starts_with("geometry")
-            || data_type_lower.starts_with("point")
-            || data_type_lower.starts_with("linestring")
-            || data_type_lower.starts_with("polygon")
-            || data_type_lower.starts_with("multi")
-            || data_type_lower.starts_with("geometrycollection")
-        {
-            return RustType::Bytes;
+        // Spatial types -> geo-types if enabled, bytes otherwise
+        if let Some(kind) = Self::geo_kind(&data_type_lower) {
+            return if types.geo_types {
+                RustType::Geo(kind.to_string())
+            } else {
+                RustType::Bytes
+            };
         }
 
         // Default fallback
         RustType::String
     }
 
+    /// Resolve date/time types for the configured time library
+    fn resolve_temporal(data_type_lower: &str, types: &TypeMapping) -> Option<RustType> {
+        let chrono = types.time_library == TimeLibrary::Chrono;
+        let rust_type = if data_type_lower == "date" {
+            if chrono {
+                RustType::NaiveDate
+            } else {
+                RustType::TimeDate
+            }
+        } else if data_type_lower.starts_with("timestamp") && types.timestamp_utc {
+            if chrono {
+                RustType::DateTimeUtc
+            } else {
+                RustType::TimeOffsetDateTime
+            }
+        } else if data_type_lower.starts_with("datetime")
+            || data_type_lower.starts_with("timestamp")
+        {
+            if chrono {
+                RustType::NaiveDateTime
+            } else {
+                RustType::TimePrimitiveDateTime
+            }
+        } else if data_type_lower == "time" {
+            if chrono {
+                RustType::NaiveTime
+            } else {
+                RustType::TimeTime
+            }
+        } else {
+            return None;
+        };
+        Some(rust_type)
+    }
+
+    /// Get the geo-types kind for a spatial data type
+    fn geo_kind(data_type_lower: &str) -> Option<&'static str> {
+        // Longest names first, since they share prefixes
+        let kinds = [
+            ("geometrycollection", "GeometryCollection"),
+            ("geomcollection", "GeometryCollection"),
+            ("multilinestring", "MultiLineString"),
+            ("multipolygon", "MultiPolygon"),
+            ("multipoint", "MultiPoint"),
+            ("linestring", "LineString"),
+            ("polygon", "Polygon"),
+            ("geometry", "Geometry"),
+            ("point", "Point"),
+        ];
+        kinds
+            .iter()
+            .find(|(sql, _)| data_type_lower.starts_with(sql))
+            .map(|(_, kind)| *kind)
+    }
+
     /// Check if the type represents a boolean
     fn is_boolean_type(data_type_lower: &str, original: &str) -> bool {
         // BOOL or BOOLEAN
@@ -266,16 +464,24 @@ impl TypeResolver {
         }
 
         // BIT(1)
-        if Self::is_bit_1(data_type_lower) {
+        if Self::bit_width(data_type_lower) == Some(1) {
             return true;
         }
 
         false
     }
 
-    /// Check if this is a BIT(1) type
-    fn is_bit_1(data_type_lower: &str) -> bool {
-        data_type_lower.starts_with("bit") && data_type_lower.contains("(1)")
+    /// Get the width of a BIT(n) type (a bare `BIT` is `BIT(1)`)
+    fn bit_width(data_type_lower: &str) -> Option<u32> {
+        let rest = data_type_lower.strip_prefix("bit")?.trim();
+        if rest.is_empty() {
+            return Some(1);
+        }
+        rest.strip_prefix('(')?
+            .strip_suffix(')')?
+            .trim()
+            .parse()
+            .ok()
     }
 }
 
@@ -292,6 +498,7 @@ mod tests {
             is_auto_increment: false,
             is_unsigned: unsigned,
             enum_values: None,
+            set_values: None,
             comment: None,
         }
     }
@@ -315,6 +522,35 @@ mod tests {
 
         let col = make_column("flag", "BOOL", false, false);
         assert_eq!(TypeResolver::resolve(&col, "users"), RustType::Bool);
+
+        let col = make_column("flag", "BIT(1)", false, false);
+        assert_eq!(TypeResolver::resolve(&col, "users"), RustType::Bool);
+    }
+
+    #[test]
+    fn test_bit_types() {
+        let resolve = |data_type: &str| {
+            TypeResolver::resolve(&make_column("bits", data_type, false, false), "t")
+        };
+        assert_eq!(resolve("BIT"), RustType::Bool);
+        assert_eq!(resolve("BIT(2)"), RustType::U8);
+        assert_eq!(resolve("BIT(8)"), RustType::U8);
+        assert_eq!(resolve("BIT(10)"), RustType::U16);
+        assert_eq!(resolve("BIT(32)"), RustType::U32);
+        assert_eq!(resolve("BIT(64)"), RustType::U64);
+    }
+
+    #[test]
+    fn test_set_type() {
+        let mut col = make_column("tags", "SET('NEW', 'SALE')", true, false);
+        col.set_values = Some(vec!["NEW".to_string(), "SALE".to_string()]);
+        let rust_type = TypeResolver::resolve(&col, "items");
+        assert_eq!(
+            rust_type,
+            RustType::Option(Box::new(RustType::Set("ItemsTags".to_string())))
+        );
+        assert_eq!(rust_type.to_param_type_string(), "Option<ItemsTags>");
+        assert!(rust_type.is_copy());
     }
 
     #[test]
@@ -341,6 +577,165 @@ mod tests {
         assert_eq!(TypeResolver::resolve(&col, "users"), RustType::NaiveDate);
     }
 
+    #[test]
+    fn test_timestamp_utc_mapping() {
+        let types = TypeMapping {
+            timestamp_utc: true,
+            ..Default::default()
+        };
+        let col = make_column("created_at", "TIMESTAMP", false, false);
+        assert_eq!(
+            TypeResolver::resolve_with(&col, "t", &types),
+            RustType::DateTimeUtc
+        );
+        assert_eq!(
+            RustType::DateTimeUtc.to_type_string(),
+            "chrono::DateTime<chrono::Utc>"
+        );
+        assert!(RustType::DateTimeUtc.needs_chrono());
+
+        // DATETIME is unaffected, and TIMESTAMP stays naive by default
+        let col = make_column("event_time", "DATETIME", false, false);
+        assert_eq!(
+            TypeResolver::resolve_with(&col, "t", &types),
+            RustType::NaiveDateTime
+        );
+        let col = make_column("created_at", "TIMESTAMP", false, false);
+        assert_eq!(TypeResolver::resolve(&col, "t"), RustType::NaiveDateTime);
+    }
+
+    #[test]
+    fn test_time_library_mapping() {
+        let types = TypeMapping {
+            time_library: TimeLibrary::Time,
+            ..Default::default()
+        };
+        let resolve = |data_type: &str| {
+            TypeResolver::resolve_with(&make_column("c", data_type, false, false), "t", &types)
+        };
+        assert_eq!(resolve("DATE"), RustType::TimeDate);
+        assert_eq!(resolve("DATETIME"), RustType::TimePrimitiveDateTime);
+        assert_eq!(resolve("TIMESTAMP"), RustType::TimePrimitiveDateTime);
+        assert_eq!(resolve("TIME"), RustType::TimeTime);
+        assert_eq!(
+            RustType::TimePrimitiveDateTime.to_type_string(),
+            "time::PrimitiveDateTime"
+        );
+        assert!(!RustType::TimeDate.needs_chrono());
+
+        let types = TypeMapping {
+            timestamp_utc: true,
+            time_library: TimeLibrary::Time,
+            ..Default::default()
+        };
+        let col = make_column("created_at", "TIMESTAMP", true, false);
+        assert_eq!(
+            TypeResolver::resolve_with(&col, "t", &types),
+            RustType::Option(Box::new(RustType::TimeOffsetDateTime))
+        );
+    }
+
+    #[test]
+    fn test_typed_json_mapping() {
+        let types = TypeMapping {
+            json_types: BTreeMap::from([(
+                "users.settings".to_string(),
+                "crate::types::Settings".to_string(),
+            )]),
+            ..Default::default()
+        };
+        let col = make_column("settings", "JSON", true, false);
+        let rust_type = TypeResolver::resolve_with(&col, "users", &types);
+        assert_eq!(
+            rust_type.to_type_string(),
+            "Option<rdbi::Json<crate::types::Settings>>"
+        );
+        assert_eq!(
+            rust_type.to_param_type_string(),
+            "Option<&rdbi::Json<crate::types::Settings>>"
+        );
+        assert!(!rust_type.is_copy());
+        assert!(!rust_type.needs_serde_json());
+
+        // Other JSON columns keep serde_json::Value
+        assert_eq!(
+            TypeResolver::resolve_with(&col, "orders", &types),
+            RustType::Option(Box::new(RustType::Json))
+        );
+    }
+
+    #[test]
+    fn test_geo_types_mapping() {
+        let types = TypeMapping {
+            geo_types: true,
+            ..Default::default()
+        };
+        let resolve = |data_type: &str| {
+            TypeResolver::resolve_with(&make_column("c", data_type, false, false), "t", &types)
+        };
+        assert_eq!(resolve("POINT"), RustType::Geo("Point".to_string()));
+        assert_eq!(
+            resolve("MULTIPOINT"),
+            RustType::Geo("MultiPoint".to_string())
+        );
+        assert_eq!(
+            resolve("GEOMETRYCOLLECTION"),
+            RustType::Geo("GeometryCollection".to_string())
+        );
+        assert_eq!(resolve("GEOMETRY"), RustType::Geo("Geometry".to_string()));
+
+        let point = RustType::Geo("Point".to_string());
+        assert_eq!(point.to_type_string(), "geo_types::Point<f64>");
+        assert_eq!(point.to_param_type_string(), "geo_types::Point<f64>");
+        let polygon = RustType::Option(Box::new(RustType::Geo("Polygon".to_string())));
+        assert_eq!(
+            polygon.to_param_type_string(),
+            "Option<&geo_types::Polygon<f64>>"
+        );
+        assert!(!polygon.is_copy());
+
+        // Spatial columns stay bytes unless enabled
+        let col = make_column("c", "POLYGON", false, false);
+        assert_eq!(TypeResolver::resolve(&col, "t"), RustType::Bytes);
+    }
+
+    #[test]
+    fn test_uuid_mapping() {
+        let types = TypeMapping {
+            uuid_columns: vec!["*_uuid".to_string(), "orders.ref".to_string()],
+            ..Default::default()
+        };
+        let col = make_column("external_uuid", "BINARY(16)", false, false);
+        assert_eq!(
+            TypeResolver::resolve_with(&col, "users", &types),
+            RustType::Uuid
+        );
+        let col = make_column("ref", "BINARY(16)", true, false);
+        assert_eq!(
+            TypeResolver::resolve_with(&col, "orders", &types),
+            RustType::Option(Box::new(RustType::Uuid))
+        );
+        assert_eq!(RustType::Uuid.to_param_type_string(), "&uuid::Uuid");
+        assert_eq!(
+            RustType::Option(Box::new(RustType::Uuid)).to_param_type_string(),
+            "Option<&uuid::Uuid>"
+        );
+
+        // Only BINARY(16) columns that match a pattern are mapped
+        let col = make_column("ref", "BINARY(16)", false, false);
+        assert_eq!(
+            TypeResolver::resolve_with(&col, "users", &types),
+            RustType::Bytes
+        );
+        let col = make_column("legacy_uuid", "CHAR(36)", false, false);
+        assert_eq!(
+            TypeResolver::resolve_with(&col, "users", &types),
+            RustType::String
+        );
+        let col = make_column("external_uuid", "BINARY(16)", false, false);
+        assert_eq!(TypeResolver::resolve(&col, "users"), RustType::Bytes);
+    }
+
     #[test]
     fn test_enum_type() {
         let mut col = make_column("status", "ENUM", false, false);
@@ -1,5 +1,7 @@
 //! Default configuration values - single source of truth
 
+use super::settings::TimeLibrary;
+
 /// Default include tables pattern (all tables)
 pub const INCLUDE_TABLES: &str = "*";
 
@@ -26,3 +28,15 @@ pub const DAO_MODULE: &str = "dao";
 
 /// Whether to run in dry-run mode by default
 pub const DRY_RUN: bool = false;
+
+/// Whether TIMESTAMP columns map to `chrono::DateTime<Utc>` by default
+pub const TIMESTAMP_UTC: bool = false;
+
+/// Default date/time library for generated code
+pub const TIME_LIBRARY: TimeLibrary = TimeLibrary::Chrono;
+
+/// Whether spatial columns map to `geo-types` geometries by default
+pub const GEO_TYPES: bool = false;
+
+/// Default UUID column patterns (none)
+pub const UUID_COLUMNS: &str = "";
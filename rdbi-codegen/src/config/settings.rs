@@ -2,6 +2,7 @@
 
 use config::{Config, Environment, File};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use super::defaults;
@@ -50,12 +51,46 @@ pub struct CodegenConfig {
     #[serde(default = "default_dry_run")]
     pub dry_run: bool,
 
+    /// Map TIMESTAMP columns to `chrono::DateTime<Utc>` instead of `NaiveDateTime`
+    #[serde(default = "default_timestamp_utc")]
+    pub timestamp_utc: bool,
+
+    /// Date/time library used for generated fields and parameters
+    #[serde(default = "default_time_library")]
+    pub time_library: TimeLibrary,
+
+    /// Map spatial columns (GEOMETRY, POINT, POLYGON, ...) to `geo-types` geometries
+    #[serde(default = "default_geo_types")]
+    pub geo_types: bool,
+
+    /// `BINARY(16)` columns to map to `uuid::Uuid` (comma-separated
+    /// `column` or `table.column` patterns, `*` as wildcard, e.g. "*_uuid")
+    #[serde(default = "default_uuid_columns")]
+    pub uuid_columns: String,
+
+    /// JSON columns to decode into a user type as `rdbi::Json<T>`,
+    /// keyed by `table.column` (e.g. "users.settings" = "crate::types::Settings")
+    #[serde(default)]
+    pub json_types: BTreeMap<String, String>,
+
     /// Log level (trace, debug, info, warn, error)
     /// Can be overridden by RUST_LOG env var
     #[serde(default)]
     pub log_level: Option<String>,
 }
 
+/// Date/time library used in generated code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeLibrary {
+    /// `chrono::NaiveDate`, `NaiveDateTime`, `NaiveTime` and `DateTime<Utc>`
+    #[default]
+    Chrono,
+    /// `time::Date`, `PrimitiveDateTime`, `Time` and `OffsetDateTime`
+    /// (requires rdbi's `time` feature)
+    Time,
+}
+
 // Default value functions for serde
 fn default_include_tables() -> String {
     defaults::INCLUDE_TABLES.to_string()
@@ -84,6 +119,18 @@ fn default_dao_module() -> String {
 fn default_dry_run() -> bool {
     defaults::DRY_RUN
 }
+fn default_timestamp_utc() -> bool {
+    defaults::TIMESTAMP_UTC
+}
+fn default_time_library() -> TimeLibrary {
+    defaults::TIME_LIBRARY
+}
+fn default_geo_types() -> bool {
+    defaults::GEO_TYPES
+}
+fn default_uuid_columns() -> String {
+    defaults::UUID_COLUMNS.to_string()
+}
 
 impl Default for CodegenConfig {
     fn default() -> Self {
@@ -98,6 +145,11 @@ impl Default for CodegenConfig {
             models_module: default_models_module(),
             dao_module: default_dao_module(),
             dry_run: default_dry_run(),
+            timestamp_utc: default_timestamp_utc(),
+            time_library: default_time_library(),
+            geo_types: default_geo_types(),
+            uuid_columns: default_uuid_columns(),
+            json_types: BTreeMap::new(),
             log_level: None,
         }
     }
@@ -211,4 +263,45 @@ mod tests {
         let config: CodegenConfig = toml::from_str(toml_content).unwrap();
         assert_eq!(config.log_level, Some("debug".to_string()));
     }
+
+    #[test]
+    fn test_config_with_timestamp_utc() {
+        let config = CodegenConfig::default();
+        assert!(!config.timestamp_utc);
+
+        let toml_content = r#"
+            schema_file = "test.sql"
+            timestamp_utc = true
+        "#;
+        let config: CodegenConfig = toml::from_str(toml_content).unwrap();
+        assert!(config.timestamp_utc);
+    }
+
+    #[test]
+    fn test_config_with_json_types() {
+        let toml_content = r#"
+            schema_file = "test.sql"
+
+            [json_types]
+            "users.settings" = "crate::types::Settings"
+        "#;
+        let config: CodegenConfig = toml::from_str(toml_content).unwrap();
+        assert_eq!(
+            config.json_types.get("users.settings").map(String::as_str),
+            Some("crate::types::Settings")
+        );
+    }
+
+    #[test]
+    fn test_config_with_time_library() {
+        let config = CodegenConfig::default();
+        assert_eq!(config.time_library, TimeLibrary::Chrono);
+
+        let toml_content = r#"
+            schema_file = "test.sql"
+            time_library = "time"
+        "#;
+        let config: CodegenConfig = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.time_library, TimeLibrary::Time);
+    }
 }
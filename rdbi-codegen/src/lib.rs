@@ -61,12 +61,12 @@ pub mod config;
 pub mod error;
 pub mod parser;
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use tracing::{debug, info};
 
-pub use config::CodegenConfig;
+pub use config::{CodegenConfig, TimeLibrary};
 pub use error::{CodegenError, Result};
 
 /// Main entry point for code generation
@@ -233,6 +233,42 @@ impl CodegenBuilder {
         self
     }
 
+    /// Map TIMESTAMP columns to `chrono::DateTime<Utc>` instead of `NaiveDateTime`
+    pub fn timestamp_utc(mut self) -> Self {
+        self.config.timestamp_utc = true;
+        self
+    }
+
+    /// Select the date/time library used in generated structs and DAO signatures
+    pub fn time_library(mut self, library: TimeLibrary) -> Self {
+        self.config.time_library = library;
+        self
+    }
+
+    /// Map spatial columns to `geo-types` geometries instead of `Vec<u8>`
+    pub fn geo_types(mut self) -> Self {
+        self.config.geo_types = true;
+        self
+    }
+
+    /// Decode a JSON column into `rdbi::Json<rust_type>`
+    ///
+    /// `column` is `table.column`; `rust_type` is a path such as `crate::types::Settings`.
+    pub fn json_type(mut self, column: &str, rust_type: &str) -> Self {
+        self.config
+            .json_types
+            .insert(column.to_string(), rust_type.to_string());
+        self
+    }
+
+    /// Map matching `BINARY(16)` columns to `uuid::Uuid`
+    ///
+    /// Patterns are `column` or `table.column`, with `*` as a wildcard (e.g. `*_uuid`).
+    pub fn uuid_columns(mut self, patterns: &[&str]) -> Self {
+        self.config.uuid_columns = patterns.join(",");
+        self
+    }
+
     /// Generate the code
     pub fn generate(self) -> Result<()> {
         generate(&self.config)
@@ -264,6 +300,23 @@ struct CargoMetadataConfig {
 
     /// Output directory for generated DAOs
     output_dao_dir: Option<String>,
+
+    /// Map TIMESTAMP columns to `chrono::DateTime<Utc>` (default: false)
+    timestamp_utc: Option<bool>,
+
+    /// Date/time library: "chrono" (default) or "time"
+    time_library: Option<TimeLibrary>,
+
+    /// Map spatial columns to `geo-types` geometries (default: false)
+    geo_types: Option<bool>,
+
+    /// `BINARY(16)` column patterns to map to `uuid::Uuid` (optional)
+    #[serde(default)]
+    uuid_columns: Vec<String>,
+
+    /// JSON column (`table.column`) to Rust type overrides (optional)
+    #[serde(default)]
+    json_types: BTreeMap<String, String>,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -402,6 +455,28 @@ pub fn generate_from_cargo_metadata() -> Result<()> {
         builder = builder.structs_only();
     }
 
+    // Apply type mapping options
+    if let Some(true) = metadata_config.timestamp_utc {
+        builder = builder.timestamp_utc();
+    }
+    if let Some(library) = metadata_config.time_library {
+        builder = builder.time_library(library);
+    }
+    if let Some(true) = metadata_config.geo_types {
+        builder = builder.geo_types();
+    }
+    if !metadata_config.uuid_columns.is_empty() {
+        let patterns: Vec<&str> = metadata_config
+            .uuid_columns
+            .iter()
+            .map(|s| s.as_str())
+            .collect();
+        builder = builder.uuid_columns(&patterns);
+    }
+    for (column, rust_type) in &metadata_config.json_types {
+        builder = builder.json_type(column, rust_type);
+    }
+
     // Emit rerun-if-changed
     println!("cargo:rerun-if-changed={}", schema_path.display());
     println!("cargo:rerun-if-changed={}", cargo_toml_path.display());
@@ -48,6 +48,9 @@ pub struct ColumnMetadata {
     /// Enum values if this is an ENUM column
     pub enum_values: Option<Vec<String>>,
 
+    /// Set members if this is a SET column
+    pub set_values: Option<Vec<String>>,
+
     /// Column comment (if any)
     pub comment: Option<String>,
 }
@@ -121,4 +124,9 @@ impl ColumnMetadata {
     pub fn is_enum(&self) -> bool {
         self.enum_values.is_some()
     }
+
+    /// Check if this column has a set type
+    pub fn is_set(&self) -> bool {
+        self.set_values.is_some()
+    }
 }
@@ -151,6 +151,7 @@ fn extract_column_metadata(
     let name = extract_ident(&col_def.name);
     let data_type = format!("{}", col_def.data_type);
     let enum_values = extract_enum_values(&col_def.data_type);
+    let set_values = extract_set_values(&col_def.data_type);
     let is_unsigned = data_type.to_uppercase().contains("UNSIGNED");
 
     let mut nullable = true; // Default to nullable
@@ -205,6 +206,7 @@ fn extract_column_metadata(
         is_auto_increment,
         is_unsigned,
         enum_values,
+        set_values,
         comment,
     };
 
@@ -227,6 +229,14 @@ fn extract_enum_values(data_type: &DataType) -> Option<Vec<String>> {
     }
 }
 
+/// Extract set members from a data type
+fn extract_set_values(data_type: &DataType) -> Option<Vec<String>> {
+    match data_type {
+        DataType::Set(members) => Some(members.clone()),
+        _ => None,
+    }
+}
+
 /// Extract a simple string from an ObjectName
 fn extract_table_name(name: &ObjectName) -> String {
     name.0
@@ -331,6 +341,25 @@ mod tests {
         assert!(values.contains(&"ACTIVE".to_string()));
     }
 
+    #[test]
+    fn test_parse_set_column() {
+        let sql = r#"
+            CREATE TABLE items (
+                id BIGINT PRIMARY KEY,
+                tags SET('NEW', 'SALE', 'FEATURED')
+            );
+        "#;
+
+        let tables = parse_schema(sql).unwrap();
+        let tags_col = tables[0].columns.iter().find(|c| c.name == "tags").unwrap();
+        assert!(tags_col.is_set());
+        assert!(!tags_col.is_enum());
+        assert_eq!(
+            tags_col.set_values.as_deref().unwrap(),
+            ["NEW", "SALE", "FEATURED"]
+        );
+    }
+
     #[test]
     fn test_parse_foreign_key() {
         let sql = r#"
@@ -5,6 +5,9 @@ fn main() {
     let out_dir = std::env::var("OUT_DIR").unwrap();
     rdbi_codegen::CodegenBuilder::new("../examples/example-schema.sql")
         .output_dir(&out_dir)
+        .uuid_columns(&["*_uuid"])
+        .geo_types()
+        .json_type("json_documents.payload", "crate::JsonPayload")
         .generate()
         .expect("codegen failed");
 
@@ -33,6 +33,13 @@ mod dao {
 use models::*;
 use rdbi::{MySqlPool, Query, Transactional};
 
+/// Document type for `json_documents.payload`, mapped via `json_type` in build.rs
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct JsonPayload {
+    pub title: String,
+    pub tags: Vec<String>,
+}
+
 // Holds the connection URL (container lives in the thread)
 static DB_URL: OnceLock<String> = OnceLock::new();
 // Flag to signal the container thread to exit
@@ -105,6 +112,9 @@ fn get_db_url() -> &'static str {
 
 // All tables in reverse dependency order for cleanup
 const ALL_TABLES: &[&str] = &[
+    "delivery_zones",
+    "json_documents",
+    "uuid_records",
     "order_item_details",
     "multi_index_table",
     "boolean_table",
@@ -148,172 +158,2386 @@ async fn clean_all_tables(pool: &MySqlPool) {
         .unwrap();
 }
 
-// ============ CRUD Tests ============
-
+// ============ CRUD Tests ============
+
+#[tokio::test]
+#[serial]
+async fn test_insert_and_find_by_id() {
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    // Insert a user
+    let user = Users {
+        id: 0, // Will be auto-generated
+        username: "testuser".to_string(),
+        email: "test@example.com".to_string(),
+        first_name: Some("Test".to_string()),
+        last_name: Some("User".to_string()),
+        status: UsersStatus::Active,
+        is_active: true,
+        age: Some(25),
+        created_at: None,
+        updated_at: None,
+        birth_date: None,
+        login_time: None,
+    };
+
+    let last_id = dao::users::insert(&pool, &user).await.unwrap();
+    assert!(last_id > 0);
+
+    // Find by ID
+    let found = dao::users::find_by_id(&pool, last_id as i64).await.unwrap();
+    assert!(found.is_some());
+    let found = found.unwrap();
+    assert_eq!(found.username, "testuser");
+    assert_eq!(found.email, "test@example.com");
+    assert_eq!(found.status, UsersStatus::Active);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_update_and_delete() {
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    // Insert
+    let user = Users {
+        id: 0,
+        username: "updateuser".to_string(),
+        email: "update@example.com".to_string(),
+        first_name: Some("Update".to_string()),
+        last_name: Some("Test".to_string()),
+        status: UsersStatus::Pending,
+        is_active: true,
+        age: Some(30),
+        created_at: None,
+        updated_at: None,
+        birth_date: None,
+        login_time: None,
+    };
+
+    let last_id = dao::users::insert(&pool, &user).await.unwrap();
+
+    // Update
+    let mut updated = dao::users::find_by_id(&pool, last_id as i64)
+        .await
+        .unwrap()
+        .unwrap();
+    updated.status = UsersStatus::Active;
+    updated.first_name = Some("Updated".to_string());
+
+    let rows = dao::users::update(&pool, &updated).await.unwrap();
+    assert_eq!(rows, 1);
+
+    // Verify update
+    let fetched = dao::users::find_by_id(&pool, last_id as i64)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(fetched.status, UsersStatus::Active);
+    assert_eq!(fetched.first_name, Some("Updated".to_string()));
+
+    // Delete
+    let deleted = dao::users::delete_by_id(&pool, last_id as i64)
+        .await
+        .unwrap();
+    assert_eq!(deleted, 1);
+
+    // Verify delete
+    let not_found = dao::users::find_by_id(&pool, last_id as i64).await.unwrap();
+    assert!(not_found.is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_insert_all_batch() {
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    let products = vec![
+        Products {
+            sku: "SKU001".to_string(),
+            name: "Product 1".to_string(),
+            price: rust_decimal::Decimal::new(1999, 2), // 19.99
+            stock: Some(100),
+            status: Some(ProductsStatus::Active),
+        },
+        Products {
+            sku: "SKU002".to_string(),
+            name: "Product 2".to_string(),
+            price: rust_decimal::Decimal::new(2999, 2), // 29.99
+            stock: Some(50),
+            status: Some(ProductsStatus::Active),
+        },
+        Products {
+            sku: "SKU003".to_string(),
+            name: "Product 3".to_string(),
+            price: rust_decimal::Decimal::new(999, 2), // 9.99
+            stock: Some(200),
+            status: Some(ProductsStatus::OutOfStock),
+        },
+    ];
+
+    let rows = dao::products::insert_all(&pool, &products).await.unwrap();
+    assert_eq!(rows, 3);
+
+    // Verify
+    let all = dao::products::find_all(&pool).await.unwrap();
+    assert_eq!(all.len(), 3);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_upsert() {
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    // Insert first
+    let product = Products {
+        sku: "UPSERT001".to_string(),
+        name: "Original Name".to_string(),
+        price: rust_decimal::Decimal::new(1000, 2),
+        stock: Some(10),
+        status: Some(ProductsStatus::Active),
+    };
+
+    dao::products::insert(&pool, &product).await.unwrap();
+
+    // Upsert with same SKU but different data
+    let updated_product = Products {
+        sku: "UPSERT001".to_string(),
+        name: "Updated Name".to_string(),
+        price: rust_decimal::Decimal::new(1500, 2),
+        stock: Some(20),
+        status: Some(ProductsStatus::Active),
+    };
+
+    dao::products::upsert(&pool, &updated_product)
+        .await
+        .unwrap();
+
+    // Verify the update happened
+    let found = dao::products::find_by_sku(&pool, "UPSERT001")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.name, "Updated Name");
+    assert_eq!(found.price, rust_decimal::Decimal::new(1500, 2));
+    assert_eq!(found.stock, Some(20));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_batch_insert_on_conflict() {
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    let product = |sku: &str, name: &str| Products {
+        sku: sku.to_string(),
+        name: name.to_string(),
+        price: rust_decimal::Decimal::new(1000, 2),
+        stock: Some(1),
+        status: Some(ProductsStatus::Active),
+    };
+    dao::products::insert(&pool, &product("DUP001", "Original"))
+        .await
+        .unwrap();
+
+    let batch = vec![product("DUP001", "Ignored"), product("NEW001", "New")];
+
+    // Default mode fails on the duplicate key
+    assert!(rdbi::BatchInsert::new("products", &batch)
+        .execute(&pool)
+        .await
+        .is_err());
+
+    // IGNORE skips the conflicting row
+    let result = rdbi::BatchInsert::new("products", &batch)
+        .on_conflict(rdbi::OnConflict::Ignore)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(result.rows_affected, 1);
+    let found = dao::products::find_by_sku(&pool, "DUP001")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.name, "Original");
+
+    // REPLACE overwrites the conflicting row
+    let replacement = vec![product("DUP001", "Replaced")];
+    rdbi::BatchInsert::new("products", &replacement)
+        .on_conflict(rdbi::OnConflict::Replace)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let found = dao::products::find_by_sku(&pool, "DUP001")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.name, "Replaced");
+    assert_eq!(dao::products::count_all(&pool).await.unwrap(), 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_batch_upsert_update_expr() {
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    let version = rdbi::Pool::server_version(&pool).await.unwrap().unwrap();
+    assert!(version.major >= 5);
+
+    let products = vec![Products {
+        sku: "EXPR001".to_string(),
+        name: "Counter".to_string(),
+        price: rust_decimal::Decimal::new(500, 2),
+        stock: Some(10),
+        status: Some(ProductsStatus::Active),
+    }];
+    dao::products::insert_all(&pool, &products).await.unwrap();
+
+    let restock = vec![Products {
+        sku: "EXPR001".to_string(),
+        name: "Counter (restocked)".to_string(),
+        price: rust_decimal::Decimal::new(600, 2),
+        stock: Some(5),
+        status: Some(ProductsStatus::Active),
+    }];
+    rdbi::BatchUpsert::new("products", &restock)
+        .update_columns(vec!["name"])
+        .update_expr("stock", "`stock` + new.`stock`")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let found = dao::products::find_by_sku(&pool, "EXPR001")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.name, "Counter (restocked)");
+    assert_eq!(found.stock, Some(15));
+    // Price was not in the update list
+    assert_eq!(found.price, rust_decimal::Decimal::new(500, 2));
+
+    // Upserts inside a transaction pick the same syntax
+    rdbi::in_transaction!(pool, |tx| {
+        rdbi::BatchUpsert::new("products", &restock)
+            .update_expr("stock", "`stock` + new.`stock`")
+            .execute(tx)
+            .await?;
+        Ok(())
+    })
+    .await
+    .unwrap();
+    let found = dao::products::find_by_sku(&pool, "EXPR001")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.stock, Some(20));
+    assert_eq!(found.price, rust_decimal::Decimal::new(600, 2));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_batch_update_by_primary_key() {
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    let products: Vec<Products> = (1..=5)
+        .map(|i| Products {
+            sku: format!("BU{:03}", i),
+            name: format!("Product {}", i),
+            price: rust_decimal::Decimal::new(100 * i, 2),
+            stock: Some(i as u32),
+            status: Some(ProductsStatus::Active),
+        })
+        .collect();
+    dao::products::insert_all(&pool, &products).await.unwrap();
+
+    let updated: Vec<Products> = products
+        .iter()
+        .map(|p| Products {
+            name: format!("{} v2", p.name),
+            stock: p.stock.map(|s| s * 10),
+            ..p.clone()
+        })
+        .collect();
+
+    // Small chunk size forces several statements
+    let result = rdbi::BatchUpdate::new("products", &["sku"], &updated)
+        .update_columns(vec!["name", "stock"])
+        .chunk_size(2)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(result.rows_affected, 5);
+
+    for p in &updated {
+        let found = dao::products::find_by_sku(&pool, &p.sku)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.name, p.name);
+        assert_eq!(found.stock, p.stock);
+    }
+
+    // Unknown key column is reported
+    let err = rdbi::BatchUpdate::new("products", &["missing"], &updated)
+        .execute(&pool)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, rdbi::Error::ColumnNotFound(c) if c == "missing"));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_batch_delete_composite_key() {
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    let user = Users {
+        id: 0,
+        username: "batchdelete".to_string(),
+        email: "batchdelete@example.com".to_string(),
+        first_name: None,
+        last_name: None,
+        status: UsersStatus::Active,
+        is_active: true,
+        age: None,
+        created_at: None,
+        updated_at: None,
+        birth_date: None,
+        login_time: None,
+    };
+    let user_id = dao::users::insert(&pool, &user).await.unwrap() as i64;
+
+    let settings: Vec<UserSettings> = ["a", "b", "c", "d"]
+        .iter()
+        .map(|key| UserSettings {
+            user_id,
+            setting_key: key.to_string(),
+            setting_value: None,
+            is_enabled: true,
+            created_at: None,
+            updated_at: None,
+        })
+        .collect();
+    dao::user_settings::insert_all(&pool, &settings)
+        .await
+        .unwrap();
+
+    let result =
+        rdbi::BatchDelete::new("user_settings", &["user_id", "setting_key"], &settings[..3])
+            .chunk_size(2)
+            .execute(&pool)
+            .await
+            .unwrap();
+    assert_eq!(result.rows_affected, 3);
+
+    let remaining = dao::user_settings::find_all(&pool).await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].setting_key, "d");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_select_builder() {
+    use rdbi::select::col;
+    use rdbi::{Order, Select};
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    for (i, status) in [
+        UsersStatus::Active,
+        UsersStatus::Active,
+        UsersStatus::Inactive,
+        UsersStatus::Pending,
+    ]
+    .iter()
+    .enumerate()
+    {
+        let user = Users {
+            id: 0,
+            username: format!("sel{}", i + 1),
+            email: format!("sel{}@example.com", i + 1),
+            first_name: None,
+            last_name: None,
+            status: *status,
+            is_active: true,
+            age: Some(20 + i as u32),
+            created_at: None,
+            updated_at: None,
+            birth_date: None,
+            login_time: None,
+        };
+        dao::users::insert(&pool, &user).await.unwrap();
+    }
+
+    // Optional filters: None is skipped, Some adds a condition
+    let min_age: Option<u32> = None;
+    let query = Select::new()
+        .columns(&["id", "username"])
+        .from("users")
+        .where_(col("status").eq("ACTIVE").or(col("status").eq("PENDING")))
+        .and(min_age.map(|age| col("age").ge(age)))
+        .and(col("username").like("sel%"))
+        .order_by("username", Order::Desc)
+        .limit(2)
+        .offset(1)
+        .build();
+    assert_eq!(
+        query.sql(),
+        "SELECT `id`, `username` FROM `users` \
+         WHERE (`status` = ? OR `status` = ?) AND `username` LIKE ? \
+         ORDER BY `username` DESC LIMIT ? OFFSET ?"
+    );
+    assert_eq!(query.params().len(), 5);
+
+    let query = Select::new()
+        .from("users")
+        .where_(col("status").eq("ACTIVE").or(col("status").eq("PENDING")))
+        .and(min_age.map(|age| col("age").ge(age)))
+        .order_by("username", Order::Desc)
+        .limit(2)
+        .offset(1);
+    let users: Vec<Users> = query.build().fetch_all(&pool).await.unwrap();
+    let names: Vec<&str> = users.iter().map(|u| u.username.as_str()).collect();
+    assert_eq!(names, vec!["sel2", "sel1"]);
+
+    // Join with aliases, grouping and IN lists
+    let user = dao::users::find_by_username(&pool, "sel1")
+        .await
+        .unwrap()
+        .unwrap();
+    for key in ["a", "b", "c"] {
+        let setting = UserSettings {
+            user_id: user.id,
+            setting_key: key.to_string(),
+            setting_value: None,
+            is_enabled: key != "c",
+            created_at: None,
+            updated_at: None,
+        };
+        dao::user_settings::insert(&pool, &setting).await.unwrap();
+    }
+
+    let count: i64 = Select::new()
+        .column_raw("COUNT(*)")
+        .from_as("users", "u")
+        .join_as("user_settings", "s", col("s.user_id").eq_col("u.id"))
+        .where_(col("s.is_enabled").eq(true))
+        .and(col("u.username").is_in(&["sel1", "sel2"]))
+        .build()
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+
+    let none: Vec<Users> = Select::new()
+        .from("users")
+        .where_(col("id").is_in::<i64>(&[]))
+        .build()
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert!(none.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_query_builder() {
+    use rdbi::QueryBuilder;
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    // Multi-row insert assembled with push_values
+    let rows = [("qb1", "qb1@example.com"), ("qb2", "qb2@example.com")];
+    let mut insert = QueryBuilder::new("INSERT INTO ");
+    insert
+        .push_identifier("users")
+        .push_sql(" (")
+        .separated(", ")
+        .push_identifier("username")
+        .push_identifier("email");
+    insert.push_sql(") ");
+    insert.push_values(rows, |mut row, (username, email)| {
+        row.push_bind(username).push_bind(email);
+    });
+    assert_eq!(
+        insert.sql(),
+        "INSERT INTO `users` (`username`, `email`) VALUES (?, ?), (?, ?)"
+    );
+    let result = insert.build().execute(&pool).await.unwrap();
+    assert_eq!(result.rows_affected, 2);
+
+    // WHERE clause built across a helper function
+    fn push_username_filter(qb: &mut QueryBuilder, names: &[&str]) {
+        qb.push_sql(" WHERE ")
+            .push_identifier("username")
+            .push_sql(" IN (");
+        let mut list = qb.separated(", ");
+        for name in names {
+            list.push_bind(*name);
+        }
+        qb.push_sql(")");
+    }
+
+    let mut select = QueryBuilder::new("SELECT * FROM ");
+    select.push_identifier("users");
+    push_username_filter(&mut select, &["qb2", "missing"]);
+    select.push_sql(" LIMIT ").push_bind(10);
+    assert_eq!(
+        select.sql(),
+        "SELECT * FROM `users` WHERE `username` IN (?, ?) LIMIT ?"
+    );
+    assert_eq!(select.params().len(), 3);
+
+    let users: Vec<Users> = select.build().fetch_all(&pool).await.unwrap();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].email, "qb2@example.com");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_ident_quoting() {
+    use rdbi::select::col;
+    use rdbi::{BatchInsert, Error, Ident, QueryBuilder, Select};
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    assert_eq!(
+        Ident::parse("test.users").unwrap().to_string(),
+        "`test`.`users`"
+    );
+    assert_eq!(Ident::new("we`ird").unwrap().to_string(), "`we``ird`");
+    assert_eq!(Ident::new("a.b").unwrap().to_string(), "`a.b`");
+    assert!(Ident::new("").is_err());
+    assert!(Ident::new("trailing ").is_err());
+    assert!(Ident::new("nul\0").is_err());
+    assert!(Ident::new("x".repeat(65)).is_err());
+    assert!(Ident::parse("a.b.c.d").is_err());
+
+    // Schema-qualified table names work with the batch builders
+    let table = Ident::qualified("test", "users").unwrap();
+    let users = vec![Users {
+        id: 0,
+        username: "ident1".to_string(),
+        email: "ident1@example.com".to_string(),
+        first_name: None,
+        last_name: None,
+        status: UsersStatus::Active,
+        is_active: true,
+        age: None,
+        created_at: None,
+        updated_at: None,
+        birth_date: None,
+        login_time: None,
+    }];
+    let result = BatchInsert::new(&table, &users)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(result.rows_affected, 1);
+
+    // A table name that tries to break out of its quotes stays a single identifier
+    let err = BatchInsert::new("users` (id) VALUES (1); --", &users)
+        .execute(&pool)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::MySql(_)));
+    let err = BatchInsert::new("", &users)
+        .execute(&pool)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidIdentifier(_)));
+
+    // Builders report invalid identifiers when the query runs
+    let err = Select::new()
+        .from("users")
+        .where_(col("email ").eq("x"))
+        .build()
+        .fetch_all::<Users, _>(&pool)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidIdentifier(_)));
+
+    let mut qb = QueryBuilder::new("SELECT * FROM ");
+    qb.push_identifier("");
+    let err = qb.clone().build().execute(&pool).await.unwrap_err();
+    assert!(matches!(err, Error::InvalidIdentifier(_)));
+
+    // ...or up front, before the SQL can be read
+    let err = qb.try_build().unwrap_err();
+    assert!(matches!(err, Error::InvalidIdentifier(_)));
+    let err = Select::new()
+        .column("id")
+        .from("users\0")
+        .try_build()
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidIdentifier(_)));
+    let query = Select::new()
+        .column("id")
+        .from("users")
+        .try_build()
+        .unwrap();
+    assert_eq!(query.sql(), "SELECT `id` FROM `users`");
+    assert!(matches!(
+        BatchInsert::try_new("", &users),
+        Err(Error::InvalidIdentifier(_))
+    ));
+    assert!(matches!(
+        rdbi::BatchDelete::try_new("users", &["id "], &users),
+        Err(Error::InvalidIdentifier(_))
+    ));
+
+    // `*` and `alias.*` select all columns
+    let query = Select::new()
+        .column("u.*")
+        .column_as("s.setting_key", "setting")
+        .from_as("users", "u")
+        .join_as("user_settings", "s", col("s.user_id").eq_col("u.id"))
+        .build();
+    assert_eq!(
+        query.sql(),
+        "SELECT `u`.*, `s`.`setting_key` AS `setting` FROM `users` AS `u` \
+         INNER JOIN `user_settings` AS `s` ON `s`.`user_id` = `u`.`id`"
+    );
+    assert_eq!(
+        Select::new().column("*").from("users").build().sql(),
+        "SELECT * FROM `users`"
+    );
+
+    let found: Vec<Users> = Select::new()
+        .from(&table)
+        .where_(col("users.username").eq("ident1"))
+        .build()
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_column_type_aware_decoding() {
+    use rdbi::Value;
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    Query::new(
+        "INSERT INTO type_coverage (id, decimal_val, event_time, binary_data, blob_data, char_val) \
+         VALUES (1, 12.5, '2024-03-01 00:00:00', 'ascii-looking-16', 'plain text', 'chars')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    async fn column(pool: &MySqlPool, name: &str) -> Value {
+        let sql = format!("SELECT `{}` FROM type_coverage WHERE id = 1", name);
+        Query::new(&sql).fetch_scalar(pool).await.unwrap()
+    }
+
+    // Binary strings stay bytes even when the content is valid UTF-8
+    assert_eq!(
+        column(&pool, "binary_data").await,
+        Value::Bytes(b"ascii-looking-16".to_vec())
+    );
+    assert_eq!(
+        column(&pool, "blob_data").await,
+        Value::Bytes(b"plain text".to_vec())
+    );
+    assert_eq!(
+        column(&pool, "char_val").await,
+        Value::String("chars".to_string())
+    );
+
+    // DECIMAL decodes as Decimal, not a string
+    assert_eq!(
+        column(&pool, "decimal_val").await,
+        Value::Decimal("12.5000".parse().unwrap())
+    );
+
+    // DATETIME at midnight is still a DateTime
+    let midnight = chrono::NaiveDate::from_ymd_opt(2024, 3, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    assert_eq!(column(&pool, "event_time").await, Value::DateTime(midnight));
+
+    // Existing conversions keep working on top of the typed values
+    let blob: String = Query::new("SELECT blob_data FROM type_coverage WHERE id = 1")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(blob, "plain text");
+    let decimal: String = Query::new("SELECT decimal_val FROM type_coverage WHERE id = 1")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(decimal, "12.5000");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_session_time_zone() {
+    use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
+    use rdbi::Transaction;
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+    let pool = MySqlPool::builder(get_db_url())
+        .time_zone(tokyo)
+        .build()
+        .unwrap();
+
+    let zone: String = Query::new("SELECT @@session.time_zone")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(zone, "+09:00");
+
+    let instant = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+    Query::new("INSERT INTO users (username, email, created_at) VALUES (?, ?, ?)")
+        .bind("tz_user")
+        .bind("tz@example.com")
+        .bind(instant)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // The instant round-trips unchanged
+    let read: DateTime<Utc> = Query::new("SELECT created_at FROM users WHERE username = ?")
+        .bind("tz_user")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(read, instant);
+
+    let local: DateTime<FixedOffset> =
+        Query::new("SELECT created_at FROM users WHERE username = ?")
+            .bind("tz_user")
+            .fetch_scalar(&pool)
+            .await
+            .unwrap();
+    assert_eq!(local.offset(), &tokyo);
+    assert_eq!(local, instant);
+
+    // Naive reads see the session wall clock
+    let naive: NaiveDateTime = Query::new("SELECT created_at FROM users WHERE username = ?")
+        .bind("tz_user")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(naive, instant.with_timezone(&tokyo).naive_local());
+
+    // Transactions share the pool's time zone
+    let tx = pool.begin().await.unwrap();
+    let read: DateTime<Utc> = Query::new("SELECT created_at FROM users WHERE username = ?")
+        .bind("tz_user")
+        .fetch_scalar(&tx)
+        .await
+        .unwrap();
+    tx.rollback().await.unwrap();
+    assert_eq!(read, instant);
+
+    // The zone survives the reset a connection gets between checkouts
+    let single = MySqlPool::builder(get_db_url())
+        .pool_min(1)
+        .pool_max(1)
+        .time_zone(tokyo)
+        .build()
+        .unwrap();
+    for (i, hour) in [3, 13, 23].into_iter().enumerate() {
+        let zone: String = Query::new("SELECT @@session.time_zone")
+            .fetch_scalar(&single)
+            .await
+            .unwrap();
+        assert_eq!(zone, "+09:00", "checkout {}", i);
+
+        let instant = Utc.with_ymd_and_hms(2024, 3, 2, hour, 0, 0).unwrap();
+        let username = format!("tz_single_{}", i);
+        Query::new("INSERT INTO users (username, email, created_at) VALUES (?, ?, ?)")
+            .bind(&username)
+            .bind(format!("{}@example.com", username))
+            .bind(instant)
+            .execute(&single)
+            .await
+            .unwrap();
+        let read: DateTime<Utc> = Query::new("SELECT created_at FROM users WHERE username = ?")
+            .bind(&username)
+            .fetch_scalar(&single)
+            .await
+            .unwrap();
+        assert_eq!(read, instant, "checkout {}", i);
+
+        // The server stored the right instant, checked without rdbi's conversion
+        let utc: String = Query::new(
+            "SELECT DATE_FORMAT(CONVERT_TZ(created_at, @@session.time_zone, '+00:00'), \
+             '%Y-%m-%d %H:%i:%s') FROM users WHERE username = ?",
+        )
+        .bind(&username)
+        .fetch_scalar(&single)
+        .await
+        .unwrap();
+        assert_eq!(utc, instant.format("%Y-%m-%d %H:%M:%S").to_string());
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_time_as_duration() {
+    use chrono::{Duration, NaiveTime};
+    use rdbi::Value;
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    let elapsed = Duration::hours(100) + Duration::minutes(30) + Duration::milliseconds(250);
+    Query::new("INSERT INTO users (username, email, login_time) VALUES (?, ?, ?)")
+        .bind("long")
+        .bind("long@example.com")
+        .bind(elapsed)
+        .execute(&pool)
+        .await
+        .unwrap();
+    Query::new("INSERT INTO users (username, email, login_time) VALUES (?, ?, ?)")
+        .bind("negative")
+        .bind("negative@example.com")
+        .bind(-Duration::hours(800))
+        .execute(&pool)
+        .await
+        .unwrap();
+    Query::new("INSERT INTO users (username, email, login_time) VALUES (?, ?, ?)")
+        .bind("short")
+        .bind("short@example.com")
+        .bind(NaiveTime::from_hms_opt(9, 15, 0).unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    async fn login_time<T: rdbi::FromValue + Send>(pool: &MySqlPool, user: &str) -> T {
+        Query::new("SELECT login_time FROM users WHERE username = ?")
+            .bind(user)
+            .fetch_scalar(pool)
+            .await
+            .unwrap()
+    }
+
+    // Values beyond a single day round-trip as signed durations
+    let long: Duration = login_time(&pool, "long").await;
+    assert_eq!(long, elapsed);
+    let negative: Duration = login_time(&pool, "negative").await;
+    assert_eq!(negative, -Duration::hours(800));
+    let std_long: std::time::Duration = login_time(&pool, "long").await;
+    assert_eq!(std_long, elapsed.to_std().unwrap());
+
+    // NaiveTime keeps working for in-range values only
+    let short: NaiveTime = login_time(&pool, "short").await;
+    assert_eq!(short, NaiveTime::from_hms_opt(9, 15, 0).unwrap());
+    let out_of_range: rdbi::Result<NaiveTime> =
+        Query::new("SELECT login_time FROM users WHERE username = ?")
+            .bind("long")
+            .fetch_scalar(&pool)
+            .await;
+    assert!(out_of_range.is_err());
+
+    // The full TIME range is supported
+    let text: Value = Query::new("SELECT CAST('-838:59:59' AS TIME)")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        text,
+        Value::Duration(-(Duration::hours(838) + Duration::minutes(59) + Duration::seconds(59)))
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_uuid_storage_forms() {
+    use rdbi::SwappedUuid;
+    use uuid::Uuid;
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    let parent = Uuid::from_u128(0x6ccd780c_baba_1026_9564_5b8c656024db);
+    let child = Uuid::from_u128(0x1b4e28ba_2fa1_11d2_883f_0016d3cca427);
+
+    // Generated DAOs take &Uuid for BINARY(16) columns named *_uuid
+    let record = UuidRecords {
+        id: 0,
+        external_uuid: child,
+        parent_uuid: Some(parent),
+        label_id: Some(child.hyphenated().to_string()),
+    };
+    dao::uuid_records::insert(&pool, &record).await.unwrap();
+
+    let found = dao::uuid_records::find_by_external_uuid(&pool, &child)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.external_uuid, child);
+    assert_eq!(found.parent_uuid, Some(parent));
+    let children = dao::uuid_records::find_by_parent_uuid(&pool, Some(&parent))
+        .await
+        .unwrap();
+    assert_eq!(children.len(), 1);
+
+    // Stored as 16 raw bytes in standard order
+    let hex: String = Query::new("SELECT HEX(external_uuid) FROM uuid_records")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(hex, child.simple().to_string().to_uppercase());
+
+    // CHAR(36) text reads as Uuid, and hyphenated() writes the text form
+    let label: Uuid = Query::new("SELECT label_id FROM uuid_records")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(label, child);
+    let count: i64 = Query::new("SELECT COUNT(*) FROM uuid_records WHERE label_id = ?")
+        .bind(child.hyphenated())
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+
+    // SwappedUuid matches MySQL's UUID_TO_BIN(uuid, 1) ordering
+    let swapped: SwappedUuid = Query::new("SELECT UUID_TO_BIN(?, 1)")
+        .bind(child.hyphenated())
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(swapped, SwappedUuid(child));
+    let text: Uuid = Query::new("SELECT BIN_TO_UUID(?, 1)")
+        .bind(SwappedUuid(child))
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(text, child);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_time_crate_conversions() {
+    use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    let date = Date::from_calendar_date(2024, Month::March, 1).unwrap();
+    // TIME without a fraction rounds the microseconds away
+    let time = Time::from_hms_micro(13, 45, 30, 250_000).unwrap();
+    let datetime = PrimitiveDateTime::new(date, Time::from_hms(8, 0, 0).unwrap());
+    Query::new(
+        "INSERT INTO nullable_table (optional_date, optional_time, optional_timestamp) \
+         VALUES (?, ?, ?)",
+    )
+    .bind(date)
+    .bind(time)
+    .bind(datetime)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let read: Date = Query::new("SELECT optional_date FROM nullable_table")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(read, date);
+    let read: Time = Query::new("SELECT optional_time FROM nullable_table")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(read, Time::from_hms(13, 45, 30).unwrap());
+    let read: PrimitiveDateTime = Query::new("SELECT optional_timestamp FROM nullable_table")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(read, datetime);
+
+    // OffsetDateTime needs a session time zone to be written in
+    let offset = UtcOffset::from_hms(2, 0, 0).unwrap();
+    let instant = datetime.assume_offset(offset);
+    let err = Query::new("UPDATE nullable_table SET optional_timestamp = ?")
+        .bind(instant)
+        .execute(&pool)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, rdbi::Error::Query(_)), "{}", err);
+    let utc_pool = MySqlPool::builder(get_db_url())
+        .time_zone(chrono::Utc)
+        .build()
+        .unwrap();
+    Query::new("UPDATE nullable_table SET optional_timestamp = ?")
+        .bind(instant)
+        .execute(&utc_pool)
+        .await
+        .unwrap();
+    let read: OffsetDateTime = Query::new("SELECT optional_timestamp FROM nullable_table")
+        .fetch_scalar(&utc_pool)
+        .await
+        .unwrap();
+    assert_eq!(read, instant);
+    assert_eq!(read.offset(), UtcOffset::UTC);
+
+    // NULL maps to None as usual
+    let missing: Option<Date> = Query::new("SELECT CAST(NULL AS DATE)")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(missing, None);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_typed_json() {
+    use rdbi::Json;
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    let payload = JsonPayload {
+        title: "hello".to_string(),
+        tags: vec!["a".to_string(), "b".to_string()],
+    };
+    let doc = JsonDocuments {
+        id: 0,
+        payload: Json(payload.clone()),
+        meta: None,
+    };
+    let id = dao::json_documents::insert(&pool, &doc).await.unwrap();
+
+    let found = dao::json_documents::find_by_id(&pool, id as i64)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.payload.title, "hello");
+    assert_eq!(found.payload.into_inner(), payload);
+
+    // Scalars decode straight into the wrapper
+    let Json(tags): Json<Vec<String>> =
+        Query::new("SELECT JSON_EXTRACT(payload, '$.tags') FROM json_documents")
+            .fetch_scalar(&pool)
+            .await
+            .unwrap();
+    assert_eq!(tags, vec!["a", "b"]);
+
+    // A document that doesn't match the type names the column
+    Query::new("UPDATE json_documents SET payload = JSON_OBJECT('title', 42)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let err = dao::json_documents::find_by_id(&pool, id as i64)
+        .await
+        .unwrap_err();
+    match err {
+        rdbi::Error::TypeConversion { expected, actual } => {
+            assert_eq!(expected, "json");
+            assert!(actual.contains("column `payload`"), "{}", actual);
+        }
+        other => panic!("unexpected error: {}", other),
+    }
+
+    // A value that can't be encoded fails the statement instead of binding NULL
+    let keyed: std::collections::HashMap<(i32, i32), String> =
+        [((1, 2), "x".to_string())].into_iter().collect();
+    let err = Query::new("UPDATE json_documents SET meta = ? WHERE id = ?")
+        .bind(Json(keyed))
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap_err();
+    match err {
+        rdbi::Error::TypeConversion { expected, actual } => {
+            assert_eq!(expected, "json");
+            assert!(actual.contains("key must be a string"), "{}", actual);
+        }
+        other => panic!("unexpected error: {}", other),
+    }
+    let meta: Option<String> = Query::new("SELECT meta FROM json_documents WHERE id = ?")
+        .bind(id)
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(meta, None);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_spatial_values() {
+    use geo_types::{line_string, point, polygon, Geometry, LineString, Point, Polygon};
+    use rdbi::Spatial;
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    let depot = point! { x: 13.4, y: 52.5 };
+    let area = polygon![
+        (x: 0.0, y: 0.0),
+        (x: 10.0, y: 0.0),
+        (x: 10.0, y: 10.0),
+        (x: 0.0, y: 10.0),
+        (x: 0.0, y: 0.0),
+    ];
+    let route = line_string![(x: 0.0, y: 0.0), (x: 5.0, y: 5.0)];
+    let zone = DeliveryZones {
+        id: 0,
+        name: "center".to_string(),
+        depot,
+        area: Some(area.clone()),
+        route: Some(route.clone()),
+        stops: None,
+        shape: Some(Geometry::Point(depot)),
+    };
+    let id = dao::delivery_zones::insert(&pool, &zone).await.unwrap();
+
+    let found = dao::delivery_zones::find_by_id(&pool, id as i64)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.depot, depot);
+    assert_eq!(found.area, Some(area.clone()));
+    assert_eq!(found.route, Some(route.clone()));
+    assert_eq!(found.shape, Some(Geometry::Point(depot)));
+
+    // The server sees real geometries
+    let contains: i64 = Query::new("SELECT ST_Contains(area, ?) FROM delivery_zones")
+        .bind(point! { x: 5.0, y: 5.0 })
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(contains, 1);
+    let wkt: String = Query::new("SELECT ST_AsText(route) FROM delivery_zones")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(wkt, "LINESTRING(0 0,5 5)");
+
+    // Geometries built by the server decode too
+    let built: Polygon<f64> = Query::new(
+        "SELECT ST_GeomFromText('POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,2 1,2 2,1 2,1 1))')",
+    )
+    .fetch_scalar(&pool)
+    .await
+    .unwrap();
+    assert_eq!(built.interiors().len(), 1);
+    let line: LineString<f64> = Query::new("SELECT ST_GeomFromText('LINESTRING(1 2,3 4)')")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(line, line_string![(x: 1.0, y: 2.0), (x: 3.0, y: 4.0)]);
+
+    // Spatial keeps the SRID; geographic SRIDs store longitude as x
+    let located: Spatial<Point<f64>> =
+        Query::new("SELECT ST_GeomFromText('POINT(52.5 13.4)', 4326)")
+            .fetch_scalar(&pool)
+            .await
+            .unwrap();
+    assert_eq!(located, Spatial::new(4326, point! { x: 13.4, y: 52.5 }));
+    let srid: i64 = Query::new("SELECT ST_SRID(?)")
+        .bind(Spatial::new(4326, depot))
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(srid, 4326);
+
+    // Asking for the wrong kind is a conversion error
+    let wrong: rdbi::Result<Point<f64>> = Query::new("SELECT route FROM delivery_zones")
+        .fetch_scalar(&pool)
+        .await;
+    assert!(matches!(wrong, Err(rdbi::Error::TypeConversion { .. })));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_set_and_bit_columns() {
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    let entity = TypeCoverage {
+        id: 0,
+        tiny_bool: None,
+        tiny_int: None,
+        tiny_unsigned: None,
+        small_int: None,
+        small_unsigned: None,
+        medium_int: None,
+        medium_unsigned: None,
+        int_unsigned: None,
+        bigint_signed: None,
+        float_val: None,
+        double_val: None,
+        decimal_val: None,
+        event_time: None,
+        binary_data: None,
+        varbinary_data: None,
+        blob_data: None,
+        tiny_blob: None,
+        medium_blob: None,
+        long_blob: None,
+        single_bit: Some(true),
+        multi_bit: Some(0b1010_0101),
+        char_val: None,
+        tiny_text: None,
+        medium_text: None,
+        long_text: None,
+        set_val: Some(TypeCoverageSetVal::A | TypeCoverageSetVal::C),
+    };
+
+    let id = dao::type_coverage::insert(&pool, &entity).await.unwrap();
+
+    let found = dao::type_coverage::find_by_id(&pool, id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.single_bit, Some(true));
+    assert_eq!(found.multi_bit, Some(0b1010_0101));
+    let set = found.set_val.unwrap();
+    assert!(set.contains(TypeCoverageSetVal::A));
+    assert!(!set.contains(TypeCoverageSetVal::B));
+    assert_eq!(set.bits(), 0b101);
+    assert_eq!(set.to_string(), "A,C");
+
+    // MySQL stores the set as its member list
+    let stored: String = Query::new("SELECT set_val FROM type_coverage WHERE id = ?")
+        .bind(id)
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(stored, "A,C");
+
+    // Empty sets round-trip as the empty string
+    Query::new("UPDATE type_coverage SET set_val = ? WHERE id = ?")
+        .bind(TypeCoverageSetVal::empty())
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let found = dao::type_coverage::find_by_id(&pool, id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.set_val, Some(TypeCoverageSetVal::empty()));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_debug_sql_round_trip() {
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    let query = Query::new(
+        "INSERT INTO users (username, email, first_name, last_name, is_active, birth_date, login_time) \
+         VALUES (?, ?, ?, ?, ?, ?, ?) -- who?",
+    )
+    .bind("o'brien?")
+    .bind("back\\slash\n\"q\"@example.com")
+    .bind(Option::<String>::None)
+    .bind("日本")
+    .bind(true)
+    .bind(chrono::NaiveDate::from_ymd_opt(1990, 5, 17).unwrap())
+    .bind(chrono::Duration::minutes(-90));
+    let sql = query.to_debug_sql();
+    assert!(sql.ends_with("'-01:30:00') -- who?"));
+
+    // The rendered SQL runs as-is and stores the same values
+    Query::new(&sql).execute(&pool).await.unwrap();
+    let user = dao::users::find_by_username(&pool, "o'brien?")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(user.email, "back\\slash\n\"q\"@example.com");
+    assert_eq!(user.first_name, None);
+    assert_eq!(user.last_name.as_deref(), Some("日本"));
+    assert_eq!(
+        user.birth_date,
+        Some(chrono::NaiveDate::from_ymd_opt(1990, 5, 17).unwrap())
+    );
+
+    // Parameters serialize to their natural JSON form
+    let params = rdbi::DynamicQuery::new("SELECT ?, ?, ?")
+        .bind(42_i64)
+        .bind(Option::<i32>::None)
+        .bind(rust_decimal::Decimal::new(1050, 2))
+        .params()
+        .to_vec();
+    assert_eq!(
+        serde_json::to_string(&params).unwrap(),
+        r#"[42,null,"10.50"]"#
+    );
+    let values: Vec<rdbi::Value> = serde_json::from_str(r#"[42,null,"10.50"]"#).unwrap();
+    assert_eq!(values[0], rdbi::Value::I64(42));
+    assert_eq!(values[1], rdbi::Value::Null);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_fetch_as_serde() {
+    #[derive(Debug, serde::Deserialize)]
+    struct UserSummary {
+        id: i64,
+        #[serde(rename = "username")]
+        name: String,
+        status: UsersStatus,
+        is_active: bool,
+        age: Option<u32>,
+        // Not selected: absent columns are absent fields
+        nickname: Option<String>,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct DocumentRow {
+        payload: JsonPayload,
+        meta: Option<rdbi::Json<serde_json::Value>>,
+    }
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    Query::new(
+        "INSERT INTO users (username, email, status, is_active, age) VALUES \
+         ('alice', 'alice@example.com', 'ACTIVE', 1, 30), \
+         ('bob', 'bob@example.com', 'PENDING', 0, NULL)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let users: Vec<UserSummary> =
+        Query::new("SELECT id, username, status, is_active, age FROM users ORDER BY id")
+            .fetch_all_as(&pool)
+            .await
+            .unwrap();
+    assert_eq!(users.len(), 2);
+    assert_eq!(users[0].name, "alice");
+    assert_eq!(users[0].status, UsersStatus::Active);
+    assert!(users[0].is_active);
+    assert_eq!(users[0].age, Some(30));
+    assert_eq!(users[1].status, UsersStatus::Pending);
+    assert!(!users[1].is_active);
+    assert_eq!(users[1].age, None);
+    assert_eq!(users[1].nickname, None);
+    assert!(users[1].id > users[0].id);
+
+    // Tuples and plain values read columns in order
+    let (name, email): (String, String) =
+        Query::new("SELECT username, email FROM users WHERE username = ?")
+            .bind("bob")
+            .fetch_one_as(&pool)
+            .await
+            .unwrap();
+    assert_eq!((name.as_str(), email.as_str()), ("bob", "bob@example.com"));
+    let missing: Option<String> = Query::new("SELECT username FROM users WHERE username = ?")
+        .bind("carol")
+        .fetch_optional_as(&pool)
+        .await
+        .unwrap();
+    assert_eq!(missing, None);
+
+    // JSON columns deserialize into nested types
+    Query::new("INSERT INTO json_documents (payload, meta) VALUES (?, ?)")
+        .bind(rdbi::Json(JsonPayload {
+            title: "hello".to_string(),
+            tags: vec!["a".to_string()],
+        }))
+        .bind(serde_json::json!({ "views": 3 }))
+        .execute(&pool)
+        .await
+        .unwrap();
+    let doc: DocumentRow = rdbi::DynamicQuery::new("SELECT payload, meta FROM json_documents")
+        .fetch_one_as(&pool)
+        .await
+        .unwrap();
+    assert_eq!(doc.payload.title, "hello");
+    assert_eq!(doc.payload.tags, vec!["a"]);
+    assert_eq!(doc.meta.unwrap()["views"], 3);
+
+    // Type mismatches name the column
+    let err = Query::new("SELECT username AS id FROM users")
+        .fetch_all_as::<UserSummary, _>(&pool)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("column `id`"), "{}", err);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_serde_params_batch_insert() {
+    #[derive(serde::Serialize)]
+    struct NewUser {
+        #[serde(rename = "username")]
+        name: String,
+        email: String,
+        status: UsersStatus,
+        age: Option<u32>,
+        birth_date: Option<chrono::NaiveDate>,
+        // Not a column
+        #[serde(skip)]
+        #[allow(dead_code)]
+        invited_by: Option<String>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct NewDocument {
+        // Nested types are bound as JSON
+        payload: JsonPayload,
+        meta: Option<serde_json::Value>,
+    }
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    let users = vec![
+        NewUser {
+            name: "alice".to_string(),
+            email: "alice@example.com".to_string(),
+            status: UsersStatus::Active,
+            age: Some(30),
+            birth_date: chrono::NaiveDate::from_ymd_opt(1994, 5, 6),
+            invited_by: None,
+        },
+        NewUser {
+            name: "bob".to_string(),
+            email: "bob@example.com".to_string(),
+            status: UsersStatus::Pending,
+            age: None,
+            birth_date: None,
+            invited_by: Some("alice".to_string()),
+        },
+    ];
+    let params = rdbi::SerdeParams::from_slice(&users).unwrap();
+    let inserted = rdbi::BatchInsert::new("users", &params)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(inserted.rows_affected, 2);
+
+    let rows: Vec<(String, UsersStatus, Option<u32>, Option<chrono::NaiveDate>)> =
+        Query::new("SELECT username, status, age, birth_date FROM users ORDER BY id")
+            .fetch_all_as(&pool)
+            .await
+            .unwrap();
+    assert_eq!(rows[0].0, "alice");
+    assert_eq!(rows[0].1, UsersStatus::Active);
+    assert_eq!(rows[0].2, Some(30));
+    assert_eq!(rows[0].3, chrono::NaiveDate::from_ymd_opt(1994, 5, 6));
+    assert_eq!(rows[1].0, "bob");
+    assert_eq!(rows[1].2, None);
+
+    let docs = [NewDocument {
+        payload: JsonPayload {
+            title: "hello".to_string(),
+            tags: vec!["a".to_string(), "b".to_string()],
+        },
+        meta: Some(serde_json::json!({ "views": 3 })),
+    }];
+    rdbi::BatchInsert::new(
+        "json_documents",
+        &rdbi::SerdeParams::from_slice(&docs).unwrap(),
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let doc = dao::json_documents::find_all(&pool)
+        .await
+        .unwrap()
+        .remove(0);
+    assert_eq!(doc.payload.tags, vec!["a", "b"]);
+    assert_eq!(doc.meta.unwrap()["views"], 3);
+
+    // Only structs map to columns
+    let err = rdbi::SerdeParams::new(&42i64).unwrap_err();
+    assert!(matches!(err, rdbi::Error::ParamEncode(_)), "{}", err);
+
+    // Rows of a batch must serialize the same columns
+    #[derive(serde::Serialize)]
+    struct Partial {
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        age: Option<u32>,
+    }
+    let partial = [
+        Partial {
+            name: "carol".to_string(),
+            age: Some(40),
+        },
+        Partial {
+            name: "dave".to_string(),
+            age: None,
+        },
+    ];
+    let err = rdbi::SerdeParams::from_slice(&partial).unwrap_err();
+    assert!(matches!(err, rdbi::Error::ParamEncode(_)), "{}", err);
+    let mixed = [
+        rdbi::SerdeParams::new(&partial[0]).unwrap(),
+        rdbi::SerdeParams::new(&partial[1]).unwrap(),
+    ];
+    let err = rdbi::BatchInsert::new("users", &mixed)
+        .execute(&pool)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, rdbi::Error::ParamEncode(_)), "{}", err);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_fetch_all_rows() {
+    use rdbi::{ColumnType, Value};
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    Query::new(
+        "INSERT INTO users (username, email, status, is_active, age) VALUES \
+         ('alice', 'alice@example.com', 'ACTIVE', 1, 30)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    Query::new("INSERT INTO json_documents (payload) VALUES ('{\"title\": \"hi\", \"tags\": []}')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let rows = Query::new(
+        "SELECT u.username, u.status, u.age, d.payload, u.id, d.id \
+         FROM users u CROSS JOIN json_documents d",
+    )
+    .fetch_all_rows(&pool)
+    .await
+    .unwrap();
+    assert_eq!(rows.len(), 1);
+    let row = &rows[0];
+
+    // Columns keep select order, repeats included
+    let names: Vec<&str> = row.columns().iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["username", "status", "age", "payload", "id", "id"]);
+    let types: Vec<ColumnType> = row.columns().iter().map(|c| c.column_type).collect();
+    assert_eq!(
+        types,
+        [
+            ColumnType::VarChar,
+            ColumnType::Enum,
+            ColumnType::Int,
+            ColumnType::Json,
+            ColumnType::BigInt,
+            ColumnType::BigInt
+        ]
+    );
+    assert_eq!(row.columns()[0].table, "u");
+    assert!(!row.columns()[0].nullable);
+    assert!(row.columns()[2].nullable);
+    assert!(row.columns()[2].unsigned);
+    assert_eq!(row.get_index(0), Some(&Value::String("alice".to_string())));
+    assert_eq!(
+        row.get_by_name("status"),
+        Some(&Value::String("ACTIVE".to_string()))
+    );
+
+    // Typed access still works by name
+    let age: Option<u32> = rdbi::RowExt::get(row, "age").unwrap();
+    assert_eq!(age, Some(30));
+
+    let map = row.to_index_map();
+    assert_eq!(
+        map.keys().collect::<Vec<_>>(),
+        ["username", "status", "age", "payload", "id"]
+    );
+
+    let json = row.to_json_map();
+    assert_eq!(json["username"], "alice");
+    assert_eq!(json["age"], 30);
+    assert_eq!(json["payload"]["title"], "hi");
+
+    // Expressions have no table and report their computed type
+    let row = rdbi::DynamicQuery::new("SELECT 1 + 1 AS two, NULL AS nothing")
+        .fetch_all_rows(&pool)
+        .await
+        .unwrap()
+        .remove(0);
+    assert_eq!(row.columns()[0].table, "");
+    assert_eq!(row.columns()[1].column_type, ColumnType::Null);
+    assert_eq!(row.get_by_name("nothing"), Some(&Value::Null));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_export_streams() {
+    use rdbi::export::{CsvWriter, NdjsonWriter, RecordBatchWriter};
+    use rdbi::Transaction;
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    Query::new(
+        "INSERT INTO users (username, email, first_name, status, age) VALUES \
+         ('alice', 'alice@example.com', 'Alice \"Al\", Jr.', 'ACTIVE', 30), \
+         ('bob', 'bob@example.com', '', 'PENDING', NULL), \
+         ('carol', 'carol@example.com', NULL, 'INACTIVE', 41)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let sql = "SELECT username, first_name, status, age FROM users ORDER BY id";
+
+    let mut csv = CsvWriter::new(Vec::new()).null_value("\\N");
+    Query::new(sql).stream_rows(&pool, &mut csv).await.unwrap();
+    assert_eq!(csv.rows_written(), 3);
+    let text = String::from_utf8(csv.into_inner()).unwrap();
+    assert_eq!(
+        text,
+        "username,first_name,status,age\r\n\
+         alice,\"Alice \"\"Al\"\", Jr.\",ACTIVE,30\r\n\
+         bob,,PENDING,\\N\r\n\
+         carol,\\N,INACTIVE,41\r\n"
+    );
+
+    // Header only for an empty result
+    let mut csv = CsvWriter::new(Vec::new());
+    Query::new("SELECT username, age FROM users WHERE id < 0")
+        .stream_rows(&pool, &mut csv)
+        .await
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(csv.into_inner()).unwrap(),
+        "username,age\r\n"
+    );
+
+    let mut ndjson = NdjsonWriter::new(Vec::new());
+    Query::new(sql)
+        .stream_rows(&pool, &mut ndjson)
+        .await
+        .unwrap();
+    let text = String::from_utf8(ndjson.into_inner()).unwrap();
+    let lines: Vec<serde_json::Value> = text
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(
+        lines[1],
+        serde_json::json!({ "username": "bob", "first_name": "", "status": "PENDING", "age": null })
+    );
+
+    // Transactions stream too
+    let mut batches = Vec::new();
+    let mut arrow = RecordBatchWriter::new(|batch| {
+        batches.push(batch);
+        Ok(())
+    })
+    .batch_size(2);
+    let tx = pool.begin().await.unwrap();
+    Query::new(sql).stream_rows(&tx, &mut arrow).await.unwrap();
+    tx.commit().await.unwrap();
+    arrow.finish().unwrap();
+    let schema = arrow.schema().unwrap();
+    drop(arrow);
+    assert_eq!(
+        batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+        [2, 1]
+    );
+    assert_eq!(schema.field(3).data_type().to_string(), "UInt32");
+    assert!(!schema.field(0).is_nullable());
+    assert!(schema.field(3).is_nullable());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_fetch_collections() {
+    use std::collections::HashMap;
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    Query::new(
+        "INSERT INTO users (username, email, status) VALUES \
+         ('alice', 'alice@example.com', 'ACTIVE'), \
+         ('bob', 'bob@example.com', 'PENDING'), \
+         ('carol', 'carol@example.com', 'ACTIVE')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let ids: HashMap<String, Users> = Query::new("SELECT * FROM users")
+        .fetch_map(&pool, |u: &Users| u.username.clone())
+        .await
+        .unwrap();
+    assert_eq!(ids.len(), 3);
+    let (alice, bob, carol) = (ids["alice"].id, ids["bob"].id, ids["carol"].id);
+
+    let by_status: HashMap<UsersStatus, Vec<Users>> = Query::new("SELECT * FROM users ORDER BY id")
+        .fetch_grouped(&pool, |u: &Users| u.status)
+        .await
+        .unwrap();
+    let active: Vec<&str> = by_status[&UsersStatus::Active]
+        .iter()
+        .map(|u| u.username.as_str())
+        .collect();
+    assert_eq!(active, ["alice", "carol"]);
+    assert_eq!(by_status[&UsersStatus::Pending].len(), 1);
+    assert!(!by_status.contains_key(&UsersStatus::Inactive));
+
+    for (user_id, key) in [(alice, "theme"), (alice, "lang"), (carol, "theme")] {
+        Query::new(
+            "INSERT INTO user_settings (user_id, setting_key, setting_value) VALUES (?, ?, ?)",
+        )
+        .bind(user_id)
+        .bind(key)
+        .bind(format!("{}-{}", key, user_id))
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    // users and user_settings share created_at/updated_at; parents read the first
+    let users: Vec<(Users, Vec<UserSettings>)> = rdbi::DynamicQuery::new(
+        "SELECT u.*, s.* FROM users u \
+         LEFT JOIN user_settings s ON s.user_id = u.id \
+         ORDER BY u.id, s.setting_key",
+    )
+    .fetch_one_to_many(&pool, |u: &Users| u.id)
+    .await
+    .unwrap();
+    assert_eq!(users.len(), 3);
+    let (user, settings) = &users[0];
+    assert_eq!(user.id, alice);
+    assert!(user.created_at.is_some());
+    let keys: Vec<&str> = settings.iter().map(|s| s.setting_key.as_str()).collect();
+    assert_eq!(keys, ["lang", "theme"]);
+    assert_eq!(settings[0].user_id, alice);
+    assert_eq!(users[1].0.id, bob);
+    assert!(users[1].1.is_empty());
+    assert_eq!(users[2].0.id, carol);
+    assert_eq!(users[2].1.len(), 1);
+    assert_eq!(
+        users[2].1[0].setting_value.as_deref(),
+        Some(format!("theme-{}", carol).as_str())
+    );
+
+    // The parent's created_at is not taken for a child when the child's own
+    // columns are not selected
+    let users: Vec<(Users, Vec<UserSettings>)> =
+        rdbi::DynamicQuery::new("SELECT u.* FROM users u ORDER BY u.id")
+            .fetch_one_to_many(&pool, |u: &Users| u.id)
+            .await
+            .unwrap();
+    assert_eq!(users.len(), 3);
+    assert!(users.iter().all(|(_, settings)| settings.is_empty()));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_max_rows_guard() {
+    use rdbi::Transaction;
+
+    let pool = MySqlPool::builder(get_db_url())
+        .max_rows(2)
+        .build()
+        .unwrap();
+    clean_all_tables(&pool).await;
+
+    Query::new(
+        "INSERT INTO users (username, email) VALUES \
+         ('a', 'a@example.com'), ('b', 'b@example.com'), ('c', 'c@example.com')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // Generated find_all goes through the pool's limit
+    let err = dao::users::find_all(&pool).await.unwrap_err();
+    assert!(
+        matches!(err, rdbi::Error::TooManyRows { max: 2 }),
+        "{}",
+        err
+    );
+
+    // ...or a limit of its own
+    let err = dao::users::find_all_limited(&pool, 1).await.unwrap_err();
+    assert!(
+        matches!(err, rdbi::Error::TooManyRows { max: 1 }),
+        "{}",
+        err
+    );
+    assert_eq!(
+        dao::users::find_all_limited(&pool, 3).await.unwrap().len(),
+        3
+    );
+
+    // Exactly at the limit is fine
+    let two: Vec<Users> = Query::new("SELECT * FROM users ORDER BY id LIMIT 2")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(two.len(), 2);
+
+    // Per-query overrides
+    let all: Vec<Users> = Query::new("SELECT * FROM users")
+        .unlimited_rows()
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 3);
+    let rows = rdbi::DynamicQuery::new("SELECT username FROM users")
+        .max_rows(10)
+        .fetch_all_rows(&pool)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 3);
+    let err = Query::new("SELECT username FROM users")
+        .max_rows(1)
+        .fetch_all_as::<(String,), _>(&pool)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, rdbi::Error::TooManyRows { max: 1 }),
+        "{}",
+        err
+    );
+
+    // Transactions share the pool's limit, and stay usable after an abort
+    let tx = pool.begin().await.unwrap();
+    let err = dao::users::find_all(&tx).await.unwrap_err();
+    assert!(
+        matches!(err, rdbi::Error::TooManyRows { max: 2 }),
+        "{}",
+        err
+    );
+    let count: i64 = Query::new("SELECT COUNT(*) FROM users")
+        .fetch_scalar(&tx)
+        .await
+        .unwrap();
+    assert_eq!(count, 3);
+    tx.rollback().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_unprepared_queries() {
+    use rdbi::{Pool, Transaction};
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    // Values that need escaping round-trip through inlined literals
+    let tricky = "o'brien \\ \"quoted\" \n -- ? /*";
+    let result = Query::new("INSERT INTO users (username, email) VALUES (?, ?)")
+        .bind(tricky)
+        .bind("plain@example.com")
+        .unprepared()
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(result.rows_affected, 1);
+    assert!(result.last_insert_id.unwrap() > 0);
+
+    let user: Users = Query::new("SELECT * FROM users WHERE username = ? AND email = ?")
+        .bind(tricky)
+        .bind("plain@example.com")
+        .unprepared()
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(user.username, tricky);
+    assert_eq!(user.status, UsersStatus::Active);
+
+    let users: Vec<Users> = Query::new("SELECT * FROM users WHERE id = ?")
+        .bind(user.id)
+        .unprepared()
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(users.len(), 1);
+    let missing: Option<Users> = Query::new("SELECT * FROM users WHERE id = ?")
+        .bind(-1)
+        .unprepared()
+        .fetch_optional(&pool)
+        .await
+        .unwrap();
+    assert!(missing.is_none());
+
+    // Placeholders must match the bound values
+    let err = Query::new("SELECT ? + ?")
+        .bind(1)
+        .unprepared()
+        .fetch_scalar::<i64, _>(&pool)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, rdbi::Error::Query(_)), "{}", err);
+
+    // A single connection for session state and table locks
+    let tx = pool.begin().await.unwrap();
+    Query::new("SET SESSION sql_mode = CONCAT(@@sql_mode, ',NO_BACKSLASH_ESCAPES')")
+        .execute(&tx)
+        .await
+        .unwrap();
+    let echoed: String = Query::new("SELECT ?")
+        .bind(tricky)
+        .unprepared()
+        .fetch_scalar(&tx)
+        .await
+        .unwrap();
+    assert_eq!(echoed, tricky);
+    let date = chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+    let echoed: chrono::NaiveDate = Query::new("SELECT CAST(? AS DATE)")
+        .bind(date)
+        .unprepared()
+        .fetch_scalar(&tx)
+        .await
+        .unwrap();
+    assert_eq!(echoed, date);
+    let price = rust_decimal::Decimal::new(1250, 2);
+    let echoed: rust_decimal::Decimal = Query::new("SELECT CAST(? AS DECIMAL(10, 2))")
+        .bind(price)
+        .unprepared()
+        .fetch_scalar(&tx)
+        .await
+        .unwrap();
+    assert_eq!(echoed, price);
+    Query::new("SET SESSION sql_mode = REPLACE(@@sql_mode, 'NO_BACKSLASH_ESCAPES', '')")
+        .execute(&tx)
+        .await
+        .unwrap();
+
+    // LOCK TABLES cannot be prepared
+    Query::new("LOCK TABLES users READ")
+        .unprepared()
+        .execute(&tx)
+        .await
+        .unwrap();
+    let count: i64 = Query::new("SELECT COUNT(*) FROM users")
+        .unprepared()
+        .fetch_scalar(&tx)
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+    Query::new("UNLOCK TABLES")
+        .unprepared()
+        .execute(&tx)
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+
+    // Scripts report one result per statement
+    let results = pool
+        .execute_script(
+            "INSERT INTO users (username, email) VALUES ('s1', 's1@example.com'); \
+             SELECT COUNT(*) FROM users; \
+             UPDATE users SET first_name = 'x' WHERE username LIKE 's%'; \
+             DELETE FROM users WHERE username = 'nobody'",
+        )
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].rows_affected, 1);
+    assert!(results[0].last_insert_id.unwrap() > user.id as u64);
+    assert_eq!(results[2].rows_affected, 1);
+    assert_eq!(results[3].rows_affected, 0);
+
+    // A failing statement stops the script
+    let err = pool
+        .execute_script(
+            "UPDATE users SET first_name = 'y'; SELECT * FROM no_such_table; DELETE FROM users",
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, rdbi::Error::MySql(_)), "{}", err);
+    let count: i64 = Query::new("SELECT COUNT(*) FROM users")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+}
+
 #[tokio::test]
 #[serial]
-async fn test_insert_and_find_by_id() {
-    let pool = MySqlPool::new(get_db_url()).unwrap();
+async fn test_prepared_statements() {
+    use rdbi::{ToValue, Transaction};
+
+    // A single connection makes cache hits predictable
+    let pool = MySqlPool::builder(get_db_url())
+        .pool_min(1)
+        .pool_max(1)
+        .stmt_cache_size(4)
+        .build()
+        .unwrap();
     clean_all_tables(&pool).await;
 
-    // Insert a user
-    let user = Users {
-        id: 0, // Will be auto-generated
-        username: "testuser".to_string(),
-        email: "test@example.com".to_string(),
-        first_name: Some("Test".to_string()),
-        last_name: Some("User".to_string()),
-        status: UsersStatus::Active,
-        is_active: true,
-        age: Some(25),
-        created_at: None,
-        updated_at: None,
-        birth_date: None,
-        login_time: None,
-    };
+    let mut insert = pool
+        .prepare("INSERT INTO users (username, email) VALUES (?, ?)")
+        .await
+        .unwrap();
+    assert_eq!(insert.param_count(), 2);
+    let result = insert
+        .execute_batch((1..=3).map(|i| {
+            vec![
+                format!("p{}", i).to_value(),
+                format!("p{}@example.com", i).to_value(),
+            ]
+        }))
+        .await
+        .unwrap();
+    assert_eq!(result.rows_affected, 3);
+    let last_id = result.last_insert_id.unwrap();
+    let one = insert
+        .execute(vec!["p4".to_value(), "p4@example.com".to_value()])
+        .await
+        .unwrap();
+    assert_eq!(one.rows_affected, 1);
+    assert!(one.last_insert_id.unwrap() > last_id);
+    insert.close().await.unwrap();
 
-    let last_id = dao::users::insert(&pool, &user).await.unwrap();
-    assert!(last_id > 0);
+    let mut find = pool
+        .prepare("SELECT * FROM users WHERE username LIKE ? ORDER BY id")
+        .await
+        .unwrap();
+    let users: Vec<Users> = find.fetch_all(vec!["p%".to_value()]).await.unwrap();
+    assert_eq!(users.len(), 4);
+    let user: Users = find.fetch_one(vec!["p2".to_value()]).await.unwrap();
+    assert_eq!(user.email, "p2@example.com");
+    let missing: Option<Users> = find.fetch_optional(vec!["x%".to_value()]).await.unwrap();
+    assert!(missing.is_none());
+    drop(find);
+
+    let mut count = pool
+        .prepare("SELECT COUNT(*) FROM users WHERE id > ?")
+        .await
+        .unwrap();
+    let n: i64 = count.fetch_scalar(vec![last_id.to_value()]).await.unwrap();
+    assert_eq!(n, 1);
+    count.close().await.unwrap();
+
+    // Repeats within one checkout hit the cache
+    let sql = "SELECT COUNT(*) FROM users WHERE username = ?";
+    let before = pool.stmt_cache_stats();
+    let tx = pool.begin().await.unwrap();
+    for name in ["p1", "p2", "p3"] {
+        let n: i64 = Query::new(sql).bind(name).fetch_scalar(&tx).await.unwrap();
+        assert_eq!(n, 1);
+    }
 
-    // Find by ID
-    let found = dao::users::find_by_id(&pool, last_id as i64).await.unwrap();
-    assert!(found.is_some());
-    let found = found.unwrap();
-    assert_eq!(found.username, "testuser");
-    assert_eq!(found.email, "test@example.com");
-    assert_eq!(found.status, UsersStatus::Active);
+    // SQL that fails to prepare is not counted, so a retry is no hit
+    for _ in 0..2 {
+        let err = Query::new("SELECT * FROM no_such_table")
+            .fetch_all::<Users, _>(&tx)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, rdbi::Error::MySql(_)), "{}", err);
+    }
+    tx.rollback().await.unwrap();
+    let after = pool.stmt_cache_stats();
+    assert_eq!(after.estimated_misses - before.estimated_misses, 1);
+    assert_eq!(after.estimated_hits - before.estimated_hits, 2);
+
+    // Connections are reset when they go back to the pool, clearing their cache
+    let pool = MySqlPool::builder(get_db_url())
+        .pool_min(1)
+        .pool_max(1)
+        .build()
+        .unwrap();
+    for _ in 0..2 {
+        let _: i64 = Query::new(sql)
+            .bind("p1")
+            .fetch_scalar(&pool)
+            .await
+            .unwrap();
+    }
+    let stats = pool.stmt_cache_stats();
+    assert_eq!((stats.estimated_hits, stats.estimated_misses), (0, 2));
+    let tx = pool.begin().await.unwrap();
+    for _ in 0..2 {
+        let _: i64 = Query::new(sql).bind("p1").fetch_scalar(&tx).await.unwrap();
+    }
+    tx.rollback().await.unwrap();
+    let stats = pool.stmt_cache_stats();
+    assert_eq!((stats.estimated_hits, stats.estimated_misses), (1, 3));
+    assert!((stats.estimated_hit_ratio() - 0.25).abs() < f64::EPSILON);
+
+    // Without resets, statements stay cached across checkouts
+    let pool = MySqlPool::builder(get_db_url())
+        .pool_min(1)
+        .pool_max(1)
+        .reset_connection(false)
+        .build()
+        .unwrap();
+    for _ in 0..3 {
+        let _: i64 = Query::new(sql)
+            .bind("p1")
+            .fetch_scalar(&pool)
+            .await
+            .unwrap();
+    }
+    let stats = pool.stmt_cache_stats();
+    assert_eq!((stats.estimated_hits, stats.estimated_misses), (2, 1));
 }
 
 #[tokio::test]
 #[serial]
-async fn test_update_and_delete() {
+async fn test_execute_many() {
+    use rdbi::{DynamicQuery, ExecuteMode, OnError, Pool, Transaction};
+
     let pool = MySqlPool::new(get_db_url()).unwrap();
     clean_all_tables(&pool).await;
 
-    // Insert
-    let user = Users {
-        id: 0,
-        username: "updateuser".to_string(),
-        email: "update@example.com".to_string(),
-        first_name: Some("Update".to_string()),
-        last_name: Some("Test".to_string()),
-        status: UsersStatus::Pending,
-        is_active: true,
-        age: Some(30),
-        created_at: None,
-        updated_at: None,
-        birth_date: None,
-        login_time: None,
+    let insert = |name: &str| {
+        DynamicQuery::new("INSERT INTO users (username, email) VALUES (?, ?)")
+            .bind(name)
+            .bind(format!("{}@example.com", name))
+    };
+    let count = |pool: &MySqlPool| {
+        let pool = pool.clone();
+        async move {
+            Query::new("SELECT COUNT(*) FROM users")
+                .fetch_scalar::<i64, _>(&pool)
+                .await
+                .unwrap()
+        }
     };
 
-    let last_id = dao::users::insert(&pool, &user).await.unwrap();
-
-    // Update
-    let mut updated = dao::users::find_by_id(&pool, last_id as i64)
+    // All succeed
+    let results = pool
+        .execute_many(
+            vec![
+                insert("m1"),
+                insert("m2;"),
+                DynamicQuery::new("UPDATE users SET first_name = ? WHERE username LIKE 'm%';")
+                    .bind("it's"),
+            ],
+            ExecuteMode::Transaction,
+            OnError::Stop,
+        )
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 3);
+    let first = results[0].as_ref().unwrap();
+    assert_eq!(first.rows_affected, 1);
+    assert!(first.last_insert_id.unwrap() > 0);
+    assert_eq!(results[2].as_ref().unwrap().rows_affected, 2);
+    assert_eq!(count(&pool).await, 2);
+
+    // A duplicate key stops the batch and rolls it back
+    let results = pool
+        .execute_many(
+            vec![insert("m3"), insert("m1"), insert("m4")],
+            ExecuteMode::Transaction,
+            OnError::Stop,
+        )
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(matches!(results[1], Err(rdbi::Error::MySql(_))));
+    assert_eq!(count(&pool).await, 2);
+
+    // In autocommit, statements before the failure stay
+    let results = pool
+        .execute_many(
+            vec![insert("m3"), insert("m1"), insert("m4")],
+            ExecuteMode::Autocommit,
+            OnError::Stop,
+        )
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(count(&pool).await, 3);
+
+    // Continue runs the rest, including after a bad parameter count
+    let results = pool
+        .execute_many(
+            vec![
+                insert("m5"),
+                insert("m1"),
+                DynamicQuery::new("DELETE FROM users WHERE username = ?"),
+                insert("m6"),
+            ],
+            ExecuteMode::Transaction,
+            OnError::Continue,
+        )
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 4);
+    assert!(results[0].is_ok());
+    assert!(matches!(results[1], Err(rdbi::Error::MySql(_))));
+    assert!(matches!(results[2], Err(rdbi::Error::Query(_))));
+    assert!(results[3].is_ok());
+    assert_eq!(count(&pool).await, 5);
+
+    // Within a transaction, a savepoint scopes the rollback
+    let tx = pool.begin().await.unwrap();
+    tx.execute_many(vec![insert("t1")], ExecuteMode::Autocommit, OnError::Stop)
         .await
         .unwrap()
+        .remove(0)
         .unwrap();
-    updated.status = UsersStatus::Active;
-    updated.first_name = Some("Updated".to_string());
+    let results = tx
+        .execute_many(
+            vec![insert("t2"), insert("t1")],
+            ExecuteMode::Transaction,
+            OnError::Stop,
+        )
+        .await
+        .unwrap();
+    assert!(results[1].is_err());
+    let in_tx: i64 = Query::new("SELECT COUNT(*) FROM users")
+        .fetch_scalar(&tx)
+        .await
+        .unwrap();
+    assert_eq!(in_tx, 6);
+    tx.commit().await.unwrap();
+    assert_eq!(count(&pool).await, 6);
+
+    // A statement failing in the middle of a script gets its own error, under
+    // each policy, and does not leak into the connection's next query
+    let batch = || {
+        vec![
+            insert("f1"),
+            DynamicQuery::new("UPDATE no_such_table SET x = 1"),
+            insert("f2"),
+        ]
+    };
+    for mode in [ExecuteMode::Transaction, ExecuteMode::Autocommit] {
+        let results = pool
+            .execute_many(batch(), mode, OnError::Stop)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2, "{:?}", mode);
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(rdbi::Error::MySql(_))));
+        let expected = if mode == ExecuteMode::Transaction {
+            6
+        } else {
+            7
+        };
+        assert_eq!(count(&pool).await, expected, "{:?}", mode);
+        Query::new("DELETE FROM users WHERE username LIKE 'f%'")
+            .execute(&pool)
+            .await
+            .unwrap();
 
-    let rows = dao::users::update(&pool, &updated).await.unwrap();
-    assert_eq!(rows, 1);
+        let results = pool
+            .execute_many(batch(), mode, OnError::Continue)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 3, "{:?}", mode);
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(rdbi::Error::MySql(_))));
+        assert_eq!(results[2].as_ref().unwrap().rows_affected, 1);
+        assert_eq!(count(&pool).await, 8, "{:?}", mode);
+        Query::new("DELETE FROM users WHERE username LIKE 'f%'")
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
 
-    // Verify update
-    let fetched = dao::users::find_by_id(&pool, last_id as i64)
+    let tx = pool.begin().await.unwrap();
+    let results = tx
+        .execute_many(batch(), ExecuteMode::Transaction, OnError::Stop)
         .await
-        .unwrap()
         .unwrap();
-    assert_eq!(fetched.status, UsersStatus::Active);
-    assert_eq!(fetched.first_name, Some("Updated".to_string()));
-
-    // Delete
-    let deleted = dao::users::delete_by_id(&pool, last_id as i64)
+    assert_eq!(results.len(), 2);
+    assert!(results[1].is_err());
+    let in_tx: i64 = Query::new("SELECT COUNT(*) FROM users")
+        .fetch_scalar(&tx)
         .await
         .unwrap();
-    assert_eq!(deleted, 1);
-
-    // Verify delete
-    let not_found = dao::users::find_by_id(&pool, last_id as i64).await.unwrap();
-    assert!(not_found.is_none());
+    assert_eq!(in_tx, 6);
+    tx.rollback().await.unwrap();
 }
 
 #[tokio::test]
 #[serial]
-async fn test_insert_all_batch() {
-    let pool = MySqlPool::new(get_db_url()).unwrap();
-    clean_all_tables(&pool).await;
-
-    let products = vec![
-        Products {
-            sku: "SKU001".to_string(),
-            name: "Product 1".to_string(),
-            price: rust_decimal::Decimal::new(1999, 2), // 19.99
-            stock: Some(100),
-            status: Some(ProductsStatus::Active),
-        },
-        Products {
-            sku: "SKU002".to_string(),
-            name: "Product 2".to_string(),
-            price: rust_decimal::Decimal::new(2999, 2), // 29.99
-            stock: Some(50),
-            status: Some(ProductsStatus::Active),
-        },
-        Products {
-            sku: "SKU003".to_string(),
-            name: "Product 3".to_string(),
-            price: rust_decimal::Decimal::new(999, 2), // 9.99
-            stock: Some(200),
-            status: Some(ProductsStatus::OutOfStock),
-        },
-    ];
-
-    let rows = dao::products::insert_all(&pool, &products).await.unwrap();
-    assert_eq!(rows, 3);
-
-    // Verify
-    let all = dao::products::find_all(&pool).await.unwrap();
-    assert_eq!(all.len(), 3);
-}
+async fn test_execute_result_details() {
+    use rdbi::{Error, Pool, Transaction, WarningMode};
 
-#[tokio::test]
-#[serial]
-async fn test_upsert() {
     let pool = MySqlPool::new(get_db_url()).unwrap();
     clean_all_tables(&pool).await;
 
-    // Insert first
-    let product = Products {
-        sku: "UPSERT001".to_string(),
-        name: "Original Name".to_string(),
-        price: rust_decimal::Decimal::new(1000, 2),
-        stock: Some(10),
-        status: Some(ProductsStatus::Active),
-    };
-
-    dao::products::insert(&pool, &product).await.unwrap();
-
-    // Upsert with same SKU but different data
-    let updated_product = Products {
-        sku: "UPSERT001".to_string(),
-        name: "Updated Name".to_string(),
-        price: rust_decimal::Decimal::new(1500, 2),
-        stock: Some(20),
-        status: Some(ProductsStatus::Active),
-    };
+    pool.execute(
+        "INSERT INTO users (username, email, first_name) VALUES ('d1', 'd1@example.com', 'Ann')",
+        vec![],
+    )
+    .await
+    .unwrap();
 
-    dao::products::upsert(&pool, &updated_product)
+    // An UPDATE to the same value matches a row without changing it
+    let result = pool
+        .execute(
+            "UPDATE users SET first_name = 'Ann' WHERE username = 'd1'",
+            vec![],
+        )
         .await
         .unwrap();
+    assert_eq!(result.rows_affected, 0);
+    assert_eq!(result.matched_rows, Some(1));
+    assert_eq!(result.changed_rows, Some(0));
+    assert!(result.info.contains("Rows matched: 1"));
+
+    // With CLIENT_FOUND_ROWS, matched rows count as affected
+    let found_rows = MySqlPool::builder(get_db_url())
+        .client_found_rows(true)
+        .build()
+        .unwrap();
+    let result = found_rows
+        .execute(
+            "UPDATE users SET first_name = 'Ann' WHERE username = 'd1'",
+            vec![],
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.rows_affected, 1);
 
-    // Verify the update happened
-    let found = dao::products::find_by_sku(&pool, "UPSERT001")
+    // Truncation under a non-strict sql_mode is a warning...
+    let fetching = MySqlPool::builder(get_db_url())
+        .warning_mode(WarningMode::Fetch)
+        .build()
+        .unwrap();
+    let tx = fetching.begin().await.unwrap();
+    tx.execute("SET SESSION sql_mode = ''", vec![])
         .await
-        .unwrap()
         .unwrap();
-    assert_eq!(found.name, "Updated Name");
-    assert_eq!(found.price, rust_decimal::Decimal::new(1500, 2));
-    assert_eq!(found.stock, Some(20));
+    let result = tx
+        .execute(
+            "UPDATE users SET first_name = ? WHERE username = 'd1'",
+            vec!["x".repeat(150).into()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.warnings, 1);
+    assert_eq!(result.warning_details.len(), 1);
+    assert!(result.warning_details[0].is_truncation());
+    tx.rollback().await.unwrap();
+
+    // ...which TruncationAsError turns into an error
+    let strict = MySqlPool::builder(get_db_url())
+        .warning_mode(WarningMode::TruncationAsError)
+        .build()
+        .unwrap();
+    let tx = strict.begin().await.unwrap();
+    tx.execute("SET SESSION sql_mode = ''", vec![])
+        .await
+        .unwrap();
+    let err = tx
+        .execute(
+            "UPDATE users SET first_name = ? WHERE username = 'd1'",
+            vec!["x".repeat(150).into()],
+        )
+        .await
+        .unwrap_err();
+    match err {
+        Error::Truncated(warnings) => assert_eq!(warnings[0].code, 1265),
+        other => panic!("expected Truncated, got {:?}", other),
+    }
+    tx.rollback().await.unwrap();
 }
 
 // ============ Query Tests ============
@@ -519,6 +2743,7 @@ async fn test_pagination() {
 
 #[tokio::test]
 #[serial]
+#[allow(clippy::approx_constant)] // arbitrary fixtures, not meant as PI and E
 async fn test_all_integer_types() {
     let pool = MySqlPool::new(get_db_url()).unwrap();
     clean_all_tables(&pool).await;
@@ -1639,7 +3864,7 @@ async fn test_bulk_find_by_statuses() {
             email: format!("status{}@example.com", i),
             first_name: None,
             last_name: None,
-            status: status.clone(),
+            status: *status,
             is_active: true,
             age: None,
             created_at: None,
@@ -2022,7 +4247,7 @@ async fn test_transaction_with_macro_isolation_level() {
 #[serial]
 async fn test_error_other_variant() {
     // Test that Error::Other can wrap arbitrary errors
-    let custom_err = std::io::Error::new(std::io::ErrorKind::Other, "custom io error");
+    let custom_err = std::io::Error::other("custom io error");
     let rdbi_err = rdbi::Error::Other(Box::new(custom_err));
     assert_eq!(rdbi_err.to_string(), "custom io error");
 
@@ -2056,6 +4281,179 @@ async fn test_with_connection_macro() {
     assert_eq!(count, 0);
 }
 
+#[tokio::test]
+#[serial]
+async fn test_transaction_options() {
+    use rdbi::{Error, Pool, Transaction, TransactionOptions, Transactional};
+    use std::time::Duration;
+
+    let pool = MySqlPool::new(get_db_url()).unwrap();
+    clean_all_tables(&pool).await;
+
+    // Read-only transactions reject writes
+    let result = rdbi::in_transaction_with!(pool, TransactionOptions::new().read_only(), |tx| {
+        tx.execute(
+            "INSERT INTO users (username, email) VALUES ('ro', 'ro@example.com')",
+            vec![],
+        )
+        .await?;
+        Ok(())
+    })
+    .await;
+    assert!(result.is_err());
+
+    // The lock wait timeout applies while the transaction is open, then is
+    // restored on the connection. Without resets the single connection keeps
+    // its session across checkouts, so only the restore puts it back.
+    let single = MySqlPool::builder(get_db_url())
+        .pool_min(1)
+        .pool_max(1)
+        .reset_connection(false)
+        .build()
+        .unwrap();
+    let lock_wait_timeout = "SELECT @@SESSION.innodb_lock_wait_timeout";
+    let default_timeout: i64 = Query::new(lock_wait_timeout)
+        .fetch_scalar(&single)
+        .await
+        .unwrap();
+    let options = TransactionOptions::new()
+        .isolation_level(rdbi::IsolationLevel::RepeatableRead)
+        .consistent_snapshot()
+        .lock_wait_timeout(Duration::from_secs(7));
+    let tx = single.begin_with_options(options).await.unwrap();
+    let timeout: i64 = Query::new(lock_wait_timeout)
+        .fetch_scalar(&tx)
+        .await
+        .unwrap();
+    assert_eq!(timeout, 7);
+    tx.commit().await.unwrap();
+    let restored: i64 = Query::new(lock_wait_timeout)
+        .fetch_scalar(&single)
+        .await
+        .unwrap();
+    assert_eq!(restored, default_timeout);
+
+    let tx = single
+        .begin_with_options(TransactionOptions::new().lock_wait_timeout(Duration::from_secs(5)))
+        .await
+        .unwrap();
+    tx.rollback().await.unwrap();
+    let restored: i64 = Query::new(lock_wait_timeout)
+        .fetch_scalar(&single)
+        .await
+        .unwrap();
+    assert_eq!(restored, default_timeout);
+
+    let count: i64 = single
+        .in_transaction_with(
+            TransactionOptions::new().lock_wait_timeout(Duration::from_secs(2)),
+            |tx| {
+                Box::pin(async move {
+                    Query::new("SELECT COUNT(*) FROM users")
+                        .fetch_scalar::<i64, _>(tx)
+                        .await
+                })
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+    let restored: i64 = Query::new(lock_wait_timeout)
+        .fetch_scalar(&single)
+        .await
+        .unwrap();
+    assert_eq!(restored, default_timeout);
+
+    // ...and when the transaction is dropped while open. The restore runs on
+    // a spawned task holding the only connection, so the next query waits
+    // for it.
+    let tx = single
+        .begin_with_options(TransactionOptions::new().lock_wait_timeout(Duration::from_secs(3)))
+        .await
+        .unwrap();
+    drop(tx);
+    let restored: i64 = Query::new(lock_wait_timeout)
+        .fetch_scalar(&single)
+        .await
+        .unwrap();
+    assert_eq!(restored, default_timeout);
+
+    // A deadline too far off to represent means none
+    let tx = pool
+        .begin_with_options(TransactionOptions::new().deadline(Duration::MAX))
+        .await
+        .unwrap();
+    tx.execute("SELECT 1", vec![]).await.unwrap();
+    tx.commit().await.unwrap();
+
+    // Past the deadline, statements fail and commit rolls back
+    let tx = pool
+        .begin_with_options(TransactionOptions::new().deadline(Duration::from_millis(50)))
+        .await
+        .unwrap();
+    tx.execute(
+        "INSERT INTO users (username, email) VALUES ('late', 'late@example.com')",
+        vec![],
+    )
+    .await
+    .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let err = tx.execute("SELECT 1", vec![]).await.unwrap_err();
+    assert!(matches!(err, Error::DeadlineExceeded));
+    let err = tx.commit().await.unwrap_err();
+    assert!(matches!(err, Error::DeadlineExceeded));
+    let count: i64 = Query::new("SELECT COUNT(*) FROM users")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+
+    // A statement still waiting at the deadline is stopped
+    pool.execute(
+        "INSERT INTO users (username, email) VALUES ('locked', 'locked@example.com')",
+        vec![],
+    )
+    .await
+    .unwrap();
+    let holder = pool.begin().await.unwrap();
+    holder
+        .execute(
+            "SELECT id FROM users WHERE username = 'locked' FOR UPDATE",
+            vec![],
+        )
+        .await
+        .unwrap();
+    let tx = pool
+        .begin_with_options(TransactionOptions::new().deadline(Duration::from_millis(500)))
+        .await
+        .unwrap();
+    tx.execute(
+        "INSERT INTO users (username, email) VALUES ('waiter', 'waiter@example.com')",
+        vec![],
+    )
+    .await
+    .unwrap();
+    let started = std::time::Instant::now();
+    let err = tx
+        .execute(
+            "UPDATE users SET email = 'late@example.com' WHERE username = 'locked'",
+            vec![],
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::DeadlineExceeded), "{}", err);
+    assert!(started.elapsed() < Duration::from_secs(5));
+    let err = tx.execute("SELECT 1", vec![]).await.unwrap_err();
+    assert!(matches!(err, Error::DeadlineExceeded));
+    drop(tx);
+    holder.rollback().await.unwrap();
+    let emails: String = Query::new("SELECT GROUP_CONCAT(email ORDER BY username) FROM users")
+        .fetch_scalar(&pool)
+        .await
+        .unwrap();
+    assert_eq!(emails, "locked@example.com");
+}
+
 #[tokio::test]
 #[serial]
 async fn test_transaction_macro_non_static_references() {
@@ -1,9 +1,42 @@
-//! Batch insert operations for rdbi
+//! Batch insert, upsert, update and delete operations for rdbi
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::ident::{quote_name, Ident, ToIdent};
 use crate::traits::{ExecuteResult, Pool, ToParams};
 use crate::value::Value;
 
+/// Alias given to the incoming row in `INSERT ... AS new ON DUPLICATE KEY UPDATE`.
+const ROW_ALIAS: &str = "new";
+
+/// Maximum number of `?` placeholders MySQL accepts in one prepared statement.
+const MAX_PLACEHOLDERS: usize = 65_535;
+
+/// Default number of entities per statement for chunked batch operations.
+const DEFAULT_CHUNK_SIZE: usize = 1_000;
+
+/// How a batch insert handles rows that collide with an existing unique key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OnConflict {
+    /// Fail the whole statement with a duplicate-key error (`INSERT INTO`).
+    #[default]
+    Error,
+    /// Silently skip conflicting rows (`INSERT IGNORE INTO`).
+    Ignore,
+    /// Delete the conflicting row and insert the new one (`REPLACE INTO`).
+    Replace,
+}
+
+impl OnConflict {
+    /// Get the statement prefix for this mode
+    fn insert_verb(&self) -> &'static str {
+        match self {
+            OnConflict::Error => "INSERT INTO",
+            OnConflict::Ignore => "INSERT IGNORE INTO",
+            OnConflict::Replace => "REPLACE INTO",
+        }
+    }
+}
+
 /// A batch insert builder for inserting multiple entities efficiently.
 ///
 /// This generates a single INSERT statement with multiple value tuples,
@@ -12,7 +45,7 @@ use crate::value::Value;
 /// # Example
 ///
 /// ```ignore
-/// use rdbi::{BatchInsert, Pool};
+/// use rdbi::{BatchInsert, OnConflict, Pool};
 ///
 /// let users = vec![
 ///     User { id: 0, username: "alice".into(), email: "alice@example.com".into() },
@@ -24,16 +57,44 @@ use crate::value::Value;
 ///     .await?;
 ///
 /// println!("Inserted {} rows", result.rows_affected);
+///
+/// // Skip rows whose unique key already exists
+/// BatchInsert::new("users", &users)
+///     .on_conflict(OnConflict::Ignore)
+///     .execute(&pool)
+///     .await?;
 /// ```
 pub struct BatchInsert<'a, T> {
-    table: &'a str,
+    table: Result<Ident>,
     entities: &'a [T],
+    on_conflict: OnConflict,
 }
 
 impl<'a, T: ToParams> BatchInsert<'a, T> {
     /// Create a new batch insert for the given table and entities.
-    pub fn new(table: &'a str, entities: &'a [T]) -> Self {
-        Self { table, entities }
+    ///
+    /// The table may be schema-qualified (`db.table`) or an [`Ident`].
+    pub fn new(table: impl ToIdent, entities: &'a [T]) -> Self {
+        Self {
+            table: table.to_ident(),
+            entities,
+            on_conflict: OnConflict::Error,
+        }
+    }
+
+    /// Like [`new`](Self::new), but fails right away on an invalid table
+    /// name rather than when the insert runs.
+    pub fn try_new(table: impl ToIdent, entities: &'a [T]) -> Result<Self> {
+        Ok(Self::new(table.to_ident()?, entities))
+    }
+
+    /// Choose how rows that conflict with an existing unique key are handled.
+    ///
+    /// Defaults to [`OnConflict::Error`]. To update conflicting rows instead,
+    /// use [`BatchUpsert`].
+    pub fn on_conflict(mut self, mode: OnConflict) -> Self {
+        self.on_conflict = mode;
+        self
     }
 
     /// Execute the batch insert.
@@ -41,80 +102,75 @@ impl<'a, T: ToParams> BatchInsert<'a, T> {
     /// Returns the number of rows affected and the last insert ID
     /// (which is the ID of the first inserted row for batch inserts).
     pub async fn execute<P: Pool>(self, pool: &P) -> Result<ExecuteResult> {
+        let table = self.table?;
         if self.entities.is_empty() {
-            return Ok(ExecuteResult {
-                rows_affected: 0,
-                last_insert_id: None,
-            });
+            return Ok(ExecuteResult::default());
         }
 
-        let column_names = T::insert_column_names();
+        let column_names = self.entities[0].insert_columns();
         if column_names.is_empty() {
-            return Ok(ExecuteResult {
-                rows_affected: 0,
-                last_insert_id: None,
-            });
+            return Ok(ExecuteResult::default());
         }
 
-        // Build column list
-        let columns = column_names
-            .iter()
-            .map(|c| format!("`{}`", c))
-            .collect::<Vec<_>>()
-            .join(", ");
-
-        // Build placeholder for a single row
-        let single_placeholder = column_names
-            .iter()
-            .map(|_| "?")
-            .collect::<Vec<_>>()
-            .join(", ");
-        let single_placeholder = format!("({})", single_placeholder);
-
-        // Build all placeholders
-        let all_placeholders = self
-            .entities
-            .iter()
-            .map(|_| single_placeholder.as_str())
-            .collect::<Vec<_>>()
-            .join(", ");
-
-        // Build the SQL
-        let sql = format!(
-            "INSERT INTO `{}` ({}) VALUES {}",
-            self.table, columns, all_placeholders
-        );
-
-        // Collect all values
-        let mut params: Vec<Value> = Vec::with_capacity(self.entities.len() * column_names.len());
-        for entity in self.entities {
-            params.extend(entity.insert_values());
-        }
+        let sql = build_insert_sql(
+            self.on_conflict.insert_verb(),
+            &table,
+            column_names,
+            self.entities.len(),
+        )?;
 
-        pool.execute(&sql, params).await
+        pool.execute(&sql, collect_insert_values(column_names, self.entities)?)
+            .await
     }
 }
 
 /// A batch upsert builder for upserting multiple entities efficiently.
 ///
 /// This generates a single INSERT ... ON DUPLICATE KEY UPDATE statement.
+/// On MySQL 8.0.19+ the incoming row is referenced through a row alias
+/// (`AS new ... col = new.col`); older servers and MariaDB get the
+/// `col = VALUES(col)` form. The server version is detected automatically.
+///
+/// # Example
+///
+/// ```ignore
+/// use rdbi::BatchUpsert;
+///
+/// // Add the incoming counter to the stored one instead of overwriting it
+/// BatchUpsert::new("page_views", &views)
+///     .update_columns(vec!["last_seen"])
+///     .update_expr("counter", "`counter` + new.`counter`")
+///     .execute(&pool)
+///     .await?;
+/// ```
 pub struct BatchUpsert<'a, T> {
-    table: &'a str,
+    table: Result<Ident>,
     entities: &'a [T],
     /// Columns to update on duplicate key (if empty, updates all non-PK columns)
     update_columns: Option<Vec<&'a str>>,
+    /// Custom update expressions, keyed by column
+    update_exprs: Vec<(&'a str, &'a str)>,
 }
 
 impl<'a, T: ToParams> BatchUpsert<'a, T> {
     /// Create a new batch upsert for the given table and entities.
-    pub fn new(table: &'a str, entities: &'a [T]) -> Self {
+    ///
+    /// The table may be schema-qualified (`db.table`) or an [`Ident`].
+    pub fn new(table: impl ToIdent, entities: &'a [T]) -> Self {
         Self {
-            table,
+            table: table.to_ident(),
             entities,
             update_columns: None,
+            update_exprs: Vec::new(),
         }
     }
 
+    /// Like [`new`](Self::new), but fails right away on an invalid table
+    /// name rather than when the upsert runs.
+    pub fn try_new(table: impl ToIdent, entities: &'a [T]) -> Result<Self> {
+        Ok(Self::new(table.to_ident()?, entities))
+    }
+
     /// Specify which columns to update on duplicate key.
     ///
     /// If not called, all inserted columns will be updated.
@@ -123,68 +179,502 @@ impl<'a, T: ToParams> BatchUpsert<'a, T> {
         self
     }
 
+    /// Override the value assigned to `column` on duplicate key.
+    ///
+    /// `expr` is raw SQL. The incoming row is referenced as `new.<column>`,
+    /// e.g. `` "`counter` + new.`counter`" ``; on servers without row-alias
+    /// support these references are rewritten to `VALUES(<column>)`.
+    ///
+    /// The column is updated even if it is not listed in
+    /// [`update_columns`](Self::update_columns). The expression is inserted
+    /// verbatim, so never build it from untrusted input.
+    pub fn update_expr(mut self, column: &'a str, expr: &'a str) -> Self {
+        self.update_exprs.retain(|(c, _)| *c != column);
+        self.update_exprs.push((column, expr));
+        self
+    }
+
     /// Execute the batch upsert.
     pub async fn execute<P: Pool>(self, pool: &P) -> Result<ExecuteResult> {
+        let table = self.table?;
         if self.entities.is_empty() {
-            return Ok(ExecuteResult {
-                rows_affected: 0,
-                last_insert_id: None,
-            });
+            return Ok(ExecuteResult::default());
         }
 
-        let column_names = T::insert_column_names();
+        let column_names = self.entities[0].insert_columns();
         if column_names.is_empty() {
-            return Ok(ExecuteResult {
-                rows_affected: 0,
-                last_insert_id: None,
-            });
+            return Ok(ExecuteResult::default());
         }
 
-        // Determine which columns to update
-        let update_cols: Vec<&str> = self.update_columns.unwrap_or_else(|| column_names.to_vec());
-
-        // Build column list
-        let columns = column_names
-            .iter()
-            .map(|c| format!("`{}`", c))
-            .collect::<Vec<_>>()
-            .join(", ");
-
-        // Build placeholder for a single row
-        let single_placeholder = column_names
-            .iter()
-            .map(|_| "?")
-            .collect::<Vec<_>>()
-            .join(", ");
-        let single_placeholder = format!("({})", single_placeholder);
+        let use_row_alias = pool
+            .server_version()
+            .await?
+            .is_some_and(|v| v.supports_row_alias());
 
-        // Build all placeholders
-        let all_placeholders = self
-            .entities
-            .iter()
-            .map(|_| single_placeholder.as_str())
-            .collect::<Vec<_>>()
-            .join(", ");
+        // Determine which columns to update
+        let mut update_cols: Vec<&str> =
+            self.update_columns.unwrap_or_else(|| column_names.to_vec());
+        for (column, _) in &self.update_exprs {
+            if !update_cols.contains(column) {
+                update_cols.push(column);
+            }
+        }
 
         // Build ON DUPLICATE KEY UPDATE clause
         let update_clause = update_cols
             .iter()
-            .map(|c| format!("`{name}` = VALUES(`{name}`)", name = c))
-            .collect::<Vec<_>>()
+            .map(|c| {
+                let column = quote_name(c)?;
+                let value = match self.update_exprs.iter().find(|(col, _)| col == c) {
+                    Some((_, expr)) if use_row_alias => expr.to_string(),
+                    Some((_, expr)) => to_values_syntax(expr),
+                    None if use_row_alias => format!("{}.{}", ROW_ALIAS, column),
+                    None => format!("VALUES({})", column),
+                };
+                Ok(format!("{} = {}", column, value))
+            })
+            .collect::<Result<Vec<_>>>()?
             .join(", ");
 
         // Build the SQL
-        let sql = format!(
-            "INSERT INTO `{}` ({}) VALUES {} ON DUPLICATE KEY UPDATE {}",
-            self.table, columns, all_placeholders, update_clause
-        );
+        let mut sql = build_insert_sql("INSERT INTO", &table, column_names, self.entities.len())?;
+        if use_row_alias {
+            sql.push_str(" AS ");
+            sql.push_str(ROW_ALIAS);
+        }
+        sql.push_str(" ON DUPLICATE KEY UPDATE ");
+        sql.push_str(&update_clause);
+
+        pool.execute(&sql, collect_insert_values(column_names, self.entities)?)
+            .await
+    }
+}
+
+/// A batch update builder for updating many rows by key in one statement.
+///
+/// Each chunk of entities becomes a single statement of the form
+/// `UPDATE t SET col = CASE WHEN key = ? THEN ? ... ELSE col END WHERE key IN (...)`,
+/// so every row can receive different values. Column names and values come
+/// from the entity's [`ToParams`] implementation; key columns are matched
+/// against the first entity's [`ToParams::all_columns`].
+///
+/// Large batches are split into chunks automatically (see
+/// [`chunk_size`](Self::chunk_size)). Chunks run as separate statements, so
+/// wrap the call in a transaction if the whole batch must be atomic.
+///
+/// # Example
+///
+/// ```ignore
+/// use rdbi::BatchUpdate;
+///
+/// let result = BatchUpdate::new("users", &["id"], &users)
+///     .update_columns(vec!["email", "status"])
+///     .execute(&pool)
+///     .await?;
+/// ```
+pub struct BatchUpdate<'a, T> {
+    table: Result<Ident>,
+    key_columns: &'a [&'a str],
+    entities: &'a [T],
+    /// Columns to update (if not set, updates all non-key columns)
+    update_columns: Option<Vec<&'a str>>,
+    chunk_size: Option<usize>,
+}
 
-        // Collect all values
-        let mut params: Vec<Value> = Vec::with_capacity(self.entities.len() * column_names.len());
-        for entity in self.entities {
-            params.extend(entity.insert_values());
+impl<'a, T: ToParams> BatchUpdate<'a, T> {
+    /// Create a new batch update for the given table, key columns and entities.
+    pub fn new(table: impl ToIdent, key_columns: &'a [&'a str], entities: &'a [T]) -> Self {
+        Self {
+            table: table.to_ident(),
+            key_columns,
+            entities,
+            update_columns: None,
+            chunk_size: None,
         }
+    }
+
+    /// Like [`new`](Self::new), but fails right away on an invalid table or
+    /// key column name rather than when the update runs.
+    pub fn try_new(
+        table: impl ToIdent,
+        key_columns: &'a [&'a str],
+        entities: &'a [T],
+    ) -> Result<Self> {
+        quote_names(key_columns)?;
+        Ok(Self::new(table.to_ident()?, key_columns, entities))
+    }
+
+    /// Specify which columns to update.
+    ///
+    /// If not called, all non-key columns will be updated.
+    pub fn update_columns(mut self, columns: Vec<&'a str>) -> Self {
+        self.update_columns = Some(columns);
+        self
+    }
 
-        pool.execute(&sql, params).await
+    /// Set the maximum number of entities per statement.
+    ///
+    /// Defaults to 1000. The value is lowered if a chunk would exceed
+    /// MySQL's limit of 65,535 placeholders per statement.
+    pub fn chunk_size(mut self, size: usize) -> Self {
+        self.chunk_size = Some(size);
+        self
     }
+
+    /// Execute the batch update.
+    ///
+    /// Returns the total number of rows affected across all chunks.
+    pub async fn execute<P: Pool>(self, pool: &P) -> Result<ExecuteResult> {
+        let table = self.table?;
+        let mut result = ExecuteResult::default();
+        if self.entities.is_empty() {
+            return Ok(result);
+        }
+
+        if self.key_columns.is_empty() {
+            return Err(Error::Query(
+                "BatchUpdate requires at least one key column".to_string(),
+            ));
+        }
+
+        let all_columns = self.entities[0].all_columns();
+        let key_indexes = column_indexes(all_columns, self.key_columns)?;
+        let update_cols: Vec<&str> = self.update_columns.unwrap_or_else(|| {
+            all_columns
+                .iter()
+                .filter(|c| !self.key_columns.contains(c))
+                .copied()
+                .collect()
+        });
+        let update_indexes = column_indexes(all_columns, &update_cols)?;
+        if update_cols.is_empty() {
+            return Ok(result);
+        }
+
+        let params_per_row = update_cols.len() * (key_indexes.len() + 1) + key_indexes.len();
+        let chunk_size = resolve_chunk_size(self.chunk_size, params_per_row);
+        let key_columns = quote_names(self.key_columns)?;
+        let quoted_update_cols = quote_names(&update_cols)?;
+        let key_match = key_match_sql(&key_columns);
+
+        for chunk in self.entities.chunks(chunk_size) {
+            let rows = chunk
+                .iter()
+                .map(|e| {
+                    check_columns(all_columns, e.all_columns())?;
+                    Ok(e.all_values())
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let mut params: Vec<Value> = Vec::with_capacity(rows.len() * params_per_row);
+
+            // Build one CASE expression per updated column
+            let mut assignments = Vec::with_capacity(update_cols.len());
+            for (column, &value_index) in quoted_update_cols.iter().zip(&update_indexes) {
+                let mut case = format!("{} = CASE", column);
+                for row in &rows {
+                    case.push_str(" WHEN ");
+                    case.push_str(&key_match);
+                    case.push_str(" THEN ?");
+                    params.extend(key_indexes.iter().map(|&i| row[i].clone()));
+                    params.push(row[value_index].clone());
+                }
+                case.push_str(&format!(" ELSE {} END", column));
+                assignments.push(case);
+            }
+
+            let where_clause = key_in_sql(&key_columns, rows.len());
+            for row in &rows {
+                params.extend(key_indexes.iter().map(|&i| row[i].clone()));
+            }
+
+            let sql = format!(
+                "UPDATE {} SET {} WHERE {}",
+                table,
+                assignments.join(", "),
+                where_clause
+            );
+            result.merge(pool.execute(&sql, params).await?);
+        }
+
+        Ok(result)
+    }
+}
+
+/// A batch delete builder for deleting many rows by key in one statement.
+///
+/// Single-column keys generate `DELETE FROM t WHERE key IN (?, ...)`;
+/// composite keys use a row constructor,
+/// `DELETE FROM t WHERE (a, b) IN ((?, ?), ...)`. Key values are read from
+/// the entity's [`ToParams`] implementation.
+///
+/// Large batches are split into chunks automatically, like [`BatchUpdate`].
+///
+/// # Example
+///
+/// ```ignore
+/// use rdbi::BatchDelete;
+///
+/// let result = BatchDelete::new("user_settings", &["user_id", "setting_key"], &settings)
+///     .execute(&pool)
+///     .await?;
+/// ```
+pub struct BatchDelete<'a, T> {
+    table: Result<Ident>,
+    key_columns: &'a [&'a str],
+    entities: &'a [T],
+    chunk_size: Option<usize>,
+}
+
+impl<'a, T: ToParams> BatchDelete<'a, T> {
+    /// Create a new batch delete for the given table, key columns and entities.
+    pub fn new(table: impl ToIdent, key_columns: &'a [&'a str], entities: &'a [T]) -> Self {
+        Self {
+            table: table.to_ident(),
+            key_columns,
+            entities,
+            chunk_size: None,
+        }
+    }
+
+    /// Like [`new`](Self::new), but fails right away on an invalid table or
+    /// key column name rather than when the delete runs.
+    pub fn try_new(
+        table: impl ToIdent,
+        key_columns: &'a [&'a str],
+        entities: &'a [T],
+    ) -> Result<Self> {
+        quote_names(key_columns)?;
+        Ok(Self::new(table.to_ident()?, key_columns, entities))
+    }
+
+    /// Set the maximum number of entities per statement.
+    ///
+    /// Defaults to 1000. The value is lowered if a chunk would exceed
+    /// MySQL's limit of 65,535 placeholders per statement.
+    pub fn chunk_size(mut self, size: usize) -> Self {
+        self.chunk_size = Some(size);
+        self
+    }
+
+    /// Execute the batch delete.
+    ///
+    /// Returns the total number of rows affected across all chunks.
+    pub async fn execute<P: Pool>(self, pool: &P) -> Result<ExecuteResult> {
+        let table = self.table?;
+        let mut result = ExecuteResult::default();
+        if self.entities.is_empty() {
+            return Ok(result);
+        }
+
+        if self.key_columns.is_empty() {
+            return Err(Error::Query(
+                "BatchDelete requires at least one key column".to_string(),
+            ));
+        }
+
+        let all_columns = self.entities[0].all_columns();
+        let key_indexes = column_indexes(all_columns, self.key_columns)?;
+        let key_columns = quote_names(self.key_columns)?;
+        let chunk_size = resolve_chunk_size(self.chunk_size, key_indexes.len());
+
+        for chunk in self.entities.chunks(chunk_size) {
+            let mut params: Vec<Value> = Vec::with_capacity(chunk.len() * key_indexes.len());
+            for entity in chunk {
+                check_columns(all_columns, entity.all_columns())?;
+                let row = entity.all_values();
+                params.extend(key_indexes.iter().map(|&i| row[i].clone()));
+            }
+
+            let sql = format!(
+                "DELETE FROM {} WHERE {}",
+                table,
+                key_in_sql(&key_columns, chunk.len())
+            );
+            result.merge(pool.execute(&sql, params).await?);
+        }
+
+        Ok(result)
+    }
+}
+
+/// Find the position of each column in `all_columns`.
+fn column_indexes(all_columns: &[&str], columns: &[&str]) -> Result<Vec<usize>> {
+    columns
+        .iter()
+        .map(|column| {
+            all_columns
+                .iter()
+                .position(|c| c == column)
+                .ok_or_else(|| Error::ColumnNotFound(column.to_string()))
+        })
+        .collect()
+}
+
+/// Pick the number of entities per statement, staying under the placeholder limit.
+fn resolve_chunk_size(requested: Option<usize>, params_per_row: usize) -> usize {
+    let max = (MAX_PLACEHOLDERS / params_per_row.max(1)).max(1);
+    requested.unwrap_or(DEFAULT_CHUNK_SIZE).clamp(1, max)
+}
+
+/// Validate and quote unqualified column names.
+fn quote_names(columns: &[&str]) -> Result<Vec<String>> {
+    columns.iter().map(|c| quote_name(c)).collect()
+}
+
+/// Build the condition matching one row by key, e.g. `(`a` = ? AND `b` = ?)`.
+fn key_match_sql(key_columns: &[String]) -> String {
+    let conditions = key_columns
+        .iter()
+        .map(|c| format!("{} = ?", c))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    if key_columns.len() > 1 {
+        format!("({})", conditions)
+    } else {
+        conditions
+    }
+}
+
+/// Build a key membership test for `rows` keys, using a row constructor for composite keys.
+fn key_in_sql(key_columns: &[String], rows: usize) -> String {
+    let columns = key_columns.join(", ");
+    if key_columns.len() > 1 {
+        let tuple = format!("({})", vec!["?"; key_columns.len()].join(", "));
+        format!(
+            "({}) IN ({})",
+            columns,
+            vec![tuple.as_str(); rows].join(", ")
+        )
+    } else {
+        format!("{} IN ({})", columns, vec!["?"; rows].join(", "))
+    }
+}
+
+/// Build a multi-row `<verb> `table` (cols) VALUES (?, ...), ...` statement.
+fn build_insert_sql(
+    verb: &str,
+    table: &Ident,
+    column_names: &[&str],
+    rows: usize,
+) -> Result<String> {
+    // Build column list
+    let columns = quote_names(column_names)?.join(", ");
+
+    // Build placeholder for a single row
+    let single_placeholder = column_names
+        .iter()
+        .map(|_| "?")
+        .collect::<Vec<_>>()
+        .join(", ");
+    let single_placeholder = format!("({})", single_placeholder);
+
+    // Build all placeholders
+    let all_placeholders = vec![single_placeholder.as_str(); rows].join(", ");
+
+    Ok(format!(
+        "{} {} ({}) VALUES {}",
+        verb, table, columns, all_placeholders
+    ))
+}
+
+/// Collect the insert values of all entities in row order.
+fn collect_insert_values<T: ToParams>(columns: &[&str], entities: &[T]) -> Result<Vec<Value>> {
+    let mut params: Vec<Value> = Vec::with_capacity(entities.len() * columns.len());
+    for entity in entities {
+        check_columns(columns, entity.insert_columns())?;
+        params.extend(entity.insert_values());
+    }
+    Ok(params)
+}
+
+/// Check that a row has the same columns as the first row of its batch.
+fn check_columns(expected: &[&str], actual: &[&str]) -> Result<()> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(Error::ParamEncode(format!(
+            "row has columns {:?}, but the first row of the batch has {:?}",
+            actual, expected
+        )))
+    }
+}
+
+/// Rewrite `new.<column>` references in an update expression to `VALUES(<column>)`.
+///
+/// Used for servers that predate the row-alias syntax. Quoted strings are left
+/// untouched, and `new.` only matches at the start of an identifier.
+fn to_values_syntax(expr: &str) -> String {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut out = String::with_capacity(expr.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        // Copy string literals and quoted identifiers verbatim
+        if c == '\'' || c == '"' || c == '`' {
+            let end = find_closing_quote(&chars, i);
+            out.extend(&chars[i..end]);
+            i = end;
+            continue;
+        }
+
+        let at_word_start = i == 0 || !is_ident_char(chars[i - 1]) && chars[i - 1] != '.';
+        let prefix: String = chars[i..chars.len().min(i + ROW_ALIAS.len() + 1)]
+            .iter()
+            .collect();
+        if at_word_start && prefix.eq_ignore_ascii_case(&format!("{}.", ROW_ALIAS)) {
+            let start = i + ROW_ALIAS.len() + 1;
+            let end = match chars.get(start) {
+                Some('`') => find_closing_quote(&chars, start),
+                _ => {
+                    let mut end = start;
+                    while end < chars.len() && is_ident_char(chars[end]) {
+                        end += 1;
+                    }
+                    end
+                }
+            };
+            if end > start {
+                out.push_str("VALUES(");
+                out.extend(&chars[start..end]);
+                out.push(')');
+                i = end;
+                continue;
+            }
+        }
+
+        out.push(c);
+        i += 1;
+    }
+
+    out
+}
+
+/// Find the index just past the quote that closes the one at `start`.
+///
+/// A doubled quote character inside the literal is treated as an escape.
+fn find_closing_quote(chars: &[char], start: usize) -> usize {
+    let quote = chars[start];
+    let mut i = start + 1;
+    while i < chars.len() {
+        if chars[i] == '\\' && quote != '`' {
+            i += 2;
+            continue;
+        }
+        if chars[i] == quote {
+            if chars.get(i + 1) == Some(&quote) {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// Check if a character can appear in an unquoted MySQL identifier.
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
 }
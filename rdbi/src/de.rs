@@ -0,0 +1,440 @@
+//! Serde deserialization of rows
+//!
+//! An alternative to `#[derive(FromRow)]` for types that already derive
+//! `serde::Deserialize`. Struct fields are matched to columns by name
+//! (honouring `#[serde(rename)]`, `#[serde(default)]` and friends), maps and
+//! tuples take every column in select order, and JSON columns deserialize
+//! into nested structs, maps and sequences. A JSON column read as
+//! `serde_json::Value` needs the [`Json`](crate::Json) wrapper, since the
+//! column text is otherwise indistinguishable from a string.
+//!
+//! ```ignore
+//! #[derive(serde::Deserialize)]
+//! struct UserSummary {
+//!     id: i64,
+//!     #[serde(rename = "username")]
+//!     name: String,
+//!     settings: Option<Settings>, // JSON column
+//! }
+//!
+//! let users: Vec<UserSummary> = Query::new("SELECT id, username, settings FROM users")
+//!     .fetch_all_as(&pool)
+//!     .await?;
+//! ```
+
+use std::fmt;
+
+use rust_decimal::prelude::ToPrimitive;
+use serde::de::value::SeqDeserializer;
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+
+use crate::error::{Error, Result};
+use crate::traits::{FromRow, FromValue, Row};
+use crate::types::JSON_NEWTYPE;
+use crate::value::{DurationText, Value};
+
+/// Deserialize a row into any `DeserializeOwned` type.
+///
+/// Structs read their fields by column name; a missing column is treated as
+/// an absent field, so `Option` fields become `None` and `#[serde(default)]`
+/// applies. Maps and tuples read all columns in select order, and a plain
+/// value such as `i64` or `String` reads the row's only column.
+pub fn from_row<T: DeserializeOwned, R: Row>(row: &R) -> Result<T> {
+    T::deserialize(RowDeserializer { row })
+}
+
+/// Deserialize a single database value into any `DeserializeOwned` type.
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T> {
+    T::deserialize(ValueDeserializer(value))
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::RowDecode(msg.to_string())
+    }
+}
+
+/// Adapter that reads a row through serde, so `Deserialize` types can use the
+/// `FromRow`-based pool methods.
+pub(crate) struct SerdeRow<T>(pub T);
+
+impl<T: DeserializeOwned> FromRow for SerdeRow<T> {
+    fn from_row<R: Row>(row: &R) -> Result<Self> {
+        from_row(row).map(SerdeRow)
+    }
+
+    fn column_names() -> &'static [&'static str] {
+        &[]
+    }
+}
+
+/// Name the column a value came from in its error.
+fn with_column(error: Error, column: &str) -> Error {
+    match error {
+        Error::TypeConversion { expected, actual } => Error::TypeConversion {
+            expected,
+            actual: format!("{} (column `{}`)", actual, column),
+        },
+        Error::RowDecode(message) => Error::RowDecode(format!("{} (column `{}`)", message, column)),
+        other => other,
+    }
+}
+
+fn json_error(error: serde_json::Error) -> Error {
+    Error::RowDecode(error.to_string())
+}
+
+struct RowDeserializer<'r, R> {
+    row: &'r R,
+}
+
+impl<'r, R: Row> RowDeserializer<'r, R> {
+    /// The value of the row's only column, for rows read as a plain value.
+    fn single(&self) -> Result<ValueDeserializer> {
+        match self.row.column_names().as_slice() {
+            [column] => self.row.get_value(column).map(ValueDeserializer),
+            columns => Err(Error::RowDecode(format!(
+                "expected a single column, got {}",
+                columns.len()
+            ))),
+        }
+    }
+}
+
+macro_rules! deserialize_single_column {
+    ($($method:ident),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                self.single()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'r, R: Row> de::Deserializer<'de> for RowDeserializer<'r, R> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_map(ColumnAccess {
+            row: self.row,
+            columns: self.row.column_names().into_iter(),
+            value: None,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_map(ColumnAccess {
+            row: self.row,
+            columns: fields.iter().copied(),
+            value: None,
+        })
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(ColumnValues {
+            row: self.row,
+            columns: self.row.column_names().into_iter(),
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    deserialize_single_column!(
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_f32,
+        deserialize_f64,
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_unit,
+    );
+
+    forward_to_deserialize_any! {
+        unit_struct identifier ignored_any
+    }
+}
+
+/// Columns of a row as map entries, skipping names the row doesn't have.
+struct ColumnAccess<'r, 'c, R, I> {
+    row: &'r R,
+    columns: I,
+    value: Option<(&'c str, Value)>,
+}
+
+impl<'de, 'c, R: Row, I: Iterator<Item = &'c str>> MapAccess<'de> for ColumnAccess<'_, 'c, R, I> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        for column in self.columns.by_ref() {
+            match self.row.get_value(column) {
+                Ok(value) => {
+                    self.value = Some((column, value));
+                    return seed.deserialize(column.into_deserializer()).map(Some);
+                }
+                Err(Error::ColumnNotFound(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let (column, value) = self
+            .value
+            .take()
+            .ok_or_else(|| Error::RowDecode("value requested before key".to_string()))?;
+        seed.deserialize(ValueDeserializer(value))
+            .map_err(|e| with_column(e, column))
+    }
+}
+
+/// Values of a row's columns in select order.
+struct ColumnValues<'r, R, I> {
+    row: &'r R,
+    columns: I,
+}
+
+impl<'de, 'c, R: Row, I: Iterator<Item = &'c str>> SeqAccess<'de> for ColumnValues<'_, R, I> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        match self.columns.next() {
+            Some(column) => {
+                let value = self.row.get_value(column)?;
+                seed.deserialize(ValueDeserializer(value))
+                    .map(Some)
+                    .map_err(|e| with_column(e, column))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Deserializer for a single column value.
+struct ValueDeserializer(Value);
+
+impl ValueDeserializer {
+    /// Parse a JSON column (decoded as text) into a document, or hand back
+    /// any other value unchanged.
+    fn into_json(self) -> Result<std::result::Result<serde_json::Value, Value>> {
+        match self.0 {
+            Value::Json(v) => Ok(Ok(v)),
+            Value::String(s) => serde_json::from_str(&s).map(Ok).map_err(json_error),
+            value => Ok(Err(value)),
+        }
+    }
+}
+
+macro_rules! deserialize_with_from_value {
+    ($($method:ident => $visit:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                visitor.$visit(<$ty>::from_value(self.0)?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::I8(v) => visitor.visit_i8(v),
+            Value::I16(v) => visitor.visit_i16(v),
+            Value::I32(v) => visitor.visit_i32(v),
+            Value::I64(v) => visitor.visit_i64(v),
+            Value::U8(v) => visitor.visit_u8(v),
+            Value::U16(v) => visitor.visit_u16(v),
+            Value::U32(v) => visitor.visit_u32(v),
+            Value::U64(v) => visitor.visit_u64(v),
+            Value::F32(v) => visitor.visit_f32(v),
+            Value::F64(v) => visitor.visit_f64(v),
+            Value::String(v) => visitor.visit_string(v),
+            Value::Bytes(v) => visitor.visit_byte_buf(v),
+            // The forms chrono's own Deserialize impls parse
+            Value::Date(v) => visitor.visit_string(format!("{:?}", v)),
+            Value::DateTime(v) => visitor.visit_string(format!("{:?}", v)),
+            Value::DateTimeTz(v) => visitor.visit_string(v.to_rfc3339()),
+            Value::Time(v) => visitor.visit_string(format!("{:?}", v)),
+            Value::Duration(v) => visitor.visit_string(DurationText(&v).to_string()),
+            Value::Decimal(v) => visitor.visit_string(v.to_string()),
+            Value::Json(v) => v.deserialize_any(visitor).map_err(json_error),
+            Value::Invalid { expected, actual } => Err(Error::TypeConversion { expected, actual }),
+        }
+    }
+
+    // Integers and booleans go through FromValue for its range checks and
+    // BIT / TINYINT(1) handling
+    deserialize_with_from_value!(
+        deserialize_bool => visit_bool(bool),
+        deserialize_i8 => visit_i8(i8),
+        deserialize_i16 => visit_i16(i16),
+        deserialize_i32 => visit_i32(i32),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_u8 => visit_u8(u8),
+        deserialize_u16 => visit_u16(u16),
+        deserialize_u32 => visit_u32(u32),
+        deserialize_u64 => visit_u64(u64),
+    );
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::Decimal(v) => match v.to_f64() {
+                Some(f) => visitor.visit_f64(f),
+                None => Err(Error::TypeConversion {
+                    expected: "f64",
+                    actual: format!("decimal({}) out of range", v),
+                }),
+            },
+            value => ValueDeserializer(value).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        if name != JSON_NEWTYPE {
+            return visitor.visit_newtype_struct(self);
+        }
+        // `Json<T>`: parse the column text, so even `Json<serde_json::Value>` gets a document
+        match self.into_json()? {
+            Ok(json) => visitor.visit_newtype_struct(json).map_err(json_error),
+            Err(value) => visitor.visit_newtype_struct(ValueDeserializer(value)),
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            // Binary columns into Vec<u8> and friends
+            Value::Bytes(v) => visitor.visit_seq(SeqDeserializer::new(v.into_iter())),
+            value => match ValueDeserializer(value).into_json()? {
+                Ok(json) => json.deserialize_seq(visitor).map_err(json_error),
+                Err(value) => ValueDeserializer(value).deserialize_any(visitor),
+            },
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.into_json()? {
+            Ok(json) => json.deserialize_map(visitor).map_err(json_error),
+            Err(value) => ValueDeserializer(value).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.into_json()? {
+            Ok(json) => json
+                .deserialize_struct(name, fields, visitor)
+                .map_err(json_error),
+            Err(value) => ValueDeserializer(value).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.0 {
+            // ENUM columns hold the variant name
+            Value::String(s) => visitor.visit_enum(s.into_deserializer()),
+            Value::Json(v) => v
+                .deserialize_enum(name, variants, visitor)
+                .map_err(json_error),
+            value => ValueDeserializer(value).deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        char str string bytes byte_buf unit unit_struct identifier ignored_any
+    }
+}
@@ -35,10 +35,33 @@ pub enum Error {
     #[error("Connection error: {0}")]
     Connection(String),
 
+    /// Invalid SQL identifier (table, column or schema name)
+    #[error("Invalid identifier: {0}")]
+    InvalidIdentifier(String),
+
     /// Row decode error
     #[error("Failed to decode row: {0}")]
     RowDecode(String),
 
+    /// Parameter encode error (serializing a value into query parameters)
+    #[error("Failed to encode parameters: {0}")]
+    ParamEncode(String),
+
+    /// A query returned more rows than the configured maximum
+    #[error("Query returned more than {max} rows")]
+    TooManyRows { max: u64 },
+
+    /// A statement truncated or adjusted data to fit its columns
+    ///
+    /// Raised after the statement ran, when the pool treats truncation
+    /// warnings as errors; roll back the surrounding transaction to undo it.
+    #[error("Data truncated: {}", .0.first().map_or("", |w| w.message.as_str()))]
+    Truncated(Vec<crate::traits::Warning>),
+
+    /// A transaction ran past the deadline set in its options
+    #[error("Transaction deadline exceeded")]
+    DeadlineExceeded,
+
     /// An external error wrapped as a boxed trait object.
     ///
     /// Use this to embed non-rdbi errors (e.g., application-level or third-party errors)
@@ -0,0 +1,263 @@
+//! Arrow `RecordBatch` export
+
+use std::sync::Arc;
+
+use arrow_array::builder::{
+    ArrayBuilder, BinaryBuilder, Date32Builder, DurationMicrosecondBuilder, Float32Builder,
+    Float64Builder, Int16Builder, Int32Builder, Int64Builder, Int8Builder, NullBuilder,
+    StringBuilder, TimestampMicrosecondBuilder, UInt16Builder, UInt32Builder, UInt64Builder,
+    UInt8Builder,
+};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+
+use super::value_text;
+use crate::error::{Error, Result};
+use crate::row::{Column, ColumnType, DynamicRow, RowSink};
+use crate::traits::FromValue;
+use crate::value::Value;
+
+/// Rows per batch unless set with [`RecordBatchWriter::batch_size`].
+const DEFAULT_BATCH_SIZE: usize = 8192;
+
+/// Arrow type for a column.
+///
+/// Integers keep their width and signedness, `DATETIME` and `TIMESTAMP`
+/// become microsecond timestamps (wall-clock, without a zone), `TIME`
+/// becomes a microsecond duration since it may be negative or exceed a day,
+/// and `BIT` becomes `UInt64`. `DECIMAL` becomes `Utf8`, since the result
+/// metadata does not reliably carry its precision. Text, `ENUM`, `SET` and
+/// `JSON` become `Utf8`; binary and spatial columns become `Binary`.
+fn data_type(column: &Column) -> DataType {
+    match (column.column_type, column.unsigned) {
+        (ColumnType::TinyInt, false) => DataType::Int8,
+        (ColumnType::TinyInt, true) => DataType::UInt8,
+        (ColumnType::SmallInt, false) => DataType::Int16,
+        (ColumnType::SmallInt, true) => DataType::UInt16,
+        (ColumnType::MediumInt | ColumnType::Int, false) => DataType::Int32,
+        (ColumnType::MediumInt | ColumnType::Int, true) => DataType::UInt32,
+        (ColumnType::BigInt, false) => DataType::Int64,
+        (ColumnType::BigInt, true) => DataType::UInt64,
+        (ColumnType::Year, _) => DataType::UInt16,
+        (ColumnType::Bit, _) => DataType::UInt64,
+        (ColumnType::Float, _) => DataType::Float32,
+        (ColumnType::Double, _) => DataType::Float64,
+        (ColumnType::Date, _) => DataType::Date32,
+        (ColumnType::DateTime | ColumnType::Timestamp, _) => {
+            DataType::Timestamp(TimeUnit::Microsecond, None)
+        }
+        (ColumnType::Time, _) => DataType::Duration(TimeUnit::Microsecond),
+        (ColumnType::Binary | ColumnType::VarBinary | ColumnType::Blob, _) => DataType::Binary,
+        (ColumnType::Geometry, _) => DataType::Binary,
+        (ColumnType::Null, _) => DataType::Null,
+        (
+            ColumnType::Decimal
+            | ColumnType::Char
+            | ColumnType::VarChar
+            | ColumnType::Text
+            | ColumnType::Enum
+            | ColumnType::Set
+            | ColumnType::Json
+            | ColumnType::Unknown,
+            _,
+        ) => DataType::Utf8,
+    }
+}
+
+/// Build the Arrow schema for a result's columns.
+///
+/// See [`RecordBatchWriter`] for how MySQL types map to Arrow types.
+pub fn arrow_schema(columns: &[Column]) -> Schema {
+    Schema::new(
+        columns
+            .iter()
+            .map(|column| {
+                let data_type = data_type(column);
+                let nullable = column.nullable || data_type == DataType::Null;
+                Field::new(column.name.clone(), data_type, nullable)
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Collects rows into Arrow `RecordBatch`es.
+///
+/// Each time `batch_size` rows have been collected, the batch is passed to
+/// the callback; [`finish`](Self::finish) hands over the remainder. Only one
+/// batch is held in memory at a time.
+///
+/// Integers keep their width and signedness, `DATETIME` and `TIMESTAMP`
+/// become microsecond timestamps (wall-clock, without a zone), `TIME` a
+/// microsecond duration and `BIT` `UInt64`. `DECIMAL`, text, `ENUM`, `SET`
+/// and `JSON` columns become `Utf8` (decimals as their exact text), and
+/// binary and spatial columns `Binary`.
+///
+/// ```ignore
+/// let mut batches = Vec::new();
+/// let mut writer = RecordBatchWriter::new(|batch| {
+///     batches.push(batch);
+///     Ok(())
+/// });
+/// Query::new("SELECT * FROM orders").stream_rows(&pool, &mut writer).await?;
+/// writer.finish()?;
+/// ```
+pub struct RecordBatchWriter<F>
+where
+    F: FnMut(RecordBatch) -> Result<()>,
+{
+    on_batch: F,
+    batch_size: usize,
+    schema: Option<SchemaRef>,
+    builders: Vec<Box<dyn ArrayBuilder>>,
+    pending: usize,
+    rows: u64,
+}
+
+impl<F> RecordBatchWriter<F>
+where
+    F: FnMut(RecordBatch) -> Result<()>,
+{
+    /// Create a writer that passes each completed batch to `on_batch`.
+    pub fn new(on_batch: F) -> Self {
+        Self {
+            on_batch,
+            batch_size: DEFAULT_BATCH_SIZE,
+            schema: None,
+            builders: Vec::new(),
+            pending: 0,
+            rows: 0,
+        }
+    }
+
+    /// Set the number of rows per batch (default 8192).
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// The schema of the batches, once the result's columns are known.
+    pub fn schema(&self) -> Option<SchemaRef> {
+        self.schema.clone()
+    }
+
+    /// Number of rows collected so far.
+    pub fn rows_written(&self) -> u64 {
+        self.rows
+    }
+
+    /// Pass any remaining rows on as a final, smaller batch.
+    pub fn finish(&mut self) -> Result<()> {
+        if self.pending > 0 {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let schema = match &self.schema {
+            Some(schema) => schema.clone(),
+            None => return Ok(()),
+        };
+        let arrays: Vec<ArrayRef> = self.builders.iter_mut().map(|b| b.finish()).collect();
+        let batch = RecordBatch::try_new(schema, arrays).map_err(|e| Error::Other(Box::new(e)))?;
+        self.pending = 0;
+        (self.on_batch)(batch)
+    }
+}
+
+impl<F> RowSink for RecordBatchWriter<F>
+where
+    F: FnMut(RecordBatch) -> Result<()>,
+{
+    fn columns(&mut self, columns: &[Column]) -> Result<()> {
+        let schema = Arc::new(arrow_schema(columns));
+        self.builders = schema
+            .fields()
+            .iter()
+            .map(|field| arrow_array::builder::make_builder(field.data_type(), self.batch_size))
+            .collect();
+        self.schema = Some(schema);
+        Ok(())
+    }
+
+    fn row(&mut self, row: &DynamicRow) -> Result<()> {
+        let schema = match &self.schema {
+            Some(schema) => schema.clone(),
+            None => return Err(Error::Query("Row received before its columns".to_string())),
+        };
+        for ((field, builder), value) in schema
+            .fields()
+            .iter()
+            .zip(self.builders.iter_mut())
+            .zip(row.values())
+        {
+            append(builder.as_mut(), field.data_type(), value).map_err(|e| match e {
+                Error::TypeConversion { expected, actual } => Error::TypeConversion {
+                    expected,
+                    actual: format!("{} (column `{}`)", actual, field.name()),
+                },
+                other => other,
+            })?;
+        }
+        self.pending += 1;
+        self.rows += 1;
+        if self.pending >= self.batch_size {
+            self.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Append a value to the builder `make_builder` created for `data_type`.
+fn append(builder: &mut dyn ArrayBuilder, data_type: &DataType, value: &Value) -> Result<()> {
+    macro_rules! builder {
+        ($ty:ty) => {
+            builder
+                .as_any_mut()
+                .downcast_mut::<$ty>()
+                .expect("builder made for this data type")
+        };
+    }
+    macro_rules! append_from_value {
+        ($builder:ty, $ty:ty) => {
+            builder!($builder).append_option(Option::<$ty>::from_value(value.clone())?)
+        };
+    }
+
+    match data_type {
+        DataType::Int8 => append_from_value!(Int8Builder, i8),
+        DataType::Int16 => append_from_value!(Int16Builder, i16),
+        DataType::Int32 => append_from_value!(Int32Builder, i32),
+        DataType::Int64 => append_from_value!(Int64Builder, i64),
+        DataType::UInt8 => append_from_value!(UInt8Builder, u8),
+        DataType::UInt16 => append_from_value!(UInt16Builder, u16),
+        DataType::UInt32 => append_from_value!(UInt32Builder, u32),
+        DataType::UInt64 => append_from_value!(UInt64Builder, u64),
+        DataType::Float32 => append_from_value!(Float32Builder, f32),
+        DataType::Float64 => append_from_value!(Float64Builder, f64),
+        DataType::Date32 => {
+            let days = Option::<NaiveDate>::from_value(value.clone())?
+                .map(|date| (date - NaiveDate::default()).num_days() as i32);
+            builder!(Date32Builder).append_option(days)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, None) => {
+            let micros = Option::<NaiveDateTime>::from_value(value.clone())?
+                .map(|datetime| datetime.and_utc().timestamp_micros());
+            builder!(TimestampMicrosecondBuilder).append_option(micros)
+        }
+        DataType::Duration(TimeUnit::Microsecond) => {
+            let micros = Option::<Duration>::from_value(value.clone())?
+                .and_then(|duration| duration.num_microseconds());
+            builder!(DurationMicrosecondBuilder).append_option(micros)
+        }
+        DataType::Binary => match value {
+            Value::Null => builder!(BinaryBuilder).append_null(),
+            Value::String(text) => builder!(BinaryBuilder).append_value(text),
+            _ => builder!(BinaryBuilder).append_value(Vec::<u8>::from_value(value.clone())?),
+        },
+        DataType::Null => builder!(NullBuilder).append_null(),
+        _ => builder!(StringBuilder).append_option(value_text(value)),
+    }
+    Ok(())
+}
@@ -0,0 +1,120 @@
+//! CSV export
+
+use std::io::Write;
+
+use super::{io_error, value_text};
+use crate::error::Result;
+use crate::row::{Column, DynamicRow, RowSink};
+
+/// Writes rows as CSV (RFC 4180).
+///
+/// Fields are quoted when they contain the delimiter, a quote or a line
+/// break, and quotes are doubled. `NULL` is written as an empty field by
+/// default, and an empty string as `""` so the two stay distinguishable; use
+/// [`null_value`](Self::null_value) for a marker such as `\N` instead.
+pub struct CsvWriter<W: Write> {
+    writer: W,
+    delimiter: u8,
+    null_value: String,
+    header: bool,
+    rows: u64,
+}
+
+impl<W: Write> CsvWriter<W> {
+    /// Create a writer with a header line, `,` as delimiter and empty `NULL`s.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            delimiter: b',',
+            null_value: String::new(),
+            header: true,
+            rows: 0,
+        }
+    }
+
+    /// Set the field delimiter, e.g. `b'\t'` for TSV.
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set the text written for `NULL`.
+    pub fn null_value(mut self, null_value: impl Into<String>) -> Self {
+        self.null_value = null_value.into();
+        self
+    }
+
+    /// Set whether the first line holds the column names.
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Number of rows written so far, not counting the header.
+    pub fn rows_written(&self) -> u64 {
+        self.rows
+    }
+
+    /// Flush the underlying writer.
+    pub fn finish(&mut self) -> Result<()> {
+        self.writer.flush().map_err(io_error)
+    }
+
+    /// Get the underlying writer back.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_record<'a>(&mut self, fields: impl Iterator<Item = Option<&'a str>>) -> Result<()> {
+        for (i, field) in fields.enumerate() {
+            if i > 0 {
+                self.writer.write_all(&[self.delimiter]).map_err(io_error)?;
+            }
+            match field {
+                None => self
+                    .writer
+                    .write_all(self.null_value.as_bytes())
+                    .map_err(io_error)?,
+                Some(text) => self.write_field(text)?,
+            }
+        }
+        self.writer.write_all(b"\r\n").map_err(io_error)
+    }
+
+    fn write_field(&mut self, text: &str) -> Result<()> {
+        let delimiter = self.delimiter;
+        let needs_quotes = text == self.null_value
+            || text
+                .bytes()
+                .any(|b| b == delimiter || b == b'"' || b == b'\r' || b == b'\n');
+        if !needs_quotes {
+            return self.writer.write_all(text.as_bytes()).map_err(io_error);
+        }
+        let mut quoted = String::with_capacity(text.len() + 2);
+        quoted.push('"');
+        for c in text.chars() {
+            if c == '"' {
+                quoted.push('"');
+            }
+            quoted.push(c);
+        }
+        quoted.push('"');
+        self.writer.write_all(quoted.as_bytes()).map_err(io_error)
+    }
+}
+
+impl<W: Write> RowSink for CsvWriter<W> {
+    fn columns(&mut self, columns: &[Column]) -> Result<()> {
+        if self.header {
+            self.write_record(columns.iter().map(|c| Some(c.name.as_str())))?;
+        }
+        Ok(())
+    }
+
+    fn row(&mut self, row: &DynamicRow) -> Result<()> {
+        let fields: Vec<Option<String>> = row.values().iter().map(value_text).collect();
+        self.write_record(fields.iter().map(Option::as_deref))?;
+        self.rows += 1;
+        Ok(())
+    }
+}
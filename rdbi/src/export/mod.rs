@@ -0,0 +1,82 @@
+//! Streaming export of query results
+//!
+//! The writers here implement [`RowSink`](crate::RowSink), so a query can be
+//! streamed straight into them with `stream_rows`. With [`MySqlPool`](crate::MySqlPool)
+//! and its transactions, rows are written as they arrive from the server,
+//! keeping memory use bounded however large the result is. Pools relying on
+//! the default [`Pool::stream_rows`](crate::Pool::stream_rows) fetch the whole
+//! result before the first row is written.
+//!
+//! ```ignore
+//! use rdbi::export::{CsvWriter, NdjsonWriter};
+//! use std::io::BufWriter;
+//!
+//! let mut csv = CsvWriter::new(BufWriter::new(File::create("orders.csv")?));
+//! Query::new("SELECT * FROM orders").stream_rows(&pool, &mut csv).await?;
+//! csv.finish()?;
+//!
+//! let mut json = NdjsonWriter::new(std::io::stdout().lock());
+//! Query::new("SELECT * FROM users").stream_rows(&pool, &mut json).await?;
+//! ```
+//!
+//! With the `arrow` feature, `RecordBatchWriter` collects rows into Arrow
+//! `RecordBatch`es instead.
+//!
+//! Writers take any `std::io::Write` and write to it synchronously; wrap
+//! files and sockets in a `BufWriter`.
+
+#[cfg(feature = "arrow")]
+mod arrow;
+mod csv;
+mod ndjson;
+
+#[cfg(feature = "arrow")]
+pub use self::arrow::{arrow_schema, RecordBatchWriter};
+pub use self::csv::CsvWriter;
+pub use self::ndjson::NdjsonWriter;
+
+use crate::error::Error;
+use crate::value::{DurationText, Value};
+
+/// Text form of a value for textual formats, or `None` for `NULL`.
+///
+/// Dates and times use MySQL's literal format; bytes are hex, prefixed `0x`.
+fn value_text(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::Null => return None,
+        Value::Bool(v) => if *v { "1" } else { "0" }.to_string(),
+        Value::I8(v) => v.to_string(),
+        Value::I16(v) => v.to_string(),
+        Value::I32(v) => v.to_string(),
+        Value::I64(v) => v.to_string(),
+        Value::U8(v) => v.to_string(),
+        Value::U16(v) => v.to_string(),
+        Value::U32(v) => v.to_string(),
+        Value::U64(v) => v.to_string(),
+        Value::F32(v) => v.to_string(),
+        Value::F64(v) => v.to_string(),
+        Value::String(v) => v.clone(),
+        Value::Bytes(v) => {
+            let mut hex = String::with_capacity(2 + v.len() * 2);
+            hex.push_str("0x");
+            for byte in v {
+                hex.push_str(&format!("{:02x}", byte));
+            }
+            hex
+        }
+        Value::Date(v) => v.to_string(),
+        Value::DateTime(v) => v.to_string(),
+        Value::DateTimeTz(v) => v.to_string(),
+        Value::Time(v) => v.to_string(),
+        Value::Duration(v) => DurationText(v).to_string(),
+        Value::Decimal(v) => v.to_string(),
+        Value::Json(v) => v.to_string(),
+        // Rows never hold one; only failed conversions of bound values do
+        Value::Invalid { .. } => return None,
+    };
+    Some(text)
+}
+
+fn io_error(e: std::io::Error) -> Error {
+    Error::Other(Box::new(e))
+}
@@ -0,0 +1,52 @@
+//! JSON Lines export
+
+use std::io::Write;
+
+use super::io_error;
+use crate::error::{Error, Result};
+use crate::row::{Column, DynamicRow, RowSink};
+
+/// Writes rows as JSON Lines: one JSON object per row, one row per line.
+///
+/// Objects are keyed by column name in select order, with values in their
+/// serde form (see [`Value`](crate::Value)) and `JSON` columns embedded as
+/// documents.
+pub struct NdjsonWriter<W: Write> {
+    writer: W,
+    rows: u64,
+}
+
+impl<W: Write> NdjsonWriter<W> {
+    /// Create a writer.
+    pub fn new(writer: W) -> Self {
+        Self { writer, rows: 0 }
+    }
+
+    /// Number of rows written so far.
+    pub fn rows_written(&self) -> u64 {
+        self.rows
+    }
+
+    /// Flush the underlying writer.
+    pub fn finish(&mut self) -> Result<()> {
+        self.writer.flush().map_err(io_error)
+    }
+
+    /// Get the underlying writer back.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> RowSink for NdjsonWriter<W> {
+    fn columns(&mut self, _columns: &[Column]) -> Result<()> {
+        Ok(())
+    }
+
+    fn row(&mut self, row: &DynamicRow) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, row).map_err(|e| Error::Other(Box::new(e)))?;
+        self.writer.write_all(b"\n").map_err(io_error)?;
+        self.rows += 1;
+        Ok(())
+    }
+}
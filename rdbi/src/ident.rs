@@ -0,0 +1,189 @@
+//! SQL identifier validation and quoting for rdbi
+
+use std::fmt;
+
+use crate::error::{Error, Result};
+
+/// Maximum length of a MySQL identifier, in characters.
+const MAX_IDENT_LEN: usize = 64;
+
+/// A validated, possibly schema-qualified MySQL identifier.
+///
+/// Each part is checked against MySQL's rules for quoted identifiers and
+/// rendered in backticks with embedded backticks doubled, so an `Ident` can
+/// be interpolated into SQL safely even when it comes from runtime input.
+///
+/// # Example
+///
+/// ```ignore
+/// use rdbi::Ident;
+///
+/// let table = Ident::qualified(&tenant_schema, "users")?;
+/// assert_eq!(Ident::parse("app.users")?.to_string(), "`app`.`users`");
+/// assert_eq!(Ident::new("we`ird")?.to_string(), "`we``ird`");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ident {
+    parts: Vec<String>,
+    /// Whether the path ends in `*`, e.g. `u.*`
+    wildcard: bool,
+}
+
+impl Ident {
+    /// Create an unqualified identifier.
+    ///
+    /// The name is used as-is; a `.` is part of the name, not a separator.
+    pub fn new(name: impl Into<String>) -> Result<Self> {
+        let name = name.into();
+        validate_part(&name)?;
+        Ok(Self {
+            parts: vec![name],
+            wildcard: false,
+        })
+    }
+
+    /// Create a schema-qualified identifier such as `db`.`table`.
+    pub fn qualified(schema: impl Into<String>, name: impl Into<String>) -> Result<Self> {
+        let parts = vec![schema.into(), name.into()];
+        for part in &parts {
+            validate_part(part)?;
+        }
+        Ok(Self {
+            parts,
+            wildcard: false,
+        })
+    }
+
+    /// Parse a dotted path such as `table`, `db.table` or `table.column`.
+    ///
+    /// The path is split on `.` into at most three parts. A last part of `*`
+    /// selects all columns and is rendered unquoted, as in `*` or `u.*`.
+    pub fn parse(path: &str) -> Result<Self> {
+        let mut parts: Vec<String> = path.split('.').map(str::to_string).collect();
+        if parts.len() > 3 {
+            return Err(invalid(path, "has more than three parts"));
+        }
+        let wildcard = parts.last().is_some_and(|part| part == "*");
+        if wildcard {
+            parts.pop();
+        }
+        for part in &parts {
+            if let Some(problem) = part_problem(part) {
+                return Err(invalid(path, problem));
+            }
+        }
+        Ok(Self { parts, wildcard })
+    }
+
+    /// Get the unquoted last part, e.g. the table name of `db.table`.
+    ///
+    /// Empty for a bare `*`.
+    pub fn name(&self) -> &str {
+        self.parts.last().map(String::as_str).unwrap_or_default()
+    }
+
+    /// Get the unquoted parts of this identifier.
+    pub fn parts(&self) -> &[String] {
+        &self.parts
+    }
+}
+
+impl fmt::Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, part) in self.parts.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "`{}`", part.replace('`', "``"))?;
+        }
+        if self.wildcard {
+            if !self.parts.is_empty() {
+                f.write_str(".")?;
+            }
+            f.write_str("*")?;
+        }
+        Ok(())
+    }
+}
+
+/// Conversion into a validated [`Ident`].
+///
+/// Implemented for [`Ident`] itself and for strings, which are parsed with
+/// [`Ident::parse`] so `db.table` and `alias.column` are split into parts.
+pub trait ToIdent {
+    /// Convert into an identifier, validating it.
+    fn to_ident(&self) -> Result<Ident>;
+}
+
+impl ToIdent for Ident {
+    fn to_ident(&self) -> Result<Ident> {
+        Ok(self.clone())
+    }
+}
+
+impl ToIdent for str {
+    fn to_ident(&self) -> Result<Ident> {
+        Ident::parse(self)
+    }
+}
+
+impl ToIdent for String {
+    fn to_ident(&self) -> Result<Ident> {
+        Ident::parse(self)
+    }
+}
+
+impl<T: ToIdent + ?Sized> ToIdent for &T {
+    fn to_ident(&self) -> Result<Ident> {
+        (**self).to_ident()
+    }
+}
+
+/// Validate and quote a single unqualified identifier, e.g. a column name.
+pub(crate) fn quote_name(name: &str) -> Result<String> {
+    Ident::new(name).map(|ident| ident.to_string())
+}
+
+/// Quote an identifier, recording the first validation failure in `invalid`.
+pub(crate) fn quote_deferred(name: impl ToIdent, invalid: &mut Option<String>) -> String {
+    match name.to_ident() {
+        Ok(ident) => ident.to_string(),
+        Err(err) => {
+            if invalid.is_none() {
+                *invalid = Some(match err {
+                    Error::InvalidIdentifier(message) => message,
+                    other => other.to_string(),
+                });
+            }
+            String::new()
+        }
+    }
+}
+
+fn validate_part(part: &str) -> Result<()> {
+    match part_problem(part) {
+        Some(problem) => Err(invalid(part, problem)),
+        None => Ok(()),
+    }
+}
+
+/// Describe why `part` is not a valid identifier.
+fn part_problem(part: &str) -> Option<&'static str> {
+    if part.is_empty() {
+        Some("is empty")
+    } else if part.chars().count() > MAX_IDENT_LEN {
+        Some("is longer than 64 characters")
+    } else if part.contains('\0') {
+        Some("contains a NUL character")
+    } else if part.chars().any(|c| c as u32 > 0xFFFF) {
+        Some("contains a character outside the Basic Multilingual Plane")
+    } else if part.ends_with(' ') {
+        Some("ends with a space")
+    } else {
+        None
+    }
+}
+
+fn invalid(name: &str, reason: &str) -> Error {
+    Error::InvalidIdentifier(format!("{:?} {}", name, reason))
+}
@@ -7,8 +7,19 @@
 //!
 //! - **Clean Query API**: Fluent query builder with `.bind()` chaining
 //! - **Derive Macros**: `#[derive(FromRow, ToParams)]` for automatic mapping
-//! - **Batch Operations**: Clean `BatchInsert` API for bulk inserts
+//! - **Batch Operations**: `BatchInsert`, `BatchUpsert`, `BatchUpdate` and `BatchDelete` for bulk writes
+//! - **SELECT Builder**: Composable `Select` with escaped identifiers and bound `Expr` conditions
+//! - **Query Builder**: Push-style `QueryBuilder` for assembling SQL across helper functions
 //! - **Type Safety**: Strong typing with `Value` enum for all database types
+//! - **Typed JSON**: `Json<T>` decodes JSON columns into your own serde types
+//! - **Serde Rows**: `fetch_all_as` reads rows into any `serde::Deserialize` type
+//! - **Dynamic Rows**: `fetch_all_rows` returns untyped rows with column types, for generic tooling
+//! - **Serde Params**: `SerdeParams` binds any `serde::Serialize` struct in the batch builders
+//! - **Export**: Stream query results to CSV, JSON Lines or (with `arrow`) Arrow record batches
+//! - **Optional Types**: `uuid` feature for `uuid::Uuid` in `BINARY(16)` or `CHAR(36)` columns,
+//!   `time` feature for `time` crate dates and times alongside chrono,
+//!   `geo` feature for `geo-types` geometries in spatial columns,
+//!   `arrow` feature for exporting results as Arrow record batches
 //!
 //! # Example
 //!
@@ -32,23 +43,44 @@
 //! ```
 
 pub mod batch;
+pub mod de;
 pub mod error;
+pub mod export;
+pub mod ident;
 mod macros;
 pub mod mysql;
 pub mod query;
+pub mod row;
+pub mod select;
+pub mod ser;
 pub mod traits;
+pub mod types;
 pub mod value;
 
 // Re-export the derive macros
 pub use rdbi_derive::{FromRow, ToParams};
 
 // Re-export main types
-pub use batch::BatchInsert;
+pub use batch::{BatchDelete, BatchInsert, BatchUpdate, BatchUpsert, OnConflict};
 pub use error::{Error, Result};
-pub use mysql::{MySqlPool, MySqlPoolBuilder, MySqlRow, MySqlTransaction};
-pub use query::{DynamicQuery, Query};
+pub use ident::{Ident, ToIdent};
+pub use mysql::{
+    MySqlPool, MySqlPoolBuilder, MySqlRow, MySqlTransaction, PreparedQuery, StmtCacheStats,
+    WarningMode,
+};
+pub use query::{DynamicQuery, Query, QueryBuilder, Separated};
+pub use row::{Column, ColumnType, DynamicRow, RowSink};
+pub use select::{Expr, Order, Select};
+pub use ser::SerdeParams;
 pub use traits::{
-    ExecuteResult, FromRow, FromValue, IsolationLevel, Pool, Row, RowExt, ToParams, ToValue,
-    Transaction, Transactional,
+    AccessMode, ExecuteMode, ExecuteResult, FromRow, FromValue, IsolationLevel, OnError, Pool, Row,
+    RowExt, RowLimit, ServerVersion, ToParams, ToValue, Transaction, TransactionOptions,
+    Transactional, Warning,
 };
+pub use types::Json;
 pub use value::Value;
+
+#[cfg(feature = "uuid")]
+pub use types::SwappedUuid;
+#[cfg(feature = "geo")]
+pub use types::{GeoKind, Spatial};
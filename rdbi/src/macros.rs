@@ -88,10 +88,11 @@ macro_rules! in_transaction {
     };
 }
 
-/// Execute a block within a transaction with a specified isolation level.
+/// Execute a block within a transaction with a specified isolation level or options.
 ///
-/// Like [`in_transaction!`] but accepts an [`IsolationLevel`](crate::IsolationLevel) as the
-/// second argument. No `use rdbi::Transactional` import is required.
+/// Like [`in_transaction!`] but accepts an [`IsolationLevel`](crate::IsolationLevel) or
+/// [`TransactionOptions`](crate::TransactionOptions) as the second argument. No
+/// `use rdbi::Transactional` import is required.
 ///
 /// # Syntax
 ///
@@ -107,6 +108,12 @@ macro_rules! in_transaction {
 ///     dao::users::insert(tx, &user).await?;
 ///     Ok(())
 /// }).await?;
+///
+/// // Transaction options:
+/// rdbi::in_transaction_with!(pool, TransactionOptions::new().read_only(), |tx| {
+///     let users = dao::users::find_all(tx).await?;
+///     Ok(users)
+/// }).await?;
 /// ```
 #[macro_export]
 macro_rules! in_transaction_with {
@@ -114,7 +121,10 @@ macro_rules! in_transaction_with {
         async {
             use $crate::Transaction as _;
             use $crate::Transactional as _;
-            let __rdbi_tx = match $pool.begin_with($level).await {
+            let __rdbi_tx = match $pool
+                .begin_with_options($crate::TransactionOptions::from($level))
+                .await
+            {
                 Ok(tx) => tx,
                 Err(e) => return Err(<$err as ::std::convert::From<$crate::Error>>::from(e)),
             };
@@ -136,7 +146,10 @@ macro_rules! in_transaction_with {
         async {
             use $crate::Transaction as _;
             use $crate::Transactional as _;
-            let __rdbi_tx = match $pool.begin_with($level).await {
+            let __rdbi_tx = match $pool
+                .begin_with_options($crate::TransactionOptions::from($level))
+                .await
+            {
                 Ok(tx) => tx,
                 Err(e) => return Err(e),
             };
@@ -1,10 +1,14 @@
 //! MySQL implementation for rdbi
 
 mod pool;
+mod prepared;
 mod row;
+mod stmt_cache;
 mod transaction;
 mod types;
 
-pub use pool::{MySqlPool, MySqlPoolBuilder};
+pub use pool::{MySqlPool, MySqlPoolBuilder, WarningMode};
+pub use prepared::PreparedQuery;
 pub use row::MySqlRow;
+pub use stmt_cache::StmtCacheStats;
 pub use transaction::MySqlTransaction;
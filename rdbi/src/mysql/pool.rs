@@ -1,19 +1,27 @@
 //! MySQL connection pool implementation
 
-use std::time::Duration;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
 use crate::error::{Error, Result};
+use crate::query::DynamicQuery;
+use crate::row::{Column, DynamicRow, RowSink};
 use crate::traits::{
-    ExecuteResult, FromRow, FromValue, IsolationLevel, Pool, Transaction, Transactional,
+    AccessMode, ExecuteMode, ExecuteResult, FromRow, FromValue, IsolationLevel, OnError, Pool,
+    RowLimit, ServerVersion, Transaction, TransactionOptions, Transactional, Warning,
 };
 use crate::value::Value;
 use async_trait::async_trait;
+use chrono::{FixedOffset, Offset};
 use mysql_async::prelude::*;
-use mysql_async::{Pool as MysqlAsyncPool, Row as MySqlAsyncRow};
+use mysql_async::{Pool as MysqlAsyncPool, QueryResult, Row as MySqlAsyncRow, Statement};
 
-use super::row::MySqlRow;
+use super::prepared::PreparedQuery;
+use super::row::{MySqlRow, ResultColumns};
+use super::stmt_cache::{ConnId, StmtCacheMirror, StmtCacheStats};
 use super::transaction::{to_mysql_isolation, MySqlTransaction};
-use super::types::{from_mysql_value, to_mysql_value};
+use super::types::{column_info, from_mysql_value, to_mysql_value, to_sql_literal};
+use crate::query::inline_params_with;
 
 /// A MySQL connection pool.
 ///
@@ -37,6 +45,395 @@ use super::types::{from_mysql_value, to_mysql_value};
 #[derive(Clone)]
 pub struct MySqlPool {
     inner: MysqlAsyncPool,
+    /// Settings and caches shared with clones and transactions
+    state: Arc<PoolState>,
+}
+
+/// How `execute` reports the warnings a statement raises.
+///
+/// Every [`ExecuteResult`] carries the warning count. Fetching the warnings
+/// themselves takes an extra `SHOW WARNINGS` round trip after statements
+/// that raised any. Scripts and [`Pool::execute_many`] only report counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WarningMode {
+    /// Only count warnings.
+    #[default]
+    Count,
+    /// Fetch warnings into [`ExecuteResult::warning_details`].
+    Fetch,
+    /// Fetch warnings, and fail with [`Error::Truncated`] if any reports
+    /// truncated data (see [`Warning::is_truncation`]).
+    TruncationAsError,
+}
+
+/// Report of the statement just run on `conn`.
+pub(crate) fn server_result(conn: &mysql_async::Conn) -> ExecuteResult {
+    ExecuteResult::from_server(
+        conn.affected_rows(),
+        conn.last_insert_id(),
+        conn.get_warnings(),
+        &conn.info(),
+    )
+}
+
+/// Statements bracketing a transactional [`Pool::execute_many`].
+pub(crate) struct TxStatements {
+    pub(crate) begin: &'static str,
+    pub(crate) commit: &'static str,
+    pub(crate) rollback: &'static str,
+}
+
+/// A transaction of its own, on a connection from the pool.
+const NEW_TRANSACTION: TxStatements = TxStatements {
+    begin: "START TRANSACTION",
+    commit: "COMMIT",
+    rollback: "ROLLBACK",
+};
+
+/// A savepoint within a transaction already open on the connection.
+pub(crate) const SAVEPOINT: TxStatements = TxStatements {
+    begin: "SAVEPOINT rdbi_execute_many",
+    commit: "RELEASE SAVEPOINT rdbi_execute_many",
+    rollback: "ROLLBACK TO SAVEPOINT rdbi_execute_many",
+};
+
+/// Settings and caches shared by a pool, its clones and its transactions.
+#[derive(Debug, Default)]
+pub(crate) struct PoolState {
+    /// Server version, detected on first use
+    pub(crate) server_version: OnceLock<ServerVersion>,
+    /// Session time zone set on every connection, if configured
+    pub(crate) time_zone: Option<FixedOffset>,
+    /// Default maximum number of rows for `fetch_all`, if configured
+    pub(crate) max_rows: Option<u64>,
+    /// Whether `execute` fetches warnings with `SHOW WARNINGS`
+    pub(crate) warning_mode: WarningMode,
+    /// Statement cache hit and miss counts
+    pub(crate) stmt_cache: StmtCacheMirror,
+    /// Connection options, for connections opened outside the pool
+    pub(crate) opts: mysql_async::Opts,
+}
+
+impl PoolState {
+    /// Convert query parameters for mysql_async.
+    pub(crate) fn mysql_params(&self, params: &[Value]) -> Result<Vec<mysql_async::Value>> {
+        self.check_params(params)?;
+        Ok(params
+            .iter()
+            .map(|v| to_mysql_value(v, self.time_zone))
+            .collect())
+    }
+
+    /// Check that every parameter can be sent.
+    ///
+    /// Values that failed to convert are refused, and so are
+    /// time-zone-aware values without a session time zone: the server would
+    /// take them in its own zone, which may not be the one they were
+    /// converted to.
+    fn check_params(&self, params: &[Value]) -> Result<()> {
+        if let Some(Value::Invalid { expected, actual }) =
+            params.iter().find(|v| matches!(v, Value::Invalid { .. }))
+        {
+            return Err(Error::TypeConversion {
+                expected,
+                actual: actual.clone(),
+            });
+        }
+        if self.time_zone.is_none() && params.iter().any(|v| matches!(v, Value::DateTimeTz(_))) {
+            return Err(Error::Query(
+                "Binding a time-zone-aware value requires a session time zone; \
+                 set one with MySqlPoolBuilder::time_zone"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Decode a result row.
+    pub(crate) fn decode_row(&self, row: MySqlAsyncRow) -> Result<MySqlRow> {
+        MySqlRow::from_mysql_row_in(row, self.time_zone)
+    }
+
+    /// Prepare `sql` through the connection's statement cache.
+    ///
+    /// The statement is counted in the cache statistics only once it has
+    /// been prepared, so SQL the server rejects is not recorded.
+    pub(crate) async fn prep<Q: Queryable + ConnId>(
+        &self,
+        conn: &mut Q,
+        sql: &str,
+    ) -> Result<Statement> {
+        let stmt = conn.prep(sql).await?;
+        self.stmt_cache.record(conn.conn_id(), sql);
+        Ok(stmt)
+    }
+
+    /// Run a query and decode its rows, failing once more than the row limit arrive.
+    pub(crate) async fn fetch_all<Q: Queryable + ConnId, T: FromRow>(
+        &self,
+        conn: &mut Q,
+        sql: &str,
+        params: &[Value],
+        limit: RowLimit,
+    ) -> Result<Vec<T>> {
+        let stmt = self.prep(conn, sql).await?;
+        self.fetch_all_with(conn, &stmt, params, limit).await
+    }
+
+    /// [`fetch_all`](Self::fetch_all) for a query or an already prepared statement.
+    pub(crate) async fn fetch_all_with<Q: Queryable, S: StatementLike, T: FromRow>(
+        &self,
+        conn: &mut Q,
+        stmt: S,
+        params: &[Value],
+        limit: RowLimit,
+    ) -> Result<Vec<T>> {
+        let max_rows = limit.or(self.max_rows);
+        let mut result = conn.exec_iter(stmt, self.mysql_params(params)?).await?;
+        let mut entities = Vec::new();
+        let mut columns = None;
+        while let Some(row) = result.next().await? {
+            if let Some(max) = max_rows {
+                if entities.len() as u64 >= max {
+                    return Err(Error::TooManyRows { max });
+                }
+            }
+            let columns = columns.get_or_insert_with(|| ResultColumns::new(row.columns_ref()));
+            let row = MySqlRow::decode(row, columns, self.time_zone)?;
+            entities.push(T::from_row(&row)?);
+        }
+        Ok(entities)
+    }
+
+    /// Run a query and hand its rows to `sink` as they are read.
+    pub(crate) async fn stream_rows<Q: Queryable + ConnId>(
+        &self,
+        conn: &mut Q,
+        sql: &str,
+        params: &[Value],
+        sink: &mut (dyn RowSink + Send),
+    ) -> Result<()> {
+        let stmt = self.prep(conn, sql).await?;
+        let mut result = conn.exec_iter(&stmt, self.mysql_params(params)?).await?;
+        self.feed_rows(&mut result, sink).await
+    }
+
+    /// Run a query over the text protocol, with its parameters inlined as
+    /// literals, and hand the rows of its first result set to `sink`.
+    pub(crate) async fn query_unprepared<Q: Queryable>(
+        &self,
+        conn: &mut Q,
+        sql: &str,
+        params: &[Value],
+        sink: &mut (dyn RowSink + Send),
+    ) -> Result<ExecuteResult> {
+        let sql = self.inline_params(sql, params)?;
+        let mut result = conn.query_iter(sql).await?;
+        let summary = ExecuteResult::from_server(
+            result.affected_rows(),
+            result.last_insert_id(),
+            result.warnings(),
+            &result.info(),
+        );
+        self.feed_rows(&mut result, sink).await?;
+        result.drop_result().await?;
+        self.with_warnings(conn, summary).await
+    }
+
+    /// Run a multi-statement script, returning one result per statement.
+    pub(crate) async fn execute_script<Q: Queryable>(
+        &self,
+        conn: &mut Q,
+        script: &str,
+    ) -> Result<Vec<ExecuteResult>> {
+        let mut results = Vec::new();
+        self.run_script(conn, script, &mut results).await?;
+        Ok(results)
+    }
+
+    /// Run a multi-statement script, pushing a result for each statement that succeeds.
+    ///
+    /// The server stops at the first failing statement, so on error
+    /// `results` holds the statements before it.
+    async fn run_script<Q: Queryable>(
+        &self,
+        conn: &mut Q,
+        script: &str,
+        results: &mut Vec<ExecuteResult>,
+    ) -> Result<()> {
+        let mut result = conn.query_iter(script).await?;
+        // `columns` is `Some` while a result set, even one without rows, is
+        // pending. It is `None` too when a later statement failed: the error
+        // stays pending until `drop_result` reads it.
+        while result.columns().is_some() {
+            results.push(ExecuteResult::from_server(
+                result.affected_rows(),
+                result.last_insert_id(),
+                result.warnings(),
+                &result.info(),
+            ));
+            while result.next().await?.is_some() {}
+        }
+        result.drop_result().await?;
+        Ok(())
+    }
+
+    /// Run statements on one connection, optionally in a transaction opened
+    /// and closed with `tx`.
+    pub(crate) async fn execute_many<Q: Queryable>(
+        &self,
+        conn: &mut Q,
+        queries: &[DynamicQuery],
+        mode: ExecuteMode,
+        on_error: OnError,
+        tx: &TxStatements,
+    ) -> Result<Vec<Result<ExecuteResult>>> {
+        if mode == ExecuteMode::Autocommit {
+            return Ok(self.pipeline(conn, queries, on_error).await);
+        }
+        conn.query_drop(tx.begin).await?;
+        let results = self.pipeline(conn, queries, on_error).await;
+        let stopped = on_error == OnError::Stop && results.last().is_some_and(Result::is_err);
+        conn.query_drop(if stopped { tx.rollback } else { tx.commit })
+            .await?;
+        Ok(results)
+    }
+
+    /// Send statements as scripts, resending the rest after a failure if
+    /// `on_error` says to continue.
+    async fn pipeline<Q: Queryable>(
+        &self,
+        conn: &mut Q,
+        queries: &[DynamicQuery],
+        on_error: OnError,
+    ) -> Vec<Result<ExecuteResult>> {
+        let mut statements: Vec<Result<String>> = queries
+            .iter()
+            .map(|query| {
+                query.check()?;
+                let sql = self.inline_params(query.sql(), query.params())?;
+                Ok(sql.trim_end().trim_end_matches(';').to_string())
+            })
+            .collect();
+        let mut results = Vec::with_capacity(statements.len());
+        let mut next = 0;
+        while next < statements.len() {
+            // Statements that failed to render split the script
+            let end = statements[next..]
+                .iter()
+                .position(Result::is_err)
+                .map_or(statements.len(), |i| next + i);
+            let error = if end == next {
+                std::mem::replace(&mut statements[next], Ok(String::new())).err()
+            } else {
+                let script = statements[next..end]
+                    .iter()
+                    .flatten()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(";\n");
+                let mut done = Vec::new();
+                let outcome = self.run_script(conn, &script, &mut done).await;
+                next = if outcome.is_ok() {
+                    end
+                } else {
+                    next + done.len()
+                };
+                results.extend(done.into_iter().map(Ok));
+                outcome.err()
+            };
+            if let Some(error) = error {
+                results.push(Err(error));
+                next += 1;
+                if on_error == OnError::Stop {
+                    break;
+                }
+            }
+        }
+        results
+    }
+
+    /// Inline `params` into `sql` as literals for the text protocol.
+    fn inline_params(&self, sql: &str, params: &[Value]) -> Result<String> {
+        self.check_params(params)?;
+        let (sql, placeholders) =
+            inline_params_with(sql, params, |v| to_sql_literal(v, self.time_zone));
+        if placeholders != params.len() {
+            return Err(Error::Query(format!(
+                "Query has {} placeholders but {} parameters were bound",
+                placeholders,
+                params.len()
+            )));
+        }
+        Ok(sql)
+    }
+
+    /// Hand the rows of the current result set to `sink` as they are read.
+    async fn feed_rows<P: Protocol>(
+        &self,
+        result: &mut QueryResult<'_, '_, P>,
+        sink: &mut (dyn RowSink + Send),
+    ) -> Result<()> {
+        let columns: Arc<[Column]> = result
+            .columns()
+            .map(|columns| columns.iter().map(column_info).collect())
+            .unwrap_or_else(|| Arc::from(Vec::new()));
+        sink.columns(&columns)?;
+
+        while let Some(row) = result.next().await? {
+            let mut values = Vec::with_capacity(columns.len());
+            for (i, column) in row.columns_ref().iter().enumerate() {
+                let mysql_value = row
+                    .as_ref(i)
+                    .ok_or_else(|| Error::ColumnNotFound(column.name_str().into_owned()))?
+                    .clone();
+                values.push(from_mysql_value(mysql_value, column, self.time_zone)?);
+            }
+            sink.row(&DynamicRow::new(columns.clone(), values)?)?;
+        }
+        Ok(())
+    }
+
+    /// Fetch the warnings of the statement just run on `conn`, if configured.
+    pub(crate) async fn with_warnings<Q: Queryable>(
+        &self,
+        conn: &mut Q,
+        mut result: ExecuteResult,
+    ) -> Result<ExecuteResult> {
+        if self.warning_mode == WarningMode::Count || result.warnings == 0 {
+            return Ok(result);
+        }
+        let rows: Vec<(String, u16, String)> = conn.query("SHOW WARNINGS").await?;
+        result.warning_details = rows
+            .into_iter()
+            .map(|(level, code, message)| Warning {
+                level,
+                code,
+                message,
+            })
+            .collect();
+        if self.warning_mode == WarningMode::TruncationAsError {
+            let truncations: Vec<Warning> = result
+                .warning_details
+                .iter()
+                .filter(|w| w.is_truncation())
+                .cloned()
+                .collect();
+            if !truncations.is_empty() {
+                return Err(Error::Truncated(truncations));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Decode the first column of a result row.
+    pub(crate) fn decode_scalar(&self, row: &MySqlAsyncRow) -> Result<Value> {
+        let mysql_value = row
+            .as_ref(0)
+            .ok_or_else(|| Error::Query("Expected at least one column".to_string()))?
+            .clone();
+        from_mysql_value(mysql_value, &row.columns_ref()[0], self.time_zone)
+    }
 }
 
 impl MySqlPool {
@@ -46,17 +443,70 @@ impl MySqlPool {
     pub fn new(url: &str) -> Result<Self> {
         let opts =
             mysql_async::Opts::from_url(url).map_err(|e| Error::Connection(e.to_string()))?;
-        let inner = MysqlAsyncPool::new(opts);
-        Ok(Self { inner })
+        Ok(Self::with_opts(opts))
     }
 
     /// Create a new MySQL connection pool with custom options.
     pub fn with_opts(opts: mysql_async::Opts) -> Self {
+        Self::with_state(opts, PoolState::default())
+    }
+
+    fn with_state(opts: mysql_async::Opts, state: PoolState) -> Self {
+        let state = PoolState {
+            stmt_cache: StmtCacheMirror::new(&opts),
+            opts: opts.clone(),
+            ..state
+        };
         Self {
             inner: MysqlAsyncPool::new(opts),
+            state: Arc::new(state),
         }
     }
 
+    /// Take a connection from the pool.
+    async fn get_conn(&self) -> Result<mysql_async::Conn> {
+        let conn = self.inner.get_conn().await?;
+        self.state.stmt_cache.checkout(conn.id());
+        Ok(conn)
+    }
+
+    /// Prepare a statement once on a connection taken from the pool, for
+    /// repeated execution.
+    ///
+    /// The connection stays with the returned [`PreparedQuery`] until it is
+    /// closed or dropped.
+    ///
+    /// ```ignore
+    /// let mut insert = pool.prepare("INSERT INTO tags (name) VALUES (?)").await?;
+    /// insert
+    ///     .execute_batch(names.iter().map(|name| vec![name.to_value()]))
+    ///     .await?;
+    /// insert.close().await?;
+    /// ```
+    pub async fn prepare(&self, sql: &str) -> Result<PreparedQuery> {
+        let mut conn = self.get_conn().await?;
+        let stmt = self.state.prep(&mut conn, sql).await?;
+        Ok(PreparedQuery::new(conn, stmt, sql, self.state.clone()))
+    }
+
+    /// Estimated hit and miss counts of the prepared statement cache.
+    ///
+    /// Each connection caches up to
+    /// [`stmt_cache_size`](MySqlPoolBuilder::stmt_cache_size) statements.
+    /// With [`reset_connection`](MySqlPoolBuilder::reset_connection) on (the
+    /// default), the cache is cleared whenever the connection goes back to
+    /// the pool, so only statements repeated within a transaction or on a
+    /// [`PreparedQuery`]'s connection can hit. See [`StmtCacheStats`] for how
+    /// the counts are estimated.
+    pub fn stmt_cache_stats(&self) -> StmtCacheStats {
+        self.state.stmt_cache.stats()
+    }
+
+    /// Get the session time zone configured with [`MySqlPoolBuilder::time_zone`].
+    pub fn time_zone(&self) -> Option<FixedOffset> {
+        self.state.time_zone
+    }
+
     /// Get a reference to the underlying mysql_async pool.
     pub fn inner(&self) -> &MysqlAsyncPool {
         &self.inner
@@ -94,6 +544,12 @@ pub struct MySqlPoolBuilder {
     pool_max: Option<usize>,
     inactive_connection_ttl: Option<Duration>,
     abs_conn_ttl: Option<Duration>,
+    time_zone: Option<FixedOffset>,
+    max_rows: Option<u64>,
+    stmt_cache_size: Option<usize>,
+    reset_connection: Option<bool>,
+    client_found_rows: Option<bool>,
+    warning_mode: WarningMode,
 }
 
 impl MySqlPoolBuilder {
@@ -105,6 +561,12 @@ impl MySqlPoolBuilder {
             pool_max: None,
             inactive_connection_ttl: None,
             abs_conn_ttl: None,
+            time_zone: None,
+            max_rows: None,
+            stmt_cache_size: None,
+            reset_connection: None,
+            client_found_rows: None,
+            warning_mode: WarningMode::Count,
         }
     }
 
@@ -132,10 +594,81 @@ impl MySqlPoolBuilder {
         self
     }
 
+    /// Set the session time zone of every connection, e.g. `chrono::Utc`.
+    ///
+    /// Runs `SET time_zone` on connect and after every connection reset,
+    /// after any setup statements from the URL, so the server converts
+    /// TIMESTAMP columns in this zone. `DateTime<Utc>` and `DateTime<FixedOffset>`
+    /// parameters are written as wall-clock times in this zone, and DATETIME
+    /// and TIMESTAMP columns are read back as instants in it.
+    ///
+    /// Without a session time zone, binding a `DateTime<Utc>`,
+    /// `DateTime<FixedOffset>` (or `time::OffsetDateTime`) fails, since the
+    /// server would interpret it in its own zone. Columns are then read as
+    /// naive values; reading one as `DateTime<Utc>` takes it as UTC.
+    pub fn time_zone<O: Offset>(mut self, offset: O) -> Self {
+        self.time_zone = Some(offset.fix());
+        self
+    }
+
+    /// Set the maximum number of rows `fetch_all` may return.
+    ///
+    /// Queries returning more fail with [`Error::TooManyRows`] as soon as the
+    /// extra row arrives, instead of loading the whole result. Generated
+    /// `find_all` methods are covered too. Override it per query with
+    /// [`Query::max_rows`](crate::Query::max_rows) or
+    /// [`Query::unlimited_rows`](crate::Query::unlimited_rows).
+    pub fn max_rows(mut self, max_rows: u64) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Set the number of prepared statements cached per connection (default 32).
+    ///
+    /// Least recently used statements are closed to make room. `0` disables
+    /// the cache, so every query is prepared again. See
+    /// [`MySqlPool::stmt_cache_stats`] for hit and miss counts.
+    pub fn stmt_cache_size(mut self, size: usize) -> Self {
+        self.stmt_cache_size = Some(size);
+        self
+    }
+
+    /// Set whether connections are reset when they go back to the pool (default `true`).
+    ///
+    /// A reset clears session variables, temporary tables, user locks and
+    /// the connection's statement cache, so the cache only pays off for
+    /// statements repeated within one checkout. Turning it off keeps
+    /// prepared statements across checkouts, but anything one caller leaves
+    /// in the session, such as a `SET` or a temporary table, is then seen by
+    /// the next caller handed that connection.
+    pub fn reset_connection(mut self, reset: bool) -> Self {
+        self.reset_connection = Some(reset);
+        self
+    }
+
+    /// Set whether an `UPDATE`'s affected rows count matched rows, even
+    /// those already holding the new values (`CLIENT_FOUND_ROWS`).
+    ///
+    /// Off by default, so an `UPDATE` that changes nothing reports 0 rows,
+    /// the same as one that matched nothing. Either way,
+    /// [`ExecuteResult::matched_rows`] and [`ExecuteResult::changed_rows`]
+    /// report both counts.
+    pub fn client_found_rows(mut self, found_rows: bool) -> Self {
+        self.client_found_rows = Some(found_rows);
+        self
+    }
+
+    /// Set how `execute` reports warnings (default [`WarningMode::Count`]).
+    pub fn warning_mode(mut self, mode: WarningMode) -> Self {
+        self.warning_mode = mode;
+        self
+    }
+
     /// Build the [`MySqlPool`] with the configured options.
     pub fn build(self) -> Result<MySqlPool> {
         let opts =
             mysql_async::Opts::from_url(&self.url).map_err(|e| Error::Connection(e.to_string()))?;
+        let mut setup = opts.setup().to_vec();
         let mut builder = mysql_async::OptsBuilder::from_opts(opts);
 
         let mut pool_opts = mysql_async::PoolOpts::default();
@@ -157,48 +690,63 @@ impl MySqlPoolBuilder {
             pool_opts = pool_opts.with_abs_conn_ttl(Some(ttl));
         }
 
+        if let Some(reset) = self.reset_connection {
+            pool_opts = pool_opts.with_reset_connection(reset);
+        }
+
         builder = builder.pool_opts(pool_opts);
 
-        Ok(MySqlPool {
-            inner: MysqlAsyncPool::new(builder),
-        })
+        if let Some(size) = self.stmt_cache_size {
+            builder = builder.stmt_cache_size(size);
+        }
+
+        if let Some(found_rows) = self.client_found_rows {
+            builder = builder.client_found_rows(found_rows);
+        }
+
+        if let Some(offset) = self.time_zone {
+            // `setup` rather than `init`: it is run again after the pool
+            // resets a connection, which puts the session zone back
+            setup.push(format!("SET time_zone = '{}'", offset));
+            builder = builder.setup(setup);
+        }
+
+        let state = PoolState {
+            time_zone: self.time_zone,
+            max_rows: self.max_rows,
+            warning_mode: self.warning_mode,
+            ..PoolState::default()
+        };
+        Ok(MySqlPool::with_state(builder.into(), state))
     }
 }
 
 #[async_trait]
 impl Pool for MySqlPool {
     async fn execute(&self, sql: &str, params: Vec<Value>) -> Result<ExecuteResult> {
-        let mut conn = self.inner.get_conn().await?;
-
-        let mysql_params: Vec<mysql_async::Value> = params.iter().map(to_mysql_value).collect();
+        let mut conn = self.get_conn().await?;
 
-        let _result = conn.exec_drop(sql, mysql_params).await?;
+        let mysql_params = self.state.mysql_params(&params)?;
 
-        // Get affected rows and last insert id from connection
-        let rows_affected = conn.affected_rows();
-        let last_insert_id = conn.last_insert_id();
+        let stmt = self.state.prep(&mut conn, sql).await?;
+        conn.exec_drop(&stmt, mysql_params).await?;
 
-        Ok(ExecuteResult {
-            rows_affected,
-            last_insert_id,
-        })
+        let result = server_result(&conn);
+        self.state.with_warnings(&mut conn, result).await
     }
 
     async fn fetch_all<T: FromRow + Send>(&self, sql: &str, params: Vec<Value>) -> Result<Vec<T>> {
-        let mut conn = self.inner.get_conn().await?;
-
-        let mysql_params: Vec<mysql_async::Value> = params.iter().map(to_mysql_value).collect();
-
-        let rows: Vec<MySqlAsyncRow> = conn.exec(sql, mysql_params).await?;
-
-        let mut results = Vec::with_capacity(rows.len());
-        for row in rows {
-            let rdbi_row = MySqlRow::from_mysql_row(row)?;
-            let entity = T::from_row(&rdbi_row)?;
-            results.push(entity);
-        }
+        self.fetch_all_limited(sql, params, RowLimit::Default).await
+    }
 
-        Ok(results)
+    async fn fetch_all_limited<T: FromRow + Send>(
+        &self,
+        sql: &str,
+        params: Vec<Value>,
+        limit: RowLimit,
+    ) -> Result<Vec<T>> {
+        let mut conn = self.get_conn().await?;
+        self.state.fetch_all(&mut conn, sql, &params, limit).await
     }
 
     async fn fetch_optional<T: FromRow + Send>(
@@ -206,15 +754,16 @@ impl Pool for MySqlPool {
         sql: &str,
         params: Vec<Value>,
     ) -> Result<Option<T>> {
-        let mut conn = self.inner.get_conn().await?;
+        let mut conn = self.get_conn().await?;
 
-        let mysql_params: Vec<mysql_async::Value> = params.iter().map(to_mysql_value).collect();
+        let mysql_params = self.state.mysql_params(&params)?;
 
-        let row: Option<MySqlAsyncRow> = conn.exec_first(sql, mysql_params).await?;
+        let stmt = self.state.prep(&mut conn, sql).await?;
+        let row: Option<MySqlAsyncRow> = conn.exec_first(&stmt, mysql_params).await?;
 
         match row {
             Some(row) => {
-                let rdbi_row = MySqlRow::from_mysql_row(row)?;
+                let rdbi_row = self.state.decode_row(row)?;
                 Ok(Some(T::from_row(&rdbi_row)?))
             }
             None => Ok(None),
@@ -228,25 +777,70 @@ impl Pool for MySqlPool {
     }
 
     async fn fetch_scalar<T: FromValue + Send>(&self, sql: &str, params: Vec<Value>) -> Result<T> {
-        let mut conn = self.inner.get_conn().await?;
+        let mut conn = self.get_conn().await?;
 
-        let mysql_params: Vec<mysql_async::Value> = params.iter().map(to_mysql_value).collect();
+        let mysql_params = self.state.mysql_params(&params)?;
 
-        let row: Option<MySqlAsyncRow> = conn.exec_first(sql, mysql_params).await?;
+        let stmt = self.state.prep(&mut conn, sql).await?;
+        let row: Option<MySqlAsyncRow> = conn.exec_first(&stmt, mysql_params).await?;
 
         match row {
-            Some(row) => {
-                // Get the first column value
-                let mysql_value = row
-                    .as_ref(0)
-                    .ok_or_else(|| Error::Query("Expected at least one column".to_string()))?
-                    .clone();
-                let value = from_mysql_value(mysql_value)?;
-                T::from_value(value)
-            }
+            Some(row) => T::from_value(self.state.decode_scalar(&row)?),
             None => Err(Error::Query("Expected one row, found none".to_string())),
         }
     }
+
+    async fn stream_rows(
+        &self,
+        sql: &str,
+        params: Vec<Value>,
+        sink: &mut (dyn RowSink + Send),
+    ) -> Result<()> {
+        let mut conn = self.get_conn().await?;
+        self.state.stream_rows(&mut conn, sql, &params, sink).await
+    }
+
+    async fn query_unprepared(
+        &self,
+        sql: &str,
+        params: Vec<Value>,
+        sink: &mut (dyn RowSink + Send),
+    ) -> Result<ExecuteResult> {
+        let mut conn = self.get_conn().await?;
+        self.state
+            .query_unprepared(&mut conn, sql, &params, sink)
+            .await
+    }
+
+    async fn execute_script(&self, script: &str) -> Result<Vec<ExecuteResult>> {
+        let mut conn = self.get_conn().await?;
+        self.state.execute_script(&mut conn, script).await
+    }
+
+    async fn execute_many(
+        &self,
+        queries: Vec<DynamicQuery>,
+        mode: ExecuteMode,
+        on_error: OnError,
+    ) -> Result<Vec<Result<ExecuteResult>>> {
+        let mut conn = self.get_conn().await?;
+        self.state
+            .execute_many(&mut conn, &queries, mode, on_error, &NEW_TRANSACTION)
+            .await
+    }
+
+    fn max_rows(&self) -> Option<u64> {
+        self.state.max_rows
+    }
+
+    async fn server_version(&self) -> Result<Option<ServerVersion>> {
+        if let Some(version) = self.state.server_version.get() {
+            return Ok(Some(*version));
+        }
+        let mut conn = self.get_conn().await?;
+        let version = detect_server_version(&mut conn).await?;
+        Ok(Some(*self.state.server_version.get_or_init(|| version)))
+    }
 }
 
 // Implement Pool for references to MySqlPool
@@ -260,6 +854,15 @@ impl Pool for &MySqlPool {
         (*self).fetch_all(sql, params).await
     }
 
+    async fn fetch_all_limited<T: FromRow + Send>(
+        &self,
+        sql: &str,
+        params: Vec<Value>,
+        limit: RowLimit,
+    ) -> Result<Vec<T>> {
+        (*self).fetch_all_limited(sql, params, limit).await
+    }
+
     async fn fetch_optional<T: FromRow + Send>(
         &self,
         sql: &str,
@@ -275,21 +878,84 @@ impl Pool for &MySqlPool {
     async fn fetch_scalar<T: FromValue + Send>(&self, sql: &str, params: Vec<Value>) -> Result<T> {
         (*self).fetch_scalar(sql, params).await
     }
+
+    async fn stream_rows(
+        &self,
+        sql: &str,
+        params: Vec<Value>,
+        sink: &mut (dyn RowSink + Send),
+    ) -> Result<()> {
+        (*self).stream_rows(sql, params, sink).await
+    }
+
+    async fn query_unprepared(
+        &self,
+        sql: &str,
+        params: Vec<Value>,
+        sink: &mut (dyn RowSink + Send),
+    ) -> Result<ExecuteResult> {
+        (*self).query_unprepared(sql, params, sink).await
+    }
+
+    async fn execute_script(&self, script: &str) -> Result<Vec<ExecuteResult>> {
+        (*self).execute_script(script).await
+    }
+
+    async fn execute_many(
+        &self,
+        queries: Vec<DynamicQuery>,
+        mode: ExecuteMode,
+        on_error: OnError,
+    ) -> Result<Vec<Result<ExecuteResult>>> {
+        (*self).execute_many(queries, mode, on_error).await
+    }
+
+    fn max_rows(&self) -> Option<u64> {
+        (*self).max_rows()
+    }
+
+    async fn server_version(&self) -> Result<Option<ServerVersion>> {
+        (*self).server_version().await
+    }
 }
 
 impl Transactional for MySqlPool {
     type Tx = MySqlTransaction;
 
     async fn begin(&self) -> Result<Self::Tx> {
-        let tx = self.inner.start_transaction(Default::default()).await?;
-        Ok(MySqlTransaction::new(tx))
+        self.begin_with_options(TransactionOptions::default()).await
     }
 
     async fn begin_with(&self, level: IsolationLevel) -> Result<Self::Tx> {
+        self.begin_with_options(level.into()).await
+    }
+
+    async fn begin_with_options(&self, options: TransactionOptions) -> Result<Self::Tx> {
+        let started = Instant::now();
         let mut opts = mysql_async::TxOpts::default();
-        opts.with_isolation_level(Some(to_mysql_isolation(level)));
-        let tx = self.inner.start_transaction(opts).await?;
-        Ok(MySqlTransaction::new(tx))
+        opts.with_isolation_level(options.isolation_level.map(to_mysql_isolation))
+            .with_readonly(options.access_mode.map(|mode| mode == AccessMode::ReadOnly))
+            .with_consistent_snapshot(options.consistent_snapshot);
+        let mut tx = self.inner.start_transaction(opts).await?;
+        self.state.stmt_cache.checkout(tx.id());
+
+        if let Some(timeout) = options.lock_wait_timeout {
+            tx.query_drop(format!(
+                "SET @rdbi_lock_wait_timeout = @@SESSION.innodb_lock_wait_timeout, \
+                 SESSION innodb_lock_wait_timeout = {}",
+                timeout.as_secs().max(1)
+            ))
+            .await?;
+        }
+
+        Ok(MySqlTransaction::new(
+            tx,
+            self.state.clone(),
+            options
+                .deadline
+                .and_then(|deadline| started.checked_add(deadline)),
+            options.lock_wait_timeout.is_some(),
+        ))
     }
 
     async fn in_transaction<R, E, F>(&self, f: F) -> std::result::Result<R, E>
@@ -307,7 +973,7 @@ impl Transactional for MySqlPool {
 
     async fn in_transaction_with<R, E, F>(
         &self,
-        level: IsolationLevel,
+        options: impl Into<TransactionOptions> + Send,
         f: F,
     ) -> std::result::Result<R, E>
     where
@@ -319,7 +985,10 @@ impl Transactional for MySqlPool {
                 Box<dyn std::future::Future<Output = std::result::Result<R, E>> + Send + 'a>,
             > + Send,
     {
-        let tx = self.begin_with(level).await.map_err(E::from)?;
+        let tx = self
+            .begin_with_options(options.into())
+            .await
+            .map_err(E::from)?;
 
         match f(&tx).await {
             Ok(result) => {
@@ -347,3 +1016,29 @@ impl Transactional for MySqlPool {
         f(self).await
     }
 }
+
+/// Detect the server version on an open connection.
+///
+/// mysql_async reports MariaDB's own version numbers, so the `VERSION()`
+/// string is checked to tell the two servers apart.
+pub(crate) async fn detect_server_version<Q: Queryable>(conn: &mut Q) -> Result<ServerVersion> {
+    let version: Option<String> = conn.query_first("SELECT VERSION()").await?;
+    let version = version.unwrap_or_default();
+    let is_mariadb = version.contains("MariaDB");
+
+    // Strip MariaDB's "5.5.5-" replication prefix before parsing
+    let numbers = version
+        .strip_prefix("5.5.5-")
+        .unwrap_or(&version)
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .next()
+        .unwrap_or_default();
+    let mut parts = numbers.split('.').map(|p| p.parse::<u16>().unwrap_or(0));
+
+    Ok(ServerVersion {
+        major: parts.next().unwrap_or(0),
+        minor: parts.next().unwrap_or(0),
+        patch: parts.next().unwrap_or(0),
+        is_mariadb,
+    })
+}
@@ -0,0 +1,133 @@
+//! Prepared statement handles
+
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+use crate::traits::{ExecuteResult, FromRow, FromValue, RowLimit};
+use crate::value::Value;
+use mysql_async::prelude::*;
+use mysql_async::{Conn, Row as MySqlAsyncRow, Statement};
+
+use super::pool::{server_result, PoolState};
+
+/// A statement prepared once and executed any number of times.
+///
+/// Created with [`MySqlPool::prepare`](crate::MySqlPool::prepare). The
+/// handle keeps the connection it was prepared on, so every execution skips
+/// the prepare round trip. The connection goes back to the pool when the
+/// handle is closed or dropped.
+///
+/// # Example
+///
+/// ```ignore
+/// use rdbi::ToValue;
+///
+/// let mut find = pool.prepare("SELECT * FROM users WHERE id = ?").await?;
+/// for id in ids {
+///     let user: Option<Users> = find.fetch_optional(vec![id.to_value()]).await?;
+/// }
+/// find.close().await?;
+/// ```
+pub struct PreparedQuery {
+    conn: Conn,
+    stmt: Statement,
+    sql: String,
+    /// Settings and caches shared with the pool that prepared this statement
+    state: Arc<PoolState>,
+}
+
+impl PreparedQuery {
+    pub(crate) fn new(conn: Conn, stmt: Statement, sql: &str, state: Arc<PoolState>) -> Self {
+        Self {
+            conn,
+            stmt,
+            sql: sql.to_string(),
+            state,
+        }
+    }
+
+    /// Get the SQL string.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// Number of `?` placeholders the statement takes.
+    pub fn param_count(&self) -> usize {
+        self.stmt.num_params() as usize
+    }
+
+    /// Execute the statement and return the result.
+    pub async fn execute(&mut self, params: Vec<Value>) -> Result<ExecuteResult> {
+        let mysql_params = self.state.mysql_params(&params)?;
+        self.conn.exec_drop(&self.stmt, mysql_params).await?;
+        let result = server_result(&self.conn);
+        self.state.with_warnings(&mut self.conn, result).await
+    }
+
+    /// Execute the statement once per parameter set.
+    ///
+    /// Returns the results added up: total affected rows and warnings, and
+    /// the last insert ID of the last execution that produced one. Stops at
+    /// the first error; the executions before it are not rolled back unless
+    /// run in a transaction.
+    pub async fn execute_batch<I>(&mut self, param_sets: I) -> Result<ExecuteResult>
+    where
+        I: IntoIterator<Item = Vec<Value>>,
+    {
+        let mut total = ExecuteResult::default();
+        for params in param_sets {
+            total.merge(self.execute(params).await?);
+        }
+        Ok(total)
+    }
+
+    /// Fetch all matching rows, subject to the pool's row limit.
+    pub async fn fetch_all<T: FromRow>(&mut self, params: Vec<Value>) -> Result<Vec<T>> {
+        self.state
+            .fetch_all_with(&mut self.conn, &self.stmt, &params, RowLimit::Default)
+            .await
+    }
+
+    /// Fetch a single optional row.
+    pub async fn fetch_optional<T: FromRow>(&mut self, params: Vec<Value>) -> Result<Option<T>> {
+        let mysql_params = self.state.mysql_params(&params)?;
+        let row: Option<MySqlAsyncRow> = self.conn.exec_first(&self.stmt, mysql_params).await?;
+        match row {
+            Some(row) => Ok(Some(T::from_row(&self.state.decode_row(row)?)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetch exactly one row.
+    pub async fn fetch_one<T: FromRow>(&mut self, params: Vec<Value>) -> Result<T> {
+        self.fetch_optional(params)
+            .await?
+            .ok_or_else(|| Error::Query("Expected one row, found none".to_string()))
+    }
+
+    /// Fetch a scalar value (first column of first row).
+    pub async fn fetch_scalar<T: FromValue>(&mut self, params: Vec<Value>) -> Result<T> {
+        let mysql_params = self.state.mysql_params(&params)?;
+        let row: Option<MySqlAsyncRow> = self.conn.exec_first(&self.stmt, mysql_params).await?;
+        match row {
+            Some(row) => T::from_value(self.state.decode_scalar(&row)?),
+            None => Err(Error::Query("Expected one row, found none".to_string())),
+        }
+    }
+
+    /// Close the statement on the server and return the connection to the pool.
+    ///
+    /// Dropping the handle also returns the connection, whose reset then
+    /// discards the statement.
+    pub async fn close(self) -> Result<()> {
+        let Self {
+            mut conn,
+            stmt,
+            sql,
+            state,
+        } = self;
+        state.stmt_cache.remove(conn.id(), &sql);
+        conn.close(stmt).await?;
+        Ok(())
+    }
+}
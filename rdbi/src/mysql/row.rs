@@ -1,56 +1,117 @@
 //! MySQL row implementation
 
 use crate::error::{Error, Result};
+use crate::row::{Column, DynamicRow};
 use crate::traits::Row;
 use crate::value::Value;
+use chrono::FixedOffset;
 use mysql_async::Row as MySqlAsyncRow;
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use super::types::from_mysql_value;
+use super::types::{column_info, from_mysql_value};
 
 /// A MySQL database row.
 ///
 /// This wraps the mysql_async Row and provides column-name-based access
 /// with automatic type conversion.
 pub struct MySqlRow {
-    /// Column values indexed by column name
-    values: HashMap<String, Value>,
+    /// All columns and values, in select order
+    row: DynamicRow,
+    /// Position of each column name; a repeated name maps to its last column
+    index: Arc<HashMap<String, usize>>,
+}
+
+/// Columns of a result set, decoded once and shared by all of its rows.
+pub(crate) struct ResultColumns {
+    columns: Arc<[Column]>,
+    index: Arc<HashMap<String, usize>>,
+}
+
+impl ResultColumns {
+    pub(crate) fn new(columns: &[mysql_async::Column]) -> Self {
+        let columns: Arc<[Column]> = columns.iter().map(column_info).collect();
+        let index = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| (column.name.clone(), i))
+            .collect();
+        Self {
+            columns,
+            index: Arc::new(index),
+        }
+    }
 }
 
 impl MySqlRow {
     /// Create a new MySqlRow from a mysql_async Row.
     pub fn from_mysql_row(row: MySqlAsyncRow) -> Result<Self> {
-        let columns = row.columns_ref();
-        let mut values = HashMap::with_capacity(columns.len());
+        Self::from_mysql_row_in(row, None)
+    }
+
+    /// Create a new MySqlRow, decoding date-times in the given session time zone.
+    pub(crate) fn from_mysql_row_in(
+        row: MySqlAsyncRow,
+        time_zone: Option<FixedOffset>,
+    ) -> Result<Self> {
+        let columns = ResultColumns::new(row.columns_ref());
+        Self::decode(row, &columns, time_zone)
+    }
 
-        for (i, column) in columns.iter().enumerate() {
-            let column_name = column.name_str().to_string();
+    /// Decode a row of a result set whose columns are already decoded.
+    pub(crate) fn decode(
+        row: MySqlAsyncRow,
+        columns: &ResultColumns,
+        time_zone: Option<FixedOffset>,
+    ) -> Result<Self> {
+        let mut values = Vec::with_capacity(columns.columns.len());
+        for (i, column) in row.columns_ref().iter().enumerate() {
             let mysql_value = row
                 .as_ref(i)
-                .ok_or_else(|| Error::ColumnNotFound(column_name.clone()))?
+                .ok_or_else(|| Error::ColumnNotFound(column.name_str().into_owned()))?
                 .clone();
-            let value = from_mysql_value(mysql_value)?;
-            values.insert(column_name, value);
+            values.push(from_mysql_value(mysql_value, column, time_zone)?);
         }
 
-        Ok(Self { values })
+        Ok(Self {
+            row: DynamicRow::new(columns.columns.clone(), values)?,
+            index: columns.index.clone(),
+        })
+    }
+
+    /// Convert into an untyped row that keeps every column.
+    pub fn into_dynamic(self) -> DynamicRow {
+        self.row
     }
 }
 
 impl Row for MySqlRow {
     fn get_value(&self, column: &str) -> Result<Value> {
-        self.values
+        self.index
             .get(column)
-            .cloned()
+            .map(|&i| self.row.values()[i].clone())
             .ok_or_else(|| Error::ColumnNotFound(column.to_string()))
     }
+
+    fn column_names(&self) -> Vec<&str> {
+        self.row.column_names()
+    }
+
+    fn to_dynamic_row(&self) -> Result<DynamicRow> {
+        Ok(self.row.clone())
+    }
 }
 
 impl Row for &MySqlRow {
     fn get_value(&self, column: &str) -> Result<Value> {
-        self.values
-            .get(column)
-            .cloned()
-            .ok_or_else(|| Error::ColumnNotFound(column.to_string()))
+        (*self).get_value(column)
+    }
+
+    fn column_names(&self) -> Vec<&str> {
+        (*self).column_names()
+    }
+
+    fn to_dynamic_row(&self) -> Result<DynamicRow> {
+        (*self).to_dynamic_row()
     }
 }
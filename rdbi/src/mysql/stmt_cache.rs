@@ -0,0 +1,161 @@
+//! Prepared statement cache bookkeeping
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use indexmap::IndexSet;
+use mysql_async::{Conn, Opts};
+
+/// Estimated hit and miss counts of a pool's prepared statement cache.
+///
+/// A hit is a statement that was already prepared on the connection it ran
+/// on; a miss had to be prepared first, costing an extra round trip.
+///
+/// mysql_async does not report cache hits, so the counts come from replaying
+/// its per-connection LRU caches, and are estimates: they can drift from the
+/// real caches, e.g. after connections are closed and reopened by the pool.
+/// Only statements that were prepared successfully are counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StmtCacheStats {
+    /// Statements found in their connection's cache
+    pub estimated_hits: u64,
+    /// Statements that had to be prepared
+    pub estimated_misses: u64,
+}
+
+impl StmtCacheStats {
+    /// Estimated fraction of statements that were cache hits, or `0.0` if
+    /// none ran yet.
+    pub fn estimated_hit_ratio(&self) -> f64 {
+        let total = self.estimated_hits + self.estimated_misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.estimated_hits as f64 / total as f64
+        }
+    }
+}
+
+/// Connection identity, for statement caches kept per connection.
+pub(crate) trait ConnId {
+    fn conn_id(&self) -> u32;
+}
+
+impl ConnId for Conn {
+    fn conn_id(&self) -> u32 {
+        self.id()
+    }
+}
+
+impl ConnId for mysql_async::Transaction<'_> {
+    fn conn_id(&self) -> u32 {
+        self.id()
+    }
+}
+
+/// Mirror of mysql_async's per-connection LRU statement caches.
+///
+/// mysql_async does not report whether a statement came from its cache, so
+/// the same LRU is replayed here, keyed by connection id, to count hits and
+/// misses.
+#[derive(Debug, Default)]
+pub(crate) struct StmtCacheMirror {
+    /// Statements cached per connection (`0` disables caching)
+    capacity: usize,
+    /// Whether connections are reset, clearing their cache, between checkouts
+    reset_on_checkout: bool,
+    /// Upper bound on live connections, beyond which the stalest entry is dropped
+    max_connections: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    connections: Mutex<Connections>,
+}
+
+#[derive(Debug, Default)]
+struct Connections {
+    tick: u64,
+    by_id: HashMap<u32, ConnCache>,
+}
+
+#[derive(Debug, Default)]
+struct ConnCache {
+    last_used: u64,
+    /// Cached SQL, least recently used first
+    statements: IndexSet<String>,
+}
+
+impl StmtCacheMirror {
+    /// Mirror the caches of a pool built from `opts`.
+    pub(crate) fn new(opts: &Opts) -> Self {
+        Self {
+            capacity: opts.stmt_cache_size(),
+            reset_on_checkout: opts.pool_opts().reset_connection(),
+            max_connections: opts.pool_opts().constraints().max(),
+            ..Self::default()
+        }
+    }
+
+    /// Note that a connection was taken from the pool.
+    pub(crate) fn checkout(&self, conn_id: u32) {
+        if self.reset_on_checkout {
+            self.lock().by_id.remove(&conn_id);
+        }
+    }
+
+    /// Note that `sql` was prepared (or found cached) on a connection.
+    pub(crate) fn record(&self, conn_id: u32, sql: &str) {
+        let hit = self.capacity > 0 && self.touch(conn_id, sql);
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Note that a statement was closed, dropping it from its connection's cache.
+    pub(crate) fn remove(&self, conn_id: u32, sql: &str) {
+        if let Some(cache) = self.lock().by_id.get_mut(&conn_id) {
+            cache.statements.shift_remove(sql);
+        }
+    }
+
+    /// Counts so far.
+    pub(crate) fn stats(&self) -> StmtCacheStats {
+        StmtCacheStats {
+            estimated_hits: self.hits.load(Ordering::Relaxed),
+            estimated_misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Mark `sql` most recently used on the connection, returning whether it was cached.
+    fn touch(&self, conn_id: u32, sql: &str) -> bool {
+        let mut connections = self.lock();
+        connections.tick += 1;
+        let tick = connections.tick;
+        let cache = connections.by_id.entry(conn_id).or_default();
+        cache.last_used = tick;
+        let hit = cache.statements.shift_remove(sql);
+        cache.statements.insert(sql.to_string());
+        if cache.statements.len() > self.capacity {
+            cache.statements.shift_remove_index(0);
+        }
+
+        // Connections closed by the pool leave entries behind; there are never
+        // more live connections than the pool's maximum
+        if connections.by_id.len() > self.max_connections.max(1) {
+            let stalest = connections
+                .by_id
+                .iter()
+                .min_by_key(|(_, cache)| cache.last_used)
+                .map(|(id, _)| *id);
+            if let Some(id) = stalest {
+                connections.by_id.remove(&id);
+            }
+        }
+        hit
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Connections> {
+        self.connections
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -1,15 +1,22 @@
 //! MySQL transaction implementation
 
 use crate::error::{Error, Result};
-use crate::traits::{ExecuteResult, FromRow, FromValue, IsolationLevel, Pool, Transaction};
+use crate::query::DynamicQuery;
+use crate::row::RowSink;
+use crate::traits::{
+    ExecuteMode, ExecuteResult, FromRow, FromValue, IsolationLevel, OnError, Pool, RowLimit,
+    ServerVersion, Transaction,
+};
 use crate::value::Value;
 use async_trait::async_trait;
 use mysql_async::prelude::*;
 use mysql_async::Row as MySqlAsyncRow;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::Mutex;
 
-use super::row::MySqlRow;
-use super::types::{from_mysql_value, to_mysql_value};
+use super::pool::{detect_server_version, server_result, PoolState, SAVEPOINT};
 
 /// A MySQL transaction.
 ///
@@ -29,16 +36,111 @@ pub struct MySqlTransaction {
     // but the Pool trait uses &self. The lock is uncontended since a transaction
     // is used by a single task at a time.
     inner: Mutex<Option<mysql_async::Transaction<'static>>>,
+    /// Settings and caches shared with the pool that started this transaction
+    state: Arc<PoolState>,
+    /// When statements and commit start failing, if a deadline was set
+    deadline: Option<Instant>,
+    /// Whether `innodb_lock_wait_timeout` was changed for this transaction
+    restore_lock_wait_timeout: bool,
 }
 
 impl MySqlTransaction {
     /// Create a new MySqlTransaction from a mysql_async Transaction.
-    pub(crate) fn new(tx: mysql_async::Transaction<'static>) -> Self {
+    pub(crate) fn new(
+        tx: mysql_async::Transaction<'static>,
+        state: Arc<PoolState>,
+        deadline: Option<Instant>,
+        restore_lock_wait_timeout: bool,
+    ) -> Self {
         Self {
             inner: Mutex::new(Some(tx)),
+            state,
+            deadline,
+            restore_lock_wait_timeout,
         }
     }
 
+    /// Get the open transaction from its slot.
+    /// Returns an error if it has been consumed or is past its deadline.
+    fn open<'a>(
+        &self,
+        inner: &'a mut Option<mysql_async::Transaction<'static>>,
+    ) -> Result<&'a mut mysql_async::Transaction<'static>> {
+        if self.past_deadline() {
+            return Err(Error::DeadlineExceeded);
+        }
+        inner
+            .as_mut()
+            .ok_or_else(|| Error::Query("Transaction already consumed".to_string()))
+    }
+
+    fn past_deadline(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Run a statement, giving up on it at the deadline.
+    /// Returns None if the deadline passed first.
+    async fn until_deadline<R>(
+        &self,
+        statement: impl Future<Output = Result<R>>,
+    ) -> Option<Result<R>> {
+        match self.deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline.into(), statement)
+                .await
+                .ok(),
+            None => Some(statement.await),
+        }
+    }
+
+    /// Stop the statement running on connection `id` and abandon the
+    /// transaction, which mysql_async rolls back when the connection goes
+    /// back to the pool.
+    ///
+    /// The statement is killed from a separate connection opened outside
+    /// the pool, so this works even when the pool has no free connections.
+    async fn interrupt<R>(
+        &self,
+        tx: Option<mysql_async::Transaction<'static>>,
+        id: u32,
+    ) -> Result<R> {
+        let killed = async {
+            let mut conn = mysql_async::Conn::new(self.state.opts.clone()).await?;
+            conn.query_drop(format!("KILL QUERY {}", id)).await?;
+            conn.disconnect().await
+        };
+        if let Err(e) = killed.await {
+            tracing::warn!(
+                "Failed to kill statement past the transaction deadline: {}",
+                e
+            );
+        }
+        drop(tx);
+        Err(Error::DeadlineExceeded)
+    }
+
+    /// Undo session changes made for this transaction.
+    async fn restore_session(&self, tx: &mut mysql_async::Transaction<'static>) -> Result<()> {
+        if self.restore_lock_wait_timeout {
+            tx.query_drop(RESTORE_LOCK_WAIT_TIMEOUT).await?;
+        }
+        Ok(())
+    }
+
+    /// Prepare and run a statement for its first row, until the deadline.
+    async fn exec_first(
+        &self,
+        tx: &mut mysql_async::Transaction<'static>,
+        sql: &str,
+        params: Vec<mysql_async::Value>,
+    ) -> Option<Result<Option<MySqlAsyncRow>>> {
+        self.until_deadline(async {
+            let stmt = self.state.prep(tx, sql).await?;
+            Ok(tx.exec_first(&stmt, params).await?)
+        })
+        .await
+    }
+
     /// Take the inner transaction, leaving None in its place.
     /// Returns an error if the transaction has already been consumed.
     async fn take_inner(&self) -> Result<mysql_async::Transaction<'static>> {
@@ -54,41 +156,46 @@ impl MySqlTransaction {
 impl Pool for MySqlTransaction {
     async fn execute(&self, sql: &str, params: Vec<Value>) -> Result<ExecuteResult> {
         let mut guard = self.inner.lock().await;
-        let tx = guard
-            .as_mut()
-            .ok_or_else(|| Error::Query("Transaction already consumed".to_string()))?;
-
-        let mysql_params: Vec<mysql_async::Value> = params.iter().map(to_mysql_value).collect();
-
-        tx.exec_drop(sql, mysql_params).await?;
-
-        let rows_affected = tx.affected_rows();
-        let last_insert_id = tx.last_insert_id();
-
-        Ok(ExecuteResult {
-            rows_affected,
-            last_insert_id,
-        })
+        let tx = self.open(&mut guard)?;
+        let id = tx.id();
+
+        let mysql_params = self.state.mysql_params(&params)?;
+
+        let outcome = self
+            .until_deadline(async {
+                let stmt = self.state.prep(tx, sql).await?;
+                tx.exec_drop(&stmt, mysql_params).await?;
+
+                let result = server_result(tx);
+                self.state.with_warnings(tx, result).await
+            })
+            .await;
+        match outcome {
+            Some(result) => result,
+            None => self.interrupt(guard.take(), id).await,
+        }
     }
 
     async fn fetch_all<T: FromRow + Send>(&self, sql: &str, params: Vec<Value>) -> Result<Vec<T>> {
-        let mut guard = self.inner.lock().await;
-        let tx = guard
-            .as_mut()
-            .ok_or_else(|| Error::Query("Transaction already consumed".to_string()))?;
-
-        let mysql_params: Vec<mysql_async::Value> = params.iter().map(to_mysql_value).collect();
-
-        let rows: Vec<MySqlAsyncRow> = tx.exec(sql, mysql_params).await?;
+        self.fetch_all_limited(sql, params, RowLimit::Default).await
+    }
 
-        let mut results = Vec::with_capacity(rows.len());
-        for row in rows {
-            let rdbi_row = MySqlRow::from_mysql_row(row)?;
-            let entity = T::from_row(&rdbi_row)?;
-            results.push(entity);
+    async fn fetch_all_limited<T: FromRow + Send>(
+        &self,
+        sql: &str,
+        params: Vec<Value>,
+        limit: RowLimit,
+    ) -> Result<Vec<T>> {
+        let mut guard = self.inner.lock().await;
+        let tx = self.open(&mut guard)?;
+        let id = tx.id();
+        let outcome = self
+            .until_deadline(self.state.fetch_all(tx, sql, &params, limit))
+            .await;
+        match outcome {
+            Some(result) => result,
+            None => self.interrupt(guard.take(), id).await,
         }
-
-        Ok(results)
     }
 
     async fn fetch_optional<T: FromRow + Send>(
@@ -97,17 +204,19 @@ impl Pool for MySqlTransaction {
         params: Vec<Value>,
     ) -> Result<Option<T>> {
         let mut guard = self.inner.lock().await;
-        let tx = guard
-            .as_mut()
-            .ok_or_else(|| Error::Query("Transaction already consumed".to_string()))?;
+        let tx = self.open(&mut guard)?;
+        let id = tx.id();
 
-        let mysql_params: Vec<mysql_async::Value> = params.iter().map(to_mysql_value).collect();
+        let mysql_params = self.state.mysql_params(&params)?;
 
-        let row: Option<MySqlAsyncRow> = tx.exec_first(sql, mysql_params).await?;
+        let row = match self.exec_first(tx, sql, mysql_params).await {
+            Some(row) => row?,
+            None => return self.interrupt(guard.take(), id).await,
+        };
 
         match row {
             Some(row) => {
-                let rdbi_row = MySqlRow::from_mysql_row(row)?;
+                let rdbi_row = self.state.decode_row(row)?;
                 Ok(Some(T::from_row(&rdbi_row)?))
             }
             None => Ok(None),
@@ -122,26 +231,105 @@ impl Pool for MySqlTransaction {
 
     async fn fetch_scalar<T: FromValue + Send>(&self, sql: &str, params: Vec<Value>) -> Result<T> {
         let mut guard = self.inner.lock().await;
-        let tx = guard
-            .as_mut()
-            .ok_or_else(|| Error::Query("Transaction already consumed".to_string()))?;
+        let tx = self.open(&mut guard)?;
+        let id = tx.id();
 
-        let mysql_params: Vec<mysql_async::Value> = params.iter().map(to_mysql_value).collect();
+        let mysql_params = self.state.mysql_params(&params)?;
 
-        let row: Option<MySqlAsyncRow> = tx.exec_first(sql, mysql_params).await?;
+        let row = match self.exec_first(tx, sql, mysql_params).await {
+            Some(row) => row?,
+            None => return self.interrupt(guard.take(), id).await,
+        };
 
         match row {
-            Some(row) => {
-                let mysql_value = row
-                    .as_ref(0)
-                    .ok_or_else(|| Error::Query("Expected at least one column".to_string()))?
-                    .clone();
-                let value = from_mysql_value(mysql_value)?;
-                T::from_value(value)
-            }
+            Some(row) => T::from_value(self.state.decode_scalar(&row)?),
             None => Err(Error::Query("Expected one row, found none".to_string())),
         }
     }
+
+    async fn stream_rows(
+        &self,
+        sql: &str,
+        params: Vec<Value>,
+        sink: &mut (dyn RowSink + Send),
+    ) -> Result<()> {
+        let mut guard = self.inner.lock().await;
+        let tx = self.open(&mut guard)?;
+        let id = tx.id();
+        let outcome = self
+            .until_deadline(self.state.stream_rows(tx, sql, &params, sink))
+            .await;
+        match outcome {
+            Some(result) => result,
+            None => self.interrupt(guard.take(), id).await,
+        }
+    }
+
+    async fn query_unprepared(
+        &self,
+        sql: &str,
+        params: Vec<Value>,
+        sink: &mut (dyn RowSink + Send),
+    ) -> Result<ExecuteResult> {
+        let mut guard = self.inner.lock().await;
+        let tx = self.open(&mut guard)?;
+        let id = tx.id();
+        let outcome = self
+            .until_deadline(self.state.query_unprepared(tx, sql, &params, sink))
+            .await;
+        match outcome {
+            Some(result) => result,
+            None => self.interrupt(guard.take(), id).await,
+        }
+    }
+
+    async fn execute_script(&self, script: &str) -> Result<Vec<ExecuteResult>> {
+        let mut guard = self.inner.lock().await;
+        let tx = self.open(&mut guard)?;
+        let id = tx.id();
+        let outcome = self
+            .until_deadline(self.state.execute_script(tx, script))
+            .await;
+        match outcome {
+            Some(result) => result,
+            None => self.interrupt(guard.take(), id).await,
+        }
+    }
+
+    async fn execute_many(
+        &self,
+        queries: Vec<DynamicQuery>,
+        mode: ExecuteMode,
+        on_error: OnError,
+    ) -> Result<Vec<Result<ExecuteResult>>> {
+        let mut guard = self.inner.lock().await;
+        let tx = self.open(&mut guard)?;
+        let id = tx.id();
+        let outcome = self
+            .until_deadline(
+                self.state
+                    .execute_many(tx, &queries, mode, on_error, &SAVEPOINT),
+            )
+            .await;
+        match outcome {
+            Some(result) => result,
+            None => self.interrupt(guard.take(), id).await,
+        }
+    }
+
+    fn max_rows(&self) -> Option<u64> {
+        self.state.max_rows
+    }
+
+    async fn server_version(&self) -> Result<Option<ServerVersion>> {
+        if let Some(version) = self.state.server_version.get() {
+            return Ok(Some(*version));
+        }
+        let mut guard = self.inner.lock().await;
+        let tx = self.open(&mut guard)?;
+        let version = detect_server_version(tx).await?;
+        Ok(Some(*self.state.server_version.get_or_init(|| version)))
+    }
 }
 
 // Also implement Pool for references to MySqlTransaction
@@ -155,6 +343,15 @@ impl Pool for &MySqlTransaction {
         (*self).fetch_all(sql, params).await
     }
 
+    async fn fetch_all_limited<T: FromRow + Send>(
+        &self,
+        sql: &str,
+        params: Vec<Value>,
+        limit: RowLimit,
+    ) -> Result<Vec<T>> {
+        (*self).fetch_all_limited(sql, params, limit).await
+    }
+
     async fn fetch_optional<T: FromRow + Send>(
         &self,
         sql: &str,
@@ -170,22 +367,94 @@ impl Pool for &MySqlTransaction {
     async fn fetch_scalar<T: FromValue + Send>(&self, sql: &str, params: Vec<Value>) -> Result<T> {
         (*self).fetch_scalar(sql, params).await
     }
+
+    async fn stream_rows(
+        &self,
+        sql: &str,
+        params: Vec<Value>,
+        sink: &mut (dyn RowSink + Send),
+    ) -> Result<()> {
+        (*self).stream_rows(sql, params, sink).await
+    }
+
+    async fn query_unprepared(
+        &self,
+        sql: &str,
+        params: Vec<Value>,
+        sink: &mut (dyn RowSink + Send),
+    ) -> Result<ExecuteResult> {
+        (*self).query_unprepared(sql, params, sink).await
+    }
+
+    async fn execute_script(&self, script: &str) -> Result<Vec<ExecuteResult>> {
+        (*self).execute_script(script).await
+    }
+
+    async fn execute_many(
+        &self,
+        queries: Vec<DynamicQuery>,
+        mode: ExecuteMode,
+        on_error: OnError,
+    ) -> Result<Vec<Result<ExecuteResult>>> {
+        (*self).execute_many(queries, mode, on_error).await
+    }
+
+    fn max_rows(&self) -> Option<u64> {
+        (*self).max_rows()
+    }
+
+    async fn server_version(&self) -> Result<Option<ServerVersion>> {
+        (*self).server_version().await
+    }
 }
 
 impl Transaction for MySqlTransaction {
     async fn commit(&self) -> Result<()> {
-        let tx = self.take_inner().await?;
+        let mut tx = self.take_inner().await?;
+        self.restore_session(&mut tx).await?;
+        if self.past_deadline() {
+            tx.rollback().await?;
+            return Err(Error::DeadlineExceeded);
+        }
         tx.commit().await?;
         Ok(())
     }
 
     async fn rollback(&self) -> Result<()> {
-        let tx = self.take_inner().await?;
+        let mut tx = self.take_inner().await?;
+        self.restore_session(&mut tx).await?;
         tx.rollback().await?;
         Ok(())
     }
 }
 
+/// A transaction dropped while open is rolled back by mysql_async. If it
+/// changed session settings, they are restored first, on a spawned task since
+/// that takes a round trip.
+impl Drop for MySqlTransaction {
+    fn drop(&mut self) {
+        if !self.restore_lock_wait_timeout {
+            return;
+        }
+        let Some(mut tx) = self.inner.get_mut().take() else {
+            return;
+        };
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                if let Err(e) = tx.query_drop(RESTORE_LOCK_WAIT_TIMEOUT).await {
+                    tracing::warn!("Failed to restore innodb_lock_wait_timeout: {}", e);
+                }
+                if let Err(e) = tx.rollback().await {
+                    tracing::warn!("Failed to roll back dropped transaction: {}", e);
+                }
+            });
+        }
+    }
+}
+
+const RESTORE_LOCK_WAIT_TIMEOUT: &str =
+    "SET SESSION innodb_lock_wait_timeout = @rdbi_lock_wait_timeout";
+
 /// Convert rdbi IsolationLevel to mysql_async IsolationLevel.
 pub(crate) fn to_mysql_isolation(level: IsolationLevel) -> mysql_async::IsolationLevel {
     match level {
@@ -1,12 +1,20 @@
 //! Type conversion utilities for MySQL
 
 use crate::error::{Error, Result};
+use crate::row::{Column as RowColumn, ColumnType as RowColumnType};
 use crate::value::Value;
-use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
-use mysql_async::Value as MySqlValue;
+use chrono::{
+    Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike,
+};
+use mysql_async::consts::{ColumnFlags, ColumnType};
+use mysql_async::{Column, Value as MySqlValue};
+use rust_decimal::Decimal;
 
 /// Convert rdbi Value to mysql_async Value
-pub fn to_mysql_value(value: &Value) -> MySqlValue {
+///
+/// Time-zone-aware values are converted to `time_zone` (the session time
+/// zone). The pool refuses to bind them without one; this falls back to UTC.
+pub fn to_mysql_value(value: &Value, time_zone: Option<FixedOffset>) -> MySqlValue {
     match value {
         Value::Null => MySqlValue::NULL,
         Value::Bool(v) => MySqlValue::from(*v),
@@ -26,17 +34,10 @@ pub fn to_mysql_value(value: &Value) -> MySqlValue {
             // Convert NaiveDate to MySQL Date value
             MySqlValue::Date(v.year() as u16, v.month() as u8, v.day() as u8, 0, 0, 0, 0)
         }
-        Value::DateTime(v) => {
-            // Convert NaiveDateTime to MySQL DateTime value
-            MySqlValue::Date(
-                v.year() as u16,
-                v.month() as u8,
-                v.day() as u8,
-                v.hour() as u8,
-                v.minute() as u8,
-                v.second() as u8,
-                v.and_utc().timestamp_subsec_micros(),
-            )
+        Value::DateTime(v) => naive_datetime_value(v),
+        Value::DateTimeTz(v) => {
+            let offset = time_zone.unwrap_or(FixedOffset::east_opt(0).expect("UTC is valid"));
+            naive_datetime_value(&v.with_timezone(&offset).naive_local())
         }
         Value::Time(v) => {
             // Convert NaiveTime to MySQL Time value
@@ -49,72 +50,317 @@ pub fn to_mysql_value(value: &Value) -> MySqlValue {
                 v.nanosecond() / 1000, // microseconds
             )
         }
+        Value::Duration(v) => {
+            let is_neg = *v < Duration::zero();
+            let abs = v.abs();
+            let secs = abs.num_seconds();
+            MySqlValue::Time(
+                is_neg,
+                (secs / 86_400) as u32,
+                (secs / 3600 % 24) as u8,
+                (secs / 60 % 60) as u8,
+                (secs % 60) as u8,
+                abs.subsec_nanos() as u32 / 1000,
+            )
+        }
         Value::Decimal(v) => MySqlValue::from(v.to_string()),
         Value::Json(v) => MySqlValue::from(v.to_string()),
+        // The pool refuses to bind these before converting
+        Value::Invalid { .. } => MySqlValue::NULL,
     }
 }
 
-/// Convert mysql_async Value to rdbi Value
-pub fn from_mysql_value(value: MySqlValue) -> Result<Value> {
+/// Render a value as a SQL literal for the text protocol.
+///
+/// The output is valid whether or not the session has `NO_BACKSLASH_ESCAPES`
+/// set: strings containing quotes, backslashes or control characters are
+/// sent as `_utf8mb4 X'..'` hex literals, and bytes always as `X'..'`.
+pub(crate) fn to_sql_literal(value: &Value, time_zone: Option<FixedOffset>) -> String {
     match value {
-        MySqlValue::NULL => Ok(Value::Null),
-        MySqlValue::Bytes(v) => {
-            // Try to interpret as string first
-            match String::from_utf8(v.clone()) {
-                Ok(s) => Ok(Value::String(s)),
-                Err(_) => Ok(Value::Bytes(v)),
-            }
-        }
-        MySqlValue::Int(v) => Ok(Value::I64(v)),
-        MySqlValue::UInt(v) => Ok(Value::U64(v)),
-        MySqlValue::Float(v) => Ok(Value::F32(v)),
-        MySqlValue::Double(v) => Ok(Value::F64(v)),
+        Value::String(v) => text_literal(v),
+        Value::Json(v) => text_literal(&v.to_string()),
+        Value::Bytes(v) => format!("X'{}'", hex(v)),
+        _ => match to_mysql_value(value, time_zone) {
+            MySqlValue::Bytes(bytes) => text_literal(&String::from_utf8_lossy(&bytes)),
+            other => other.as_sql(false),
+        },
+    }
+}
+
+fn text_literal(text: &str) -> String {
+    let plain = text
+        .chars()
+        .all(|c| !matches!(c, '\'' | '"' | '\\') && !c.is_control());
+    if plain {
+        format!("'{}'", text)
+    } else {
+        format!("_utf8mb4 X'{}'", hex(text.as_bytes()))
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Convert NaiveDateTime to MySQL DateTime value
+fn naive_datetime_value(v: &NaiveDateTime) -> MySqlValue {
+    MySqlValue::Date(
+        v.year() as u16,
+        v.month() as u8,
+        v.day() as u8,
+        v.hour() as u8,
+        v.minute() as u8,
+        v.second() as u8,
+        v.and_utc().timestamp_subsec_micros(),
+    )
+}
+
+/// Character set number MySQL reports for binary strings (`BINARY`, `BLOB`, ...).
+const BINARY_CHARSET: u16 = 63;
+
+/// Convert a mysql_async Value to an rdbi Value, using the column metadata
+/// to pick the variant.
+///
+/// The column type and flags decide the result, so a given column always
+/// decodes to the same `Value` variant: binary strings stay `Bytes`, DECIMAL
+/// becomes `Decimal`, DATETIME/TIMESTAMP are never narrowed to `Date`, BIT(n)
+/// becomes `U64`, and TIME is a signed `Duration` covering its full ±838 hour
+/// range.
+/// Values sent as text (text protocol) are parsed according to the type.
+///
+/// With a session `time_zone`, DATETIME and TIMESTAMP values are returned as
+/// `Value::DateTimeTz` in that zone.
+pub fn from_mysql_value(
+    value: MySqlValue,
+    column: &Column,
+    time_zone: Option<FixedOffset>,
+) -> Result<Value> {
+    let bytes = match value {
+        MySqlValue::NULL => return Ok(Value::Null),
+        MySqlValue::Int(v) => return Ok(Value::I64(v)),
+        MySqlValue::UInt(v) => return Ok(Value::U64(v)),
+        MySqlValue::Float(v) => return Ok(Value::F32(v)),
+        MySqlValue::Double(v) => return Ok(Value::F64(v)),
         MySqlValue::Date(year, month, day, hour, min, sec, micro) => {
-            if hour == 0 && min == 0 && sec == 0 && micro == 0 {
-                // Pure date
-                let date = NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
-                    .ok_or_else(|| Error::TypeConversion {
-                        expected: "date",
-                        actual: format!("{}-{}-{}", year, month, day),
-                    })?;
-                Ok(Value::Date(date))
+            let date = make_date(year, month, day)?;
+            return match column.column_type() {
+                ColumnType::MYSQL_TYPE_DATE | ColumnType::MYSQL_TYPE_NEWDATE => {
+                    Ok(Value::Date(date))
+                }
+                _ => Ok(datetime_value(
+                    NaiveDateTime::new(date, make_time(hour, min, sec, micro)?),
+                    time_zone,
+                )),
+            };
+        }
+        MySqlValue::Time(is_neg, days, hours, mins, secs, micro) => {
+            return Ok(time_value(is_neg, days, hours, mins, secs, micro));
+        }
+        MySqlValue::Bytes(v) => v,
+    };
+
+    match column.column_type() {
+        ColumnType::MYSQL_TYPE_TINY
+        | ColumnType::MYSQL_TYPE_SHORT
+        | ColumnType::MYSQL_TYPE_INT24
+        | ColumnType::MYSQL_TYPE_LONG
+        | ColumnType::MYSQL_TYPE_LONGLONG
+        | ColumnType::MYSQL_TYPE_YEAR => {
+            let text = utf8(bytes, "integer")?;
+            if column.flags().contains(ColumnFlags::UNSIGNED_FLAG) {
+                parse_text(&text, "integer").map(Value::U64)
             } else {
-                // DateTime
-                let date = NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
-                    .ok_or_else(|| Error::TypeConversion {
-                        expected: "date",
-                        actual: format!("{}-{}-{}", year, month, day),
-                    })?;
-                let time =
-                    NaiveTime::from_hms_micro_opt(hour as u32, min as u32, sec as u32, micro)
-                        .ok_or_else(|| Error::TypeConversion {
-                            expected: "time",
-                            actual: format!("{}:{}:{}.{}", hour, min, sec, micro),
-                        })?;
-                Ok(Value::DateTime(NaiveDateTime::new(date, time)))
+                parse_text(&text, "integer").map(Value::I64)
             }
         }
-        MySqlValue::Time(is_neg, days, hours, mins, secs, micro) => {
-            // NaiveTime only supports 00:00:00 to 23:59:59
-            // Reject values outside this range (negative, >24h, or with days component)
-            if is_neg || days > 0 || hours >= 24 {
-                return Err(Error::TypeConversion {
-                    expected: "time (00:00:00 to 23:59:59)",
-                    actual: format!(
-                        "{}{}:{:02}:{:02}",
-                        if is_neg { "-" } else { "" },
-                        days * 24 + hours as u32,
-                        mins,
-                        secs
-                    ),
-                });
+        ColumnType::MYSQL_TYPE_FLOAT => parse_text(&utf8(bytes, "float")?, "float").map(Value::F32),
+        ColumnType::MYSQL_TYPE_DOUBLE => {
+            parse_text(&utf8(bytes, "double")?, "double").map(Value::F64)
+        }
+        ColumnType::MYSQL_TYPE_DECIMAL | ColumnType::MYSQL_TYPE_NEWDECIMAL => {
+            let text = utf8(bytes, "decimal")?;
+            // DECIMAL(65, 30) can exceed rust_decimal's range; keep the exact text then
+            match text.parse::<Decimal>() {
+                Ok(v) => Ok(Value::Decimal(v)),
+                Err(_) => Ok(Value::String(text)),
+            }
+        }
+        ColumnType::MYSQL_TYPE_DATE | ColumnType::MYSQL_TYPE_NEWDATE => {
+            let text = utf8(bytes, "date")?;
+            NaiveDate::parse_from_str(&text, "%Y-%m-%d")
+                .map(Value::Date)
+                .map_err(|_| conversion_error("date", text))
+        }
+        ColumnType::MYSQL_TYPE_DATETIME
+        | ColumnType::MYSQL_TYPE_DATETIME2
+        | ColumnType::MYSQL_TYPE_TIMESTAMP
+        | ColumnType::MYSQL_TYPE_TIMESTAMP2 => {
+            let text = utf8(bytes, "datetime")?;
+            NaiveDateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S%.f")
+                .map(|v| datetime_value(v, time_zone))
+                .map_err(|_| conversion_error("datetime", text))
+        }
+        ColumnType::MYSQL_TYPE_TIME | ColumnType::MYSQL_TYPE_TIME2 => {
+            let text = utf8(bytes, "time")?;
+            match parse_duration(&text) {
+                Some(v) => Ok(Value::Duration(v)),
+                None => Err(conversion_error("time", text)),
             }
-            let time = NaiveTime::from_hms_micro_opt(hours as u32, mins as u32, secs as u32, micro)
-                .ok_or_else(|| Error::TypeConversion {
-                    expected: "time",
-                    actual: format!("{}:{}:{}.{}", hours, mins, secs, micro),
-                })?;
-            Ok(Value::Time(time))
         }
+        // JSON is reported with the binary charset but is always text
+        ColumnType::MYSQL_TYPE_JSON => Ok(Value::String(utf8(bytes, "json")?)),
+        // BIT(n) arrives as up to 8 big-endian bytes
+        ColumnType::MYSQL_TYPE_BIT if bytes.len() <= 8 => Ok(Value::U64(
+            bytes.iter().fold(0, |acc, b| (acc << 8) | u64::from(*b)),
+        )),
+        ColumnType::MYSQL_TYPE_BIT | ColumnType::MYSQL_TYPE_GEOMETRY => Ok(Value::Bytes(bytes)),
+        _ if column.character_set() == BINARY_CHARSET => Ok(Value::Bytes(bytes)),
+        // Text columns in a non-UTF-8 charset may not decode; keep the raw bytes
+        _ => match String::from_utf8(bytes) {
+            Ok(s) => Ok(Value::String(s)),
+            Err(e) => Ok(Value::Bytes(e.into_bytes())),
+        },
     }
 }
+
+/// Describe a result column for `DynamicRow`.
+pub(crate) fn column_info(column: &Column) -> RowColumn {
+    let flags = column.flags();
+    let binary = column.character_set() == BINARY_CHARSET;
+    let column_type = match column.column_type() {
+        ColumnType::MYSQL_TYPE_TINY => RowColumnType::TinyInt,
+        ColumnType::MYSQL_TYPE_SHORT => RowColumnType::SmallInt,
+        ColumnType::MYSQL_TYPE_INT24 => RowColumnType::MediumInt,
+        ColumnType::MYSQL_TYPE_LONG => RowColumnType::Int,
+        ColumnType::MYSQL_TYPE_LONGLONG => RowColumnType::BigInt,
+        ColumnType::MYSQL_TYPE_FLOAT => RowColumnType::Float,
+        ColumnType::MYSQL_TYPE_DOUBLE => RowColumnType::Double,
+        ColumnType::MYSQL_TYPE_DECIMAL | ColumnType::MYSQL_TYPE_NEWDECIMAL => {
+            RowColumnType::Decimal
+        }
+        ColumnType::MYSQL_TYPE_YEAR => RowColumnType::Year,
+        ColumnType::MYSQL_TYPE_DATE | ColumnType::MYSQL_TYPE_NEWDATE => RowColumnType::Date,
+        ColumnType::MYSQL_TYPE_TIME | ColumnType::MYSQL_TYPE_TIME2 => RowColumnType::Time,
+        ColumnType::MYSQL_TYPE_DATETIME | ColumnType::MYSQL_TYPE_DATETIME2 => {
+            RowColumnType::DateTime
+        }
+        ColumnType::MYSQL_TYPE_TIMESTAMP | ColumnType::MYSQL_TYPE_TIMESTAMP2 => {
+            RowColumnType::Timestamp
+        }
+        // ENUM and SET are sent as strings, marked by a flag
+        _ if flags.contains(ColumnFlags::ENUM_FLAG) => RowColumnType::Enum,
+        _ if flags.contains(ColumnFlags::SET_FLAG) => RowColumnType::Set,
+        ColumnType::MYSQL_TYPE_ENUM => RowColumnType::Enum,
+        ColumnType::MYSQL_TYPE_SET => RowColumnType::Set,
+        ColumnType::MYSQL_TYPE_STRING if binary => RowColumnType::Binary,
+        ColumnType::MYSQL_TYPE_STRING => RowColumnType::Char,
+        ColumnType::MYSQL_TYPE_VAR_STRING | ColumnType::MYSQL_TYPE_VARCHAR if binary => {
+            RowColumnType::VarBinary
+        }
+        ColumnType::MYSQL_TYPE_VAR_STRING | ColumnType::MYSQL_TYPE_VARCHAR => {
+            RowColumnType::VarChar
+        }
+        ColumnType::MYSQL_TYPE_TINY_BLOB
+        | ColumnType::MYSQL_TYPE_MEDIUM_BLOB
+        | ColumnType::MYSQL_TYPE_LONG_BLOB
+        | ColumnType::MYSQL_TYPE_BLOB
+            if binary =>
+        {
+            RowColumnType::Blob
+        }
+        ColumnType::MYSQL_TYPE_TINY_BLOB
+        | ColumnType::MYSQL_TYPE_MEDIUM_BLOB
+        | ColumnType::MYSQL_TYPE_LONG_BLOB
+        | ColumnType::MYSQL_TYPE_BLOB => RowColumnType::Text,
+        ColumnType::MYSQL_TYPE_BIT => RowColumnType::Bit,
+        ColumnType::MYSQL_TYPE_JSON => RowColumnType::Json,
+        ColumnType::MYSQL_TYPE_GEOMETRY => RowColumnType::Geometry,
+        ColumnType::MYSQL_TYPE_NULL => RowColumnType::Null,
+        _ => RowColumnType::Unknown,
+    };
+
+    RowColumn {
+        name: column.name_str().into_owned(),
+        table: column.table_str().into_owned(),
+        column_type,
+        nullable: !flags.contains(ColumnFlags::NOT_NULL_FLAG),
+        unsigned: flags.contains(ColumnFlags::UNSIGNED_FLAG),
+    }
+}
+
+/// Attach the session time zone to a decoded DATETIME/TIMESTAMP, if configured.
+fn datetime_value(naive: NaiveDateTime, time_zone: Option<FixedOffset>) -> Value {
+    match time_zone.and_then(|tz| tz.from_local_datetime(&naive).single()) {
+        Some(v) => Value::DateTimeTz(v),
+        None => Value::DateTime(naive),
+    }
+}
+
+fn make_date(year: u16, month: u8, day: u8) -> Result<NaiveDate> {
+    NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32).ok_or_else(|| {
+        Error::TypeConversion {
+            expected: "date",
+            actual: format!("{}-{}-{}", year, month, day),
+        }
+    })
+}
+
+fn make_time(hour: u8, min: u8, sec: u8, micro: u32) -> Result<NaiveTime> {
+    NaiveTime::from_hms_micro_opt(hour as u32, min as u32, sec as u32, micro).ok_or_else(|| {
+        Error::TypeConversion {
+            expected: "time",
+            actual: format!("{}:{}:{}.{}", hour, min, sec, micro),
+        }
+    })
+}
+
+fn time_value(is_neg: bool, days: u32, hours: u8, mins: u8, secs: u8, micro: u32) -> Value {
+    let v = Duration::days(days as i64)
+        + Duration::hours(hours as i64)
+        + Duration::minutes(mins as i64)
+        + Duration::seconds(secs as i64)
+        + Duration::microseconds(micro as i64);
+    Value::Duration(if is_neg { -v } else { v })
+}
+
+/// Parse a text-protocol TIME such as `-838:59:59` or `12:00:00.250000`.
+fn parse_duration(text: &str) -> Option<Duration> {
+    let (is_neg, rest) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (whole, fraction) = rest.split_once('.').unwrap_or((rest, ""));
+    let mut parts = whole.split(':');
+    let hours: i64 = parts.next()?.parse().ok()?;
+    let mins: i64 = parts.next()?.parse().ok()?;
+    let secs: i64 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || fraction.len() > 6 {
+        return None;
+    }
+    let micros: i64 = if fraction.is_empty() {
+        0
+    } else {
+        format!("{:0<6}", fraction).parse().ok()?
+    };
+    let v = Duration::hours(hours)
+        + Duration::minutes(mins)
+        + Duration::seconds(secs)
+        + Duration::microseconds(micros);
+    Some(if is_neg { -v } else { v })
+}
+
+fn utf8(bytes: Vec<u8>, expected: &'static str) -> Result<String> {
+    String::from_utf8(bytes).map_err(|e| Error::TypeConversion {
+        expected,
+        actual: format!("invalid utf8: {}", e),
+    })
+}
+
+fn parse_text<T: std::str::FromStr>(text: &str, expected: &'static str) -> Result<T> {
+    text.parse()
+        .map_err(|_| conversion_error(expected, text.to_string()))
+}
+
+fn conversion_error(expected: &'static str, actual: String) -> Error {
+    Error::TypeConversion { expected, actual }
+}
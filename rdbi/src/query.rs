@@ -1,7 +1,16 @@
-//! Query builder for rdbi
+//! Query builders for rdbi
 
-use crate::error::Result;
-use crate::traits::{ExecuteResult, FromRow, Pool, ToValue};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+
+use crate::de::SerdeRow;
+use crate::error::{Error, Result};
+use crate::ident::{quote_deferred, ToIdent};
+use crate::row::{Column, DynamicRow, RowSink};
+use crate::traits::{ExecuteResult, FromRow, Pool, Row, RowLimit, ToValue};
 use crate::value::Value;
 
 /// A query builder that supports fluent parameter binding.
@@ -20,16 +29,14 @@ use crate::value::Value;
 /// ```
 #[derive(Debug, Clone)]
 pub struct Query<'q> {
-    sql: &'q str,
-    params: Vec<Value>,
+    parts: QueryParts<&'q str>,
 }
 
 impl<'q> Query<'q> {
     /// Create a new query with the given SQL.
     pub fn new(sql: &'q str) -> Self {
         Self {
-            sql,
-            params: Vec::new(),
+            parts: QueryParts::new(sql, Vec::new()),
         }
     }
 
@@ -37,7 +44,7 @@ impl<'q> Query<'q> {
     ///
     /// Values are bound in order, replacing `?` placeholders.
     pub fn bind<T: ToValue>(mut self, value: T) -> Self {
-        self.params.push(value.to_value());
+        self.parts.params.push(value.to_value());
         self
     }
 
@@ -45,117 +52,913 @@ impl<'q> Query<'q> {
     ///
     /// This is useful for IN clauses or batch operations.
     pub fn bind_all<T: ToValue>(mut self, values: &[T]) -> Self {
-        for value in values {
-            self.params.push(value.to_value());
-        }
+        self.parts
+            .params
+            .extend(values.iter().map(ToValue::to_value));
+        self
+    }
+
+    /// Limit the rows `fetch_all` and friends may return, overriding the pool's limit.
+    ///
+    /// More rows fail with [`Error::TooManyRows`] as soon as the extra row
+    /// arrives. See [`MySqlPoolBuilder::max_rows`](crate::MySqlPoolBuilder::max_rows).
+    pub fn max_rows(mut self, max_rows: u64) -> Self {
+        self.parts.row_limit = RowLimit::Max(max_rows);
+        self
+    }
+
+    /// Lift the pool's row limit for this query, e.g. for a deliberate full-table read.
+    pub fn unlimited_rows(mut self) -> Self {
+        self.parts.row_limit = RowLimit::Unlimited;
+        self
+    }
+
+    /// Run the query over the text protocol instead of preparing it.
+    ///
+    /// Bound values are inlined as escaped literals, which is safe whether
+    /// or not `NO_BACKSLASH_ESCAPES` is set. Use this for statements MySQL
+    /// cannot prepare, such as `LOCK TABLES`, or for one-off queries where
+    /// the extra round trip to prepare is not worth it. Only the first
+    /// result set is read.
+    pub fn unprepared(mut self) -> Self {
+        self.parts.unprepared = true;
         self
     }
 
     /// Get the SQL string.
     pub fn sql(&self) -> &str {
-        self.sql
+        self.parts.sql
     }
 
     /// Get the bound parameters.
     pub fn params(&self) -> &[Value] {
-        &self.params
+        &self.parts.params
     }
 
     /// Take ownership of the parameters.
     pub fn into_params(self) -> Vec<Value> {
-        self.params
+        self.parts.params
+    }
+
+    /// Render the SQL with each `?` replaced by its parameter as a MySQL literal.
+    ///
+    /// For logging and debugging only: the output is meant to be pasted into
+    /// a MySQL client. Queries are executed with bound parameters, or with
+    /// their own escaping when [`unprepared`](Self::unprepared).
+    pub fn to_debug_sql(&self) -> String {
+        self.parts.to_debug_sql()
     }
 
     /// Execute the query and return the result.
     pub async fn execute<P: Pool>(self, pool: &P) -> Result<ExecuteResult> {
-        pool.execute(self.sql, self.params).await
+        self.parts.execute(pool).await
     }
 
     /// Fetch all matching rows.
     pub async fn fetch_all<T: FromRow + Send, P: Pool>(self, pool: &P) -> Result<Vec<T>> {
-        pool.fetch_all(self.sql, self.params).await
+        self.parts.fetch_all(pool).await
     }
 
     /// Fetch a single optional row.
     pub async fn fetch_optional<T: FromRow + Send, P: Pool>(self, pool: &P) -> Result<Option<T>> {
-        pool.fetch_optional(self.sql, self.params).await
+        self.parts.fetch_optional(pool).await
     }
 
     /// Fetch exactly one row.
     pub async fn fetch_one<T: FromRow + Send, P: Pool>(self, pool: &P) -> Result<T> {
-        pool.fetch_one(self.sql, self.params).await
+        self.parts.fetch_one(pool).await
+    }
+
+    /// Fetch all matching rows into a serde `Deserialize` type.
+    ///
+    /// Columns map to fields by name; see [`de::from_row`](crate::de::from_row).
+    pub async fn fetch_all_as<T: DeserializeOwned + Send, P: Pool>(
+        self,
+        pool: &P,
+    ) -> Result<Vec<T>> {
+        self.parts.fetch_all_as(pool).await
+    }
+
+    /// Fetch a single optional row into a serde `Deserialize` type.
+    pub async fn fetch_optional_as<T: DeserializeOwned + Send, P: Pool>(
+        self,
+        pool: &P,
+    ) -> Result<Option<T>> {
+        self.parts.fetch_optional_as(pool).await
+    }
+
+    /// Fetch exactly one row into a serde `Deserialize` type.
+    pub async fn fetch_one_as<T: DeserializeOwned + Send, P: Pool>(self, pool: &P) -> Result<T> {
+        self.parts.fetch_one_as(pool).await
+    }
+
+    /// Fetch all matching rows into a map, keyed by `key`.
+    ///
+    /// A later row with the same key replaces an earlier one.
+    ///
+    /// ```ignore
+    /// let users: HashMap<i64, Users> = Query::new("SELECT * FROM users")
+    ///     .fetch_map(&pool, |u: &Users| u.id)
+    ///     .await?;
+    /// ```
+    pub async fn fetch_map<K, T, F, P>(self, pool: &P, key: F) -> Result<HashMap<K, T>>
+    where
+        K: Eq + Hash,
+        T: FromRow + Send,
+        F: FnMut(&T) -> K,
+        P: Pool,
+    {
+        self.parts.fetch_map(pool, key).await
+    }
+
+    /// Fetch all matching rows grouped by `key`, keeping row order within each group.
+    ///
+    /// ```ignore
+    /// let by_user: HashMap<i64, Vec<Orders>> = Query::new("SELECT * FROM orders")
+    ///     .fetch_grouped(&pool, |o: &Orders| o.user_id)
+    ///     .await?;
+    /// ```
+    pub async fn fetch_grouped<K, T, F, P>(self, pool: &P, key: F) -> Result<HashMap<K, Vec<T>>>
+    where
+        K: Eq + Hash,
+        T: FromRow + Send,
+        F: FnMut(&T) -> K,
+        P: Pool,
+    {
+        self.parts.fetch_grouped(pool, key).await
+    }
+
+    /// Fetch the rows of a join as parents, each with its children.
+    ///
+    /// Every row is read as a `Parent` and, unless the child's columns are
+    /// missing or all `NULL` (as `LEFT JOIN` leaves them for a parent without
+    /// children), a `Child`. Rows with the same parent `key` are merged, so
+    /// parents come out once each, in order of first appearance.
+    ///
+    /// Select the parent's columns before the child's: where a name appears
+    /// more than once, such as `id`, the parent reads the first column and
+    /// the child the last.
+    ///
+    /// ```ignore
+    /// let users: Vec<(Users, Vec<Orders>)> = Query::new(
+    ///     "SELECT u.*, o.* FROM users u LEFT JOIN orders o ON o.user_id = u.id ORDER BY u.id, o.id",
+    /// )
+    /// .fetch_one_to_many(&pool, |u: &Users| u.id)
+    /// .await?;
+    /// ```
+    pub async fn fetch_one_to_many<Parent, Child, K, F, P>(
+        self,
+        pool: &P,
+        key: F,
+    ) -> Result<Vec<(Parent, Vec<Child>)>>
+    where
+        Parent: FromRow,
+        Child: FromRow,
+        K: Eq + Hash,
+        F: FnMut(&Parent) -> K,
+        P: Pool,
+    {
+        self.parts.fetch_one_to_many(pool, key).await
+    }
+
+    /// Fetch all matching rows as untyped [`DynamicRow`]s.
+    ///
+    /// Each row keeps its columns in select order, with their types.
+    pub async fn fetch_all_rows<P: Pool>(self, pool: &P) -> Result<Vec<DynamicRow>> {
+        self.parts.fetch_all(pool).await
+    }
+
+    /// Stream the result into a [`RowSink`], one row at a time.
+    ///
+    /// See [`export`](crate::export) for sinks writing CSV, JSON Lines and
+    /// Arrow record batches.
+    pub async fn stream_rows<S: RowSink + Send, P: Pool>(
+        self,
+        pool: &P,
+        sink: &mut S,
+    ) -> Result<()> {
+        self.parts.stream_rows(pool, sink).await
     }
 
     /// Fetch a scalar value (first column of first row).
     pub async fn fetch_scalar<T: crate::FromValue + Send, P: Pool>(self, pool: &P) -> Result<T> {
-        pool.fetch_scalar(self.sql, self.params).await
+        self.parts.fetch_scalar(pool).await
     }
 }
 
 /// A dynamic query builder for queries with variable SQL.
 ///
-/// Use this when you need to build SQL dynamically at runtime.
+/// Use this when you need to build SQL dynamically at runtime. It offers
+/// the same settings and fetch methods as [`Query`], documented there.
 #[derive(Debug, Clone)]
 pub struct DynamicQuery {
-    sql: String,
-    params: Vec<Value>,
+    parts: QueryParts<String>,
+    /// First invalid identifier given to the builder that produced this query
+    invalid: Option<String>,
 }
 
 impl DynamicQuery {
     /// Create a new dynamic query with the given SQL.
     pub fn new(sql: impl Into<String>) -> Self {
+        Self::with_params(sql, Vec::new())
+    }
+
+    /// Create a dynamic query with SQL and already converted parameters.
+    pub fn with_params(sql: impl Into<String>, params: Vec<Value>) -> Self {
         Self {
-            sql: sql.into(),
-            params: Vec::new(),
+            parts: QueryParts::new(sql.into(), params),
+            invalid: None,
+        }
+    }
+
+    /// Record an invalid identifier, failing the query when it runs.
+    pub(crate) fn with_invalid(mut self, invalid: Option<String>) -> Self {
+        self.invalid = invalid;
+        self
+    }
+
+    /// Check that the builder that produced this query saw no invalid identifiers.
+    pub(crate) fn check(&self) -> Result<()> {
+        match &self.invalid {
+            Some(message) => Err(Error::InvalidIdentifier(message.clone())),
+            None => Ok(()),
         }
     }
 
     /// Bind a single value to the query.
     pub fn bind<T: ToValue>(mut self, value: T) -> Self {
-        self.params.push(value.to_value());
+        self.parts.params.push(value.to_value());
         self
     }
 
     /// Bind multiple values to the query.
     pub fn bind_all<T: ToValue>(mut self, values: &[T]) -> Self {
-        for value in values {
-            self.params.push(value.to_value());
-        }
+        self.parts
+            .params
+            .extend(values.iter().map(ToValue::to_value));
+        self
+    }
+
+    /// See [`Query::max_rows`].
+    pub fn max_rows(mut self, max_rows: u64) -> Self {
+        self.parts.row_limit = RowLimit::Max(max_rows);
+        self
+    }
+
+    /// See [`Query::unlimited_rows`].
+    pub fn unlimited_rows(mut self) -> Self {
+        self.parts.row_limit = RowLimit::Unlimited;
+        self
+    }
+
+    /// See [`Query::unprepared`].
+    pub fn unprepared(mut self) -> Self {
+        self.parts.unprepared = true;
         self
     }
 
     /// Get the SQL string.
     pub fn sql(&self) -> &str {
-        &self.sql
+        &self.parts.sql
     }
 
     /// Get the bound parameters.
     pub fn params(&self) -> &[Value] {
-        &self.params
+        &self.parts.params
+    }
+
+    /// See [`Query::to_debug_sql`]; for diagnostics only.
+    pub fn to_debug_sql(&self) -> String {
+        self.parts.to_debug_sql()
     }
 
     /// Execute the query and return the result.
     pub async fn execute<P: Pool>(self, pool: &P) -> Result<ExecuteResult> {
-        pool.execute(&self.sql, self.params).await
+        self.check()?;
+        self.parts.execute(pool).await
     }
 
     /// Fetch all matching rows.
     pub async fn fetch_all<T: FromRow + Send, P: Pool>(self, pool: &P) -> Result<Vec<T>> {
-        pool.fetch_all(&self.sql, self.params).await
+        self.check()?;
+        self.parts.fetch_all(pool).await
     }
 
     /// Fetch a single optional row.
     pub async fn fetch_optional<T: FromRow + Send, P: Pool>(self, pool: &P) -> Result<Option<T>> {
-        pool.fetch_optional(&self.sql, self.params).await
+        self.check()?;
+        self.parts.fetch_optional(pool).await
     }
 
     /// Fetch exactly one row.
     pub async fn fetch_one<T: FromRow + Send, P: Pool>(self, pool: &P) -> Result<T> {
-        pool.fetch_one(&self.sql, self.params).await
+        self.check()?;
+        self.parts.fetch_one(pool).await
+    }
+
+    /// See [`Query::fetch_all_as`].
+    pub async fn fetch_all_as<T: DeserializeOwned + Send, P: Pool>(
+        self,
+        pool: &P,
+    ) -> Result<Vec<T>> {
+        self.check()?;
+        self.parts.fetch_all_as(pool).await
+    }
+
+    /// See [`Query::fetch_optional_as`].
+    pub async fn fetch_optional_as<T: DeserializeOwned + Send, P: Pool>(
+        self,
+        pool: &P,
+    ) -> Result<Option<T>> {
+        self.check()?;
+        self.parts.fetch_optional_as(pool).await
+    }
+
+    /// See [`Query::fetch_one_as`].
+    pub async fn fetch_one_as<T: DeserializeOwned + Send, P: Pool>(self, pool: &P) -> Result<T> {
+        self.check()?;
+        self.parts.fetch_one_as(pool).await
+    }
+
+    /// See [`Query::fetch_map`].
+    pub async fn fetch_map<K, T, F, P>(self, pool: &P, key: F) -> Result<HashMap<K, T>>
+    where
+        K: Eq + Hash,
+        T: FromRow + Send,
+        F: FnMut(&T) -> K,
+        P: Pool,
+    {
+        self.check()?;
+        self.parts.fetch_map(pool, key).await
+    }
+
+    /// See [`Query::fetch_grouped`].
+    pub async fn fetch_grouped<K, T, F, P>(self, pool: &P, key: F) -> Result<HashMap<K, Vec<T>>>
+    where
+        K: Eq + Hash,
+        T: FromRow + Send,
+        F: FnMut(&T) -> K,
+        P: Pool,
+    {
+        self.check()?;
+        self.parts.fetch_grouped(pool, key).await
+    }
+
+    /// See [`Query::fetch_one_to_many`].
+    pub async fn fetch_one_to_many<Parent, Child, K, F, P>(
+        self,
+        pool: &P,
+        key: F,
+    ) -> Result<Vec<(Parent, Vec<Child>)>>
+    where
+        Parent: FromRow,
+        Child: FromRow,
+        K: Eq + Hash,
+        F: FnMut(&Parent) -> K,
+        P: Pool,
+    {
+        self.check()?;
+        self.parts.fetch_one_to_many(pool, key).await
+    }
+
+    /// See [`Query::fetch_all_rows`].
+    pub async fn fetch_all_rows<P: Pool>(self, pool: &P) -> Result<Vec<DynamicRow>> {
+        self.check()?;
+        self.parts.fetch_all(pool).await
+    }
+
+    /// See [`Query::stream_rows`].
+    pub async fn stream_rows<S: RowSink + Send, P: Pool>(
+        self,
+        pool: &P,
+        sink: &mut S,
+    ) -> Result<()> {
+        self.check()?;
+        self.parts.stream_rows(pool, sink).await
     }
 
     /// Fetch a scalar value (first column of first row).
     pub async fn fetch_scalar<T: crate::FromValue + Send, P: Pool>(self, pool: &P) -> Result<T> {
-        pool.fetch_scalar(&self.sql, self.params).await
+        self.check()?;
+        self.parts.fetch_scalar(pool).await
+    }
+}
+
+/// SQL, bound values and run settings shared by [`Query`] and [`DynamicQuery`],
+/// which differ only in who owns the SQL.
+#[derive(Debug, Clone)]
+struct QueryParts<S> {
+    sql: S,
+    params: Vec<Value>,
+    row_limit: RowLimit,
+    unprepared: bool,
+}
+
+impl<S: AsRef<str>> QueryParts<S> {
+    fn new(sql: S, params: Vec<Value>) -> Self {
+        Self {
+            sql,
+            params,
+            row_limit: RowLimit::Default,
+            unprepared: false,
+        }
+    }
+
+    fn to_debug_sql(&self) -> String {
+        inline_params(self.sql.as_ref(), &self.params)
+    }
+
+    async fn execute<P: Pool>(self, pool: &P) -> Result<ExecuteResult> {
+        let sql = self.sql.as_ref();
+        if self.unprepared {
+            pool.query_unprepared(sql, self.params, &mut Discard).await
+        } else {
+            pool.execute(sql, self.params).await
+        }
+    }
+
+    async fn fetch_all<T: FromRow + Send, P: Pool>(self, pool: &P) -> Result<Vec<T>> {
+        let sql = self.sql.as_ref();
+        if !self.unprepared {
+            return pool
+                .fetch_all_limited(sql, self.params, self.row_limit)
+                .await;
+        }
+        let mut sink = Collect {
+            rows: Vec::new(),
+            max: self.row_limit.or(pool.max_rows()),
+        };
+        pool.query_unprepared(sql, self.params, &mut sink).await?;
+        Ok(sink.rows)
+    }
+
+    async fn fetch_optional<T: FromRow + Send, P: Pool>(self, pool: &P) -> Result<Option<T>> {
+        let sql = self.sql.as_ref();
+        if !self.unprepared {
+            return pool.fetch_optional(sql, self.params).await;
+        }
+        let mut sink = FirstRow(None);
+        pool.query_unprepared(sql, self.params, &mut sink).await?;
+        sink.0.map(|row| T::from_row(&row)).transpose()
+    }
+
+    async fn fetch_one<T: FromRow + Send, P: Pool>(self, pool: &P) -> Result<T> {
+        if !self.unprepared {
+            return pool.fetch_one(self.sql.as_ref(), self.params).await;
+        }
+        self.fetch_optional(pool)
+            .await?
+            .ok_or_else(|| Error::Query("Expected one row, found none".to_string()))
+    }
+
+    async fn fetch_scalar<T: crate::FromValue + Send, P: Pool>(self, pool: &P) -> Result<T> {
+        if !self.unprepared {
+            return pool.fetch_scalar(self.sql.as_ref(), self.params).await;
+        }
+        let row: DynamicRow = self.fetch_one(pool).await?;
+        let value = row
+            .into_values()
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::Query("Expected at least one column".to_string()))?;
+        T::from_value(value)
+    }
+
+    async fn stream_rows<R: RowSink + Send, P: Pool>(self, pool: &P, sink: &mut R) -> Result<()> {
+        let sql = self.sql.as_ref();
+        if self.unprepared {
+            pool.query_unprepared(sql, self.params, sink).await?;
+            Ok(())
+        } else {
+            pool.stream_rows(sql, self.params, sink).await
+        }
+    }
+
+    async fn fetch_all_as<T: DeserializeOwned + Send, P: Pool>(self, pool: &P) -> Result<Vec<T>> {
+        let rows: Vec<SerdeRow<T>> = self.fetch_all(pool).await?;
+        Ok(rows.into_iter().map(|row| row.0).collect())
+    }
+
+    async fn fetch_optional_as<T: DeserializeOwned + Send, P: Pool>(
+        self,
+        pool: &P,
+    ) -> Result<Option<T>> {
+        let row: Option<SerdeRow<T>> = self.fetch_optional(pool).await?;
+        Ok(row.map(|row| row.0))
+    }
+
+    async fn fetch_one_as<T: DeserializeOwned + Send, P: Pool>(self, pool: &P) -> Result<T> {
+        let row: SerdeRow<T> = self.fetch_one(pool).await?;
+        Ok(row.0)
+    }
+
+    async fn fetch_map<K, T, F, P>(self, pool: &P, mut key: F) -> Result<HashMap<K, T>>
+    where
+        K: Eq + Hash,
+        T: FromRow + Send,
+        F: FnMut(&T) -> K,
+        P: Pool,
+    {
+        let rows: Vec<T> = self.fetch_all(pool).await?;
+        Ok(rows.into_iter().map(|row| (key(&row), row)).collect())
+    }
+
+    async fn fetch_grouped<K, T, F, P>(self, pool: &P, mut key: F) -> Result<HashMap<K, Vec<T>>>
+    where
+        K: Eq + Hash,
+        T: FromRow + Send,
+        F: FnMut(&T) -> K,
+        P: Pool,
+    {
+        let rows: Vec<T> = self.fetch_all(pool).await?;
+        let mut groups: HashMap<K, Vec<T>> = HashMap::new();
+        for row in rows {
+            groups.entry(key(&row)).or_default().push(row);
+        }
+        Ok(groups)
+    }
+
+    async fn fetch_one_to_many<Parent, Child, K, F, P>(
+        self,
+        pool: &P,
+        key: F,
+    ) -> Result<Vec<(Parent, Vec<Child>)>>
+    where
+        Parent: FromRow,
+        Child: FromRow,
+        K: Eq + Hash,
+        F: FnMut(&Parent) -> K,
+        P: Pool,
+    {
+        let rows: Vec<DynamicRow> = self.fetch_all(pool).await?;
+        assemble_one_to_many(rows, key)
+    }
+}
+
+/// Merge joined rows into parents with their children, in order of first appearance.
+fn assemble_one_to_many<Parent, Child, K>(
+    rows: Vec<DynamicRow>,
+    mut key: impl FnMut(&Parent) -> K,
+) -> Result<Vec<(Parent, Vec<Child>)>>
+where
+    Parent: FromRow,
+    Child: FromRow,
+    K: Eq + Hash,
+{
+    let mut parents: IndexMap<K, (Parent, Vec<Child>)> = IndexMap::new();
+    for row in &rows {
+        let parent = Parent::from_row(&FirstColumns(row))?;
+        let children = &mut parents
+            .entry(key(&parent))
+            .or_insert_with(|| (parent, Vec::new()))
+            .1;
+        if has_child(row, Parent::column_names(), Child::column_names()) {
+            children.push(Child::from_row(row)?);
+        }
+    }
+    Ok(parents.into_values().collect())
+}
+
+/// Whether a joined row carries a child: false if none of the child's
+/// columns are in the row, or all of them are `NULL`.
+///
+/// A column the parent reads only counts for the child if the row repeats it,
+/// so the parent's values are not taken for the child's.
+fn has_child(row: &DynamicRow, parent_columns: &[&str], child_columns: &[&str]) -> bool {
+    let columns = row.columns();
+    let parent_positions: Vec<usize> = parent_columns
+        .iter()
+        .filter_map(|name| columns.iter().position(|c| c.name == *name))
+        .collect();
+    child_columns
+        .iter()
+        .filter_map(|name| columns.iter().rposition(|c| c.name == *name))
+        .filter(|i| !parent_positions.contains(i))
+        .any(|i| !row.values()[i].is_null())
+}
+
+/// A row that resolves a repeated column name to its first column.
+struct FirstColumns<'r>(&'r DynamicRow);
+
+impl Row for FirstColumns<'_> {
+    fn get_value(&self, column: &str) -> Result<Value> {
+        self.0
+            .iter()
+            .find(|(c, _)| c.name == column)
+            .map(|(_, value)| value.clone())
+            .ok_or_else(|| Error::ColumnNotFound(column.to_string()))
+    }
+
+    fn column_names(&self) -> Vec<&str> {
+        self.0.column_names()
+    }
+}
+
+/// Sink that ignores rows.
+struct Discard;
+
+impl RowSink for Discard {
+    fn columns(&mut self, _columns: &[Column]) -> Result<()> {
+        Ok(())
+    }
+
+    fn row(&mut self, _row: &DynamicRow) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Sink that decodes every row, up to an optional limit.
+struct Collect<T> {
+    rows: Vec<T>,
+    max: Option<u64>,
+}
+
+impl<T: FromRow> RowSink for Collect<T> {
+    fn columns(&mut self, _columns: &[Column]) -> Result<()> {
+        Ok(())
+    }
+
+    fn row(&mut self, row: &DynamicRow) -> Result<()> {
+        if let Some(max) = self.max {
+            if self.rows.len() as u64 >= max {
+                return Err(Error::TooManyRows { max });
+            }
+        }
+        self.rows.push(T::from_row(row)?);
+        Ok(())
+    }
+}
+
+/// Sink that keeps the first row.
+struct FirstRow(Option<DynamicRow>);
+
+impl RowSink for FirstRow {
+    fn columns(&mut self, _columns: &[Column]) -> Result<()> {
+        Ok(())
+    }
+
+    fn row(&mut self, row: &DynamicRow) -> Result<()> {
+        if self.0.is_none() {
+            self.0 = Some(row.clone());
+        }
+        Ok(())
+    }
+}
+
+/// Replace `?` placeholders with parameter literals.
+///
+/// Question marks inside quoted strings, quoted identifiers and comments are
+/// left alone. Placeholders without a matching parameter stay as `?`.
+fn inline_params(sql: &str, params: &[Value]) -> String {
+    inline_params_with(sql, params, Value::to_string).0
+}
+
+/// Replace `?` placeholders with `render(param)`, as in [`inline_params`].
+///
+/// Also returns the number of placeholders found, which may differ from the
+/// number of parameters.
+pub(crate) fn inline_params_with(
+    sql: &str,
+    params: &[Value],
+    mut render: impl FnMut(&Value) -> String,
+) -> (String, usize) {
+    let mut out = String::with_capacity(sql.len());
+    let mut params = params.iter();
+    let mut placeholders = 0;
+    let mut chars = sql.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        out.push(c);
+        match c {
+            '?' => {
+                placeholders += 1;
+                if let Some(value) = params.next() {
+                    out.pop();
+                    out.push_str(&render(value));
+                }
+            }
+            '\'' | '"' | '`' => {
+                while let Some((_, inner)) = chars.next() {
+                    out.push(inner);
+                    if inner == '\\' && c != '`' {
+                        out.extend(chars.next().map(|(_, escaped)| escaped));
+                    } else if inner == c {
+                        // A doubled quote is an escaped quote, not the end
+                        match chars.next_if(|&(_, next)| next == c) {
+                            Some((_, quote)) => out.push(quote),
+                            None => break,
+                        }
+                    }
+                }
+            }
+            '#' => push_until(&mut out, &mut chars, "\n"),
+            // `--` only starts a comment when followed by whitespace
+            '-' if sql[i..].starts_with("--")
+                && sql[i + 2..]
+                    .chars()
+                    .next()
+                    .map_or(true, char::is_whitespace) =>
+            {
+                push_until(&mut out, &mut chars, "\n")
+            }
+            '/' if sql[i + 1..].starts_with('*') => {
+                out.extend(chars.next().map(|(_, star)| star));
+                push_until(&mut out, &mut chars, "*/");
+            }
+            _ => {}
+        }
+    }
+    (out, placeholders)
+}
+
+/// Copy characters through the end of `terminator` (or the end of input).
+fn push_until(
+    out: &mut String,
+    chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>,
+    terminator: &str,
+) {
+    let start = out.len();
+    for (_, c) in chars.by_ref() {
+        out.push(c);
+        if out[start..].ends_with(terminator) {
+            break;
+        }
+    }
+}
+
+/// A push-style builder that assembles SQL and bound values across calls.
+///
+/// Unlike [`Query`], the builder owns its SQL, so fragments can be appended
+/// from helper functions. Each [`push_bind`](Self::push_bind) appends a `?`
+/// placeholder and records the value in order.
+///
+/// # Example
+///
+/// ```ignore
+/// use rdbi::QueryBuilder;
+///
+/// let mut qb = QueryBuilder::new("SELECT * FROM ");
+/// qb.push_identifier("users").push_sql(" WHERE id IN (");
+/// let mut ids = qb.separated(", ");
+/// for id in [1, 2, 3] {
+///     ids.push_bind(id);
+/// }
+/// qb.push_sql(")");
+///
+/// let users: Vec<User> = qb.build().fetch_all(pool).await?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct QueryBuilder {
+    sql: String,
+    params: Vec<Value>,
+    invalid: Option<String>,
+}
+
+impl QueryBuilder {
+    /// Create a new builder starting with the given SQL.
+    pub fn new(init: impl Into<String>) -> Self {
+        Self {
+            sql: init.into(),
+            params: Vec::new(),
+            invalid: None,
+        }
+    }
+
+    /// Append raw SQL.
+    ///
+    /// The SQL is inserted verbatim, so never build it from untrusted input.
+    pub fn push_sql(&mut self, sql: &str) -> &mut Self {
+        self.sql.push_str(sql);
+        self
+    }
+
+    /// Append a `?` placeholder and bind the value to it.
+    pub fn push_bind<T: ToValue>(&mut self, value: T) -> &mut Self {
+        self.sql.push('?');
+        self.params.push(value.to_value());
+        self
+    }
+
+    /// Append a validated, backtick-quoted identifier, e.g. `users` or `db`.`users`.
+    ///
+    /// An invalid name makes the built query fail when executed.
+    pub fn push_identifier(&mut self, name: impl ToIdent) -> &mut Self {
+        let quoted = quote_deferred(name, &mut self.invalid);
+        self.sql.push_str(&quoted);
+        self
+    }
+
+    /// Append a multi-row `VALUES (...), (...)` clause.
+    ///
+    /// The closure is called once per item with a [`Separated`] that writes
+    /// the row's parentheses contents, separated by `, `.
+    pub fn push_values<I, F>(&mut self, rows: I, mut push_row: F) -> &mut Self
+    where
+        I: IntoIterator,
+        F: FnMut(Separated<'_>, I::Item),
+    {
+        self.sql.push_str("VALUES ");
+        for (i, row) in rows.into_iter().enumerate() {
+            if i > 0 {
+                self.sql.push_str(", ");
+            }
+            self.sql.push('(');
+            push_row(self.separated(", "), row);
+            self.sql.push(')');
+        }
+        self
+    }
+
+    /// Start a list whose items are joined by `separator`.
+    pub fn separated(&mut self, separator: &str) -> Separated<'_> {
+        Separated {
+            builder: self,
+            separator: separator.to_string(),
+            first: true,
+        }
+    }
+
+    /// Get the SQL assembled so far.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// Get the values bound so far.
+    pub fn params(&self) -> &[Value] {
+        &self.params
+    }
+
+    /// Finish into a [`DynamicQuery`] ready for execution.
+    ///
+    /// If any pushed identifier was invalid, executing the query fails with
+    /// [`Error::InvalidIdentifier`], and the name is missing from
+    /// [`DynamicQuery::sql`].
+    pub fn build(self) -> DynamicQuery {
+        DynamicQuery::with_params(self.sql, self.params).with_invalid(self.invalid)
+    }
+
+    /// Finish into a [`DynamicQuery`], failing with
+    /// [`Error::InvalidIdentifier`] if any pushed identifier was invalid.
+    pub fn try_build(self) -> Result<DynamicQuery> {
+        let query = self.build();
+        query.check()?;
+        Ok(query)
+    }
+}
+
+impl From<QueryBuilder> for DynamicQuery {
+    fn from(builder: QueryBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// A list being pushed into a [`QueryBuilder`], created by [`QueryBuilder::separated`].
+///
+/// The separator is written before every item except the first.
+#[derive(Debug)]
+pub struct Separated<'b> {
+    builder: &'b mut QueryBuilder,
+    separator: String,
+    first: bool,
+}
+
+impl Separated<'_> {
+    fn push_separator(&mut self) {
+        if !self.first {
+            self.builder.sql.push_str(&self.separator);
+        }
+        self.first = false;
+    }
+
+    /// Append raw SQL as the next item.
+    pub fn push_sql(&mut self, sql: &str) -> &mut Self {
+        self.push_separator();
+        self.builder.push_sql(sql);
+        self
+    }
+
+    /// Append a bound value as the next item.
+    pub fn push_bind<T: ToValue>(&mut self, value: T) -> &mut Self {
+        self.push_separator();
+        self.builder.push_bind(value);
+        self
+    }
+
+    /// Append a quoted identifier as the next item.
+    pub fn push_identifier(&mut self, name: impl ToIdent) -> &mut Self {
+        self.push_separator();
+        self.builder.push_identifier(name);
+        self
+    }
+
+    /// Append raw SQL to the current item without a separator.
+    pub fn push_sql_unseparated(&mut self, sql: &str) -> &mut Self {
+        self.builder.push_sql(sql);
+        self
+    }
+
+    /// Append a bound value to the current item without a separator.
+    pub fn push_bind_unseparated<T: ToValue>(&mut self, value: T) -> &mut Self {
+        self.builder.push_bind(value);
+        self
     }
 }
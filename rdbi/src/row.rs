@@ -0,0 +1,341 @@
+//! Untyped rows for generic tooling
+//!
+//! [`DynamicRow`] keeps every column of a result row, in select order, with
+//! its type information and [`Value`]. Use it when the shape of a query is
+//! not known at compile time, e.g. to render arbitrary `SELECT`s:
+//!
+//! ```ignore
+//! let rows = Query::new("SELECT * FROM users").fetch_all_rows(&pool).await?;
+//! for column in rows[0].columns() {
+//!     println!("{} {}", column.name, column.column_type);
+//! }
+//! let json: Vec<serde_json::Map<String, serde_json::Value>> =
+//!     rows.iter().map(DynamicRow::to_json_map).collect();
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::error::{Error, Result};
+use crate::traits::{FromRow, Row};
+use crate::value::Value;
+
+/// Type of a result column, as reported by the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColumnType {
+    /// `TINYINT`
+    TinyInt,
+    /// `SMALLINT`
+    SmallInt,
+    /// `MEDIUMINT`
+    MediumInt,
+    /// `INT`
+    Int,
+    /// `BIGINT`
+    BigInt,
+    /// `FLOAT`
+    Float,
+    /// `DOUBLE`
+    Double,
+    /// `DECIMAL`
+    Decimal,
+    /// `YEAR`
+    Year,
+    /// `DATE`
+    Date,
+    /// `TIME`
+    Time,
+    /// `DATETIME`
+    DateTime,
+    /// `TIMESTAMP`
+    Timestamp,
+    /// `CHAR`
+    Char,
+    /// `VARCHAR`
+    VarChar,
+    /// `TEXT` and its variants
+    Text,
+    /// `BINARY`
+    Binary,
+    /// `VARBINARY`
+    VarBinary,
+    /// `BLOB` and its variants
+    Blob,
+    /// `ENUM`
+    Enum,
+    /// `SET`
+    Set,
+    /// `BIT`
+    Bit,
+    /// `JSON`
+    Json,
+    /// `GEOMETRY` and other spatial types
+    Geometry,
+    /// The `NULL` literal, e.g. `SELECT NULL`
+    Null,
+    /// A type rdbi does not recognise, or a row without type information
+    Unknown,
+}
+
+impl ColumnType {
+    /// The SQL name of the type, e.g. `"VARCHAR"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColumnType::TinyInt => "TINYINT",
+            ColumnType::SmallInt => "SMALLINT",
+            ColumnType::MediumInt => "MEDIUMINT",
+            ColumnType::Int => "INT",
+            ColumnType::BigInt => "BIGINT",
+            ColumnType::Float => "FLOAT",
+            ColumnType::Double => "DOUBLE",
+            ColumnType::Decimal => "DECIMAL",
+            ColumnType::Year => "YEAR",
+            ColumnType::Date => "DATE",
+            ColumnType::Time => "TIME",
+            ColumnType::DateTime => "DATETIME",
+            ColumnType::Timestamp => "TIMESTAMP",
+            ColumnType::Char => "CHAR",
+            ColumnType::VarChar => "VARCHAR",
+            ColumnType::Text => "TEXT",
+            ColumnType::Binary => "BINARY",
+            ColumnType::VarBinary => "VARBINARY",
+            ColumnType::Blob => "BLOB",
+            ColumnType::Enum => "ENUM",
+            ColumnType::Set => "SET",
+            ColumnType::Bit => "BIT",
+            ColumnType::Json => "JSON",
+            ColumnType::Geometry => "GEOMETRY",
+            ColumnType::Null => "NULL",
+            ColumnType::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+impl fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Name and type information of a result column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    /// Column name or alias
+    pub name: String,
+    /// Table name or alias the column comes from; empty for expressions
+    pub table: String,
+    /// Column type
+    pub column_type: ColumnType,
+    /// Whether the column can hold `NULL`
+    pub nullable: bool,
+    /// Whether a numeric column is `UNSIGNED`
+    pub unsigned: bool,
+}
+
+impl Column {
+    /// A column without type information.
+    pub fn untyped(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            table: String::new(),
+            column_type: ColumnType::Unknown,
+            nullable: true,
+            unsigned: false,
+        }
+    }
+}
+
+/// Receives the rows of a query as they arrive from the server.
+///
+/// Pass one to [`Pool::stream_rows`](crate::Pool::stream_rows) (or
+/// `Query::stream_rows`) to process a result without buffering it, on pools
+/// that read results incrementally such as [`MySqlPool`](crate::MySqlPool). The
+/// writers in [`export`](crate::export) implement this trait.
+pub trait RowSink {
+    /// Called once, before any rows, with the result's columns.
+    fn columns(&mut self, columns: &[Column]) -> Result<()>;
+
+    /// Called for each row, in order. Returning an error stops the query.
+    fn row(&mut self, row: &DynamicRow) -> Result<()>;
+}
+
+/// A result row with its columns in select order.
+///
+/// Unlike [`MySqlRow`](crate::MySqlRow), repeated column names are all kept,
+/// so `SELECT a.id, b.id` yields two columns. Looking a column up by name
+/// finds the last one, as with other rows.
+///
+/// Rows from the same result share their column list, so cloning one is
+/// cheap apart from its values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynamicRow {
+    columns: Arc<[Column]>,
+    values: Vec<Value>,
+}
+
+impl DynamicRow {
+    /// Create a row from columns and their values.
+    ///
+    /// Returns an error if the counts differ.
+    pub fn new(columns: impl Into<Arc<[Column]>>, values: Vec<Value>) -> Result<Self> {
+        let columns = columns.into();
+        if columns.len() != values.len() {
+            return Err(Error::RowDecode(format!(
+                "row has {} columns but {} values",
+                columns.len(),
+                values.len()
+            )));
+        }
+        Ok(Self { columns, values })
+    }
+
+    /// The row's columns, in select order.
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// The row's values, in select order.
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    /// Consume the row, returning its values in select order.
+    pub fn into_values(self) -> Vec<Value> {
+        self.values
+    }
+
+    /// Number of columns.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check if the row has no columns.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Get a value by column position.
+    pub fn get_index(&self, index: usize) -> Option<&Value> {
+        self.values.get(index)
+    }
+
+    /// Get a value by column name; the last column wins if the name repeats.
+    pub fn get_by_name(&self, name: &str) -> Option<&Value> {
+        self.columns
+            .iter()
+            .rposition(|c| c.name == name)
+            .map(|i| &self.values[i])
+    }
+
+    /// Iterate over columns and their values, in select order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Column, &Value)> {
+        self.columns.iter().zip(self.values.iter())
+    }
+
+    /// Convert to a map of column name to value, in select order.
+    ///
+    /// A repeated column name keeps its first position and its last value.
+    pub fn to_index_map(&self) -> IndexMap<String, Value> {
+        self.iter()
+            .map(|(column, value)| (column.name.clone(), value.clone()))
+            .collect()
+    }
+
+    /// Convert to a JSON object of column name to value.
+    ///
+    /// Values take their serde form (see [`Value`]); `JSON` columns are
+    /// embedded as documents rather than strings. With serde_json's
+    /// `preserve_order` feature the keys stay in select order.
+    pub fn to_json_map(&self) -> serde_json::Map<String, serde_json::Value> {
+        self.iter()
+            .map(|(column, value)| (column.name.clone(), json_value(column, value)))
+            .collect()
+    }
+}
+
+fn json_value(column: &Column, value: &Value) -> serde_json::Value {
+    match (column.column_type, value) {
+        (ColumnType::Json, Value::String(text)) => {
+            serde_json::from_str(text).unwrap_or_else(|_| serde_json::Value::String(text.clone()))
+        }
+        // Value's serialization cannot fail for serde_json
+        _ => serde_json::to_value(value).unwrap_or(serde_json::Value::Null),
+    }
+}
+
+impl Row for DynamicRow {
+    fn get_value(&self, column: &str) -> Result<Value> {
+        self.get_by_name(column)
+            .cloned()
+            .ok_or_else(|| Error::ColumnNotFound(column.to_string()))
+    }
+
+    fn column_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::with_capacity(self.columns.len());
+        for column in self.columns.iter() {
+            if !names.contains(&column.name.as_str()) {
+                names.push(&column.name);
+            }
+        }
+        names
+    }
+
+    fn to_dynamic_row(&self) -> Result<DynamicRow> {
+        Ok(self.clone())
+    }
+}
+
+impl FromRow for DynamicRow {
+    fn from_row<R: Row>(row: &R) -> Result<Self> {
+        row.to_dynamic_row()
+    }
+
+    fn column_names() -> &'static [&'static str] {
+        &[]
+    }
+}
+
+/// Serializes as a map of column name to value, with `JSON` columns embedded.
+///
+/// Like `to_index_map`, a repeated column name is written once, at its first
+/// position, with its last value.
+impl Serialize for DynamicRow {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let names = Row::column_names(self);
+        let mut map = serializer.serialize_map(Some(names.len()))?;
+        for name in names {
+            let i = self
+                .columns
+                .iter()
+                .rposition(|c| c.name == name)
+                .expect("name comes from the row's columns");
+            let (column, value) = (&self.columns[i], &self.values[i]);
+            match (column.column_type, value) {
+                (ColumnType::Json, Value::String(_)) => {
+                    map.serialize_entry(name, &json_value(column, value))?
+                }
+                _ => map.serialize_entry(name, value)?,
+            }
+        }
+        map.end()
+    }
+}
+
+impl From<DynamicRow> for IndexMap<String, Value> {
+    fn from(row: DynamicRow) -> Self {
+        row.columns
+            .iter()
+            .map(|c| c.name.clone())
+            .zip(row.values)
+            .collect()
+    }
+}
+
+impl From<DynamicRow> for serde_json::Map<String, serde_json::Value> {
+    fn from(row: DynamicRow) -> Self {
+        row.to_json_map()
+    }
+}
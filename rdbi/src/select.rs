@@ -0,0 +1,590 @@
+//! Composable SELECT builder for rdbi
+//!
+//! [`Select`] assembles a SELECT statement from escaped identifiers and
+//! [`Expr`] conditions, collecting bound values in placeholder order.
+//! The result is a [`DynamicQuery`], so execution goes through the
+//! regular [`Pool`](crate::Pool) trait.
+//!
+//! Table and column names are validated as [`Ident`](crate::Ident)s. An invalid name is
+//! reported when the built query runs rather than by the method that received it,
+//! or up front by [`Select::try_build`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! use rdbi::select::{col, Order, Select};
+//!
+//! async fn search(pool: &impl rdbi::Pool, name: Option<&str>) -> rdbi::Result<Vec<User>> {
+//!     Select::new()
+//!         .columns(&["id", "username", "email"])
+//!         .from("users")
+//!         .where_(col("status").eq("ACTIVE"))
+//!         .and(name.map(|n| col("username").like(format!("{}%", n))))
+//!         .order_by("username", Order::Asc)
+//!         .limit(20)
+//!         .build()
+//!         .fetch_all(pool)
+//!         .await
+//! }
+//! ```
+
+use crate::error::Result;
+use crate::ident::{quote_deferred, ToIdent};
+use crate::query::DynamicQuery;
+use crate::traits::ToValue;
+use crate::value::Value;
+
+/// Sort order for [`Select::order_by`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Order {
+    /// Ascending order
+    #[default]
+    Asc,
+    /// Descending order
+    Desc,
+}
+
+impl Order {
+    /// Get the SQL keyword for this order
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Order::Asc => "ASC",
+            Order::Desc => "DESC",
+        }
+    }
+}
+
+/// Operator precedence of an expression, used to decide where parentheses are needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Precedence {
+    Or,
+    And,
+    Atom,
+}
+
+/// A SQL boolean expression together with its bound values.
+///
+/// Expressions are usually created from a [`Column`] (see [`col`]) and
+/// combined with [`and`](Self::and), [`or`](Self::or) and [`not`](Self::not).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    sql: String,
+    params: Vec<Value>,
+    precedence: Precedence,
+    invalid: Option<String>,
+}
+
+impl Expr {
+    /// Create an expression from raw SQL and its bound values.
+    ///
+    /// The SQL is inserted verbatim (wrapped in parentheses), so never build
+    /// it from untrusted input; pass user data through `params` instead.
+    pub fn raw(sql: impl Into<String>, params: Vec<Value>) -> Self {
+        Self {
+            sql: format!("({})", sql.into()),
+            params,
+            precedence: Precedence::Atom,
+            invalid: None,
+        }
+    }
+
+    fn atom(sql: String, params: Vec<Value>) -> Self {
+        Self {
+            sql,
+            params,
+            precedence: Precedence::Atom,
+            invalid: None,
+        }
+    }
+
+    /// Combine with another expression using `AND`.
+    pub fn and(self, other: Expr) -> Expr {
+        let mut params = self.params;
+        params.extend(other.params);
+        Expr {
+            sql: format!(
+                "{} AND {}",
+                wrap(self.sql, self.precedence, Precedence::And),
+                wrap(other.sql, other.precedence, Precedence::And)
+            ),
+            params,
+            precedence: Precedence::And,
+            invalid: self.invalid.or(other.invalid),
+        }
+    }
+
+    /// Combine with another expression using `OR`.
+    pub fn or(self, other: Expr) -> Expr {
+        let mut params = self.params;
+        params.extend(other.params);
+        Expr {
+            sql: format!("{} OR {}", self.sql, other.sql),
+            params,
+            precedence: Precedence::Or,
+            invalid: self.invalid.or(other.invalid),
+        }
+    }
+
+    /// Negate this expression.
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Expr {
+        Expr {
+            invalid: self.invalid,
+            ..Expr::atom(format!("NOT ({})", self.sql), self.params)
+        }
+    }
+
+    /// Combine expressions with `AND`; an empty list yields an always-true expression.
+    pub fn all(exprs: impl IntoIterator<Item = Expr>) -> Expr {
+        exprs
+            .into_iter()
+            .reduce(Expr::and)
+            .unwrap_or_else(|| Expr::atom("1 = 1".to_string(), Vec::new()))
+    }
+
+    /// Combine expressions with `OR`; an empty list yields an always-false expression.
+    pub fn any(exprs: impl IntoIterator<Item = Expr>) -> Expr {
+        exprs
+            .into_iter()
+            .reduce(Expr::or)
+            .unwrap_or_else(|| Expr::atom("1 = 0".to_string(), Vec::new()))
+    }
+
+    /// Get the SQL fragment.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// Get the bound values, in placeholder order.
+    pub fn params(&self) -> &[Value] {
+        &self.params
+    }
+}
+
+/// Parenthesize `sql` if it binds more loosely than its surrounding operator.
+fn wrap(sql: String, inner: Precedence, outer: Precedence) -> String {
+    if (inner as u8) < (outer as u8) {
+        format!("({})", sql)
+    } else {
+        sql
+    }
+}
+
+/// A column reference used to build [`Expr`] conditions.
+///
+/// Qualified names such as `users.id` are split on `.` and each part is
+/// quoted separately.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    sql: String,
+    invalid: Option<String>,
+}
+
+/// Create a column reference, e.g. `col("id")` or `col("u.id")`.
+pub fn col(name: impl ToIdent) -> Column {
+    let mut invalid = None;
+    let sql = quote_deferred(name, &mut invalid);
+    Column { sql, invalid }
+}
+
+impl Column {
+    /// Build an expression on this column, carrying over any invalid name.
+    fn expr(&self, sql: String, params: Vec<Value>) -> Expr {
+        Expr {
+            invalid: self.invalid.clone(),
+            ..Expr::atom(sql, params)
+        }
+    }
+
+    fn compare<T: ToValue>(&self, op: &str, value: T) -> Expr {
+        self.expr(format!("{} {} ?", self.sql, op), vec![value.to_value()])
+    }
+
+    /// `col = ?`; a NULL value generates `col IS NULL`.
+    pub fn eq<T: ToValue>(&self, value: T) -> Expr {
+        match value.to_value() {
+            Value::Null => self.is_null(),
+            value => self.expr(format!("{} = ?", self.sql), vec![value]),
+        }
+    }
+
+    /// `col <> ?`; a NULL value generates `col IS NOT NULL`.
+    pub fn ne<T: ToValue>(&self, value: T) -> Expr {
+        match value.to_value() {
+            Value::Null => self.is_not_null(),
+            value => self.expr(format!("{} <> ?", self.sql), vec![value]),
+        }
+    }
+
+    /// `col > ?`
+    pub fn gt<T: ToValue>(&self, value: T) -> Expr {
+        self.compare(">", value)
+    }
+
+    /// `col >= ?`
+    pub fn ge<T: ToValue>(&self, value: T) -> Expr {
+        self.compare(">=", value)
+    }
+
+    /// `col < ?`
+    pub fn lt<T: ToValue>(&self, value: T) -> Expr {
+        self.compare("<", value)
+    }
+
+    /// `col <= ?`
+    pub fn le<T: ToValue>(&self, value: T) -> Expr {
+        self.compare("<=", value)
+    }
+
+    /// `col LIKE ?`
+    pub fn like<T: ToValue>(&self, pattern: T) -> Expr {
+        self.compare("LIKE", pattern)
+    }
+
+    /// `col NOT LIKE ?`
+    pub fn not_like<T: ToValue>(&self, pattern: T) -> Expr {
+        self.compare("NOT LIKE", pattern)
+    }
+
+    /// `col IS NULL`
+    pub fn is_null(&self) -> Expr {
+        self.expr(format!("{} IS NULL", self.sql), Vec::new())
+    }
+
+    /// `col IS NOT NULL`
+    pub fn is_not_null(&self) -> Expr {
+        self.expr(format!("{} IS NOT NULL", self.sql), Vec::new())
+    }
+
+    /// `col BETWEEN ? AND ?`
+    pub fn between<T: ToValue>(&self, low: T, high: T) -> Expr {
+        self.expr(
+            format!("{} BETWEEN ? AND ?", self.sql),
+            vec![low.to_value(), high.to_value()],
+        )
+    }
+
+    /// `col IN (?, ...)`; an empty list yields an always-false expression.
+    pub fn is_in<T: ToValue>(&self, values: &[T]) -> Expr {
+        if values.is_empty() {
+            return self.expr("1 = 0".to_string(), Vec::new());
+        }
+        let placeholders = vec!["?"; values.len()].join(", ");
+        self.expr(
+            format!("{} IN ({})", self.sql, placeholders),
+            values.iter().map(|v| v.to_value()).collect(),
+        )
+    }
+
+    /// `col NOT IN (?, ...)`; an empty list yields an always-true expression.
+    pub fn not_in<T: ToValue>(&self, values: &[T]) -> Expr {
+        if values.is_empty() {
+            return self.expr("1 = 1".to_string(), Vec::new());
+        }
+        let placeholders = vec!["?"; values.len()].join(", ");
+        self.expr(
+            format!("{} NOT IN ({})", self.sql, placeholders),
+            values.iter().map(|v| v.to_value()).collect(),
+        )
+    }
+
+    /// `col = other_col`, typically used in JOIN conditions.
+    pub fn eq_col(&self, other: impl ToIdent) -> Expr {
+        let mut invalid = self.invalid.clone();
+        let other = quote_deferred(other, &mut invalid);
+        Expr {
+            invalid,
+            ..Expr::atom(format!("{} = {}", self.sql, other), Vec::new())
+        }
+    }
+}
+
+/// A composable SELECT statement builder.
+///
+/// Clauses can be added in any order; they are rendered in SQL order by
+/// [`build`](Self::build), which also collects bound values accordingly.
+/// If no columns are given, `*` is selected.
+#[derive(Debug, Clone, Default)]
+pub struct Select {
+    columns: Vec<String>,
+    distinct: bool,
+    from: Option<String>,
+    joins: Vec<(String, Expr)>,
+    filter: Option<Expr>,
+    group_by: Vec<String>,
+    having: Option<Expr>,
+    order_by: Vec<String>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    invalid: Option<String>,
+}
+
+impl Select {
+    /// Create an empty SELECT builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Quote an identifier, remembering the first invalid one.
+    fn quote(&mut self, name: impl ToIdent) -> String {
+        quote_deferred(name, &mut self.invalid)
+    }
+
+    /// Quote `name AS alias`.
+    fn quote_as(&mut self, name: impl ToIdent, alias: impl ToIdent) -> String {
+        let name = self.quote(name);
+        let alias = self.quote(alias);
+        format!("{} AS {}", name, alias)
+    }
+
+    /// Add a column to the select list; `*` and `alias.*` select all columns.
+    pub fn column(mut self, name: impl ToIdent) -> Self {
+        let column = self.quote(name);
+        self.columns.push(column);
+        self
+    }
+
+    /// Add several columns to the select list.
+    pub fn columns<I: ToIdent>(mut self, names: &[I]) -> Self {
+        for name in names {
+            let column = self.quote(name);
+            self.columns.push(column);
+        }
+        self
+    }
+
+    /// Add a column with an alias, e.g. `u.id AS user_id`.
+    pub fn column_as(mut self, name: impl ToIdent, alias: impl ToIdent) -> Self {
+        let column = self.quote_as(name, alias);
+        self.columns.push(column);
+        self
+    }
+
+    /// Add a raw SQL expression to the select list, e.g. `COUNT(*)`.
+    ///
+    /// The SQL is inserted verbatim, so never build it from untrusted input.
+    pub fn column_raw(mut self, sql: &str) -> Self {
+        self.columns.push(sql.to_string());
+        self
+    }
+
+    /// Select only distinct rows.
+    pub fn distinct(mut self) -> Self {
+        self.distinct = true;
+        self
+    }
+
+    /// Set the table to select from.
+    pub fn from(mut self, table: impl ToIdent) -> Self {
+        self.from = Some(self.quote(table));
+        self
+    }
+
+    /// Set the table to select from, with an alias.
+    pub fn from_as(mut self, table: impl ToIdent, alias: impl ToIdent) -> Self {
+        self.from = Some(self.quote_as(table, alias));
+        self
+    }
+
+    fn push_join(mut self, kind: &str, table: String, on: Expr) -> Self {
+        self.joins.push((format!("{} {}", kind, table), on));
+        self
+    }
+
+    /// Add an `INNER JOIN table ON ...` clause.
+    pub fn join(mut self, table: impl ToIdent, on: Expr) -> Self {
+        let table = self.quote(table);
+        self.push_join("INNER JOIN", table, on)
+    }
+
+    /// Add an `INNER JOIN table AS alias ON ...` clause.
+    pub fn join_as(mut self, table: impl ToIdent, alias: impl ToIdent, on: Expr) -> Self {
+        let table = self.quote_as(table, alias);
+        self.push_join("INNER JOIN", table, on)
+    }
+
+    /// Add a `LEFT JOIN table ON ...` clause.
+    pub fn left_join(mut self, table: impl ToIdent, on: Expr) -> Self {
+        let table = self.quote(table);
+        self.push_join("LEFT JOIN", table, on)
+    }
+
+    /// Add a `LEFT JOIN table AS alias ON ...` clause.
+    pub fn left_join_as(mut self, table: impl ToIdent, alias: impl ToIdent, on: Expr) -> Self {
+        let table = self.quote_as(table, alias);
+        self.push_join("LEFT JOIN", table, on)
+    }
+
+    /// Set the WHERE condition, combining with any existing condition using `AND`.
+    ///
+    /// Accepts an `Option<Expr>` so optional filters can be passed directly;
+    /// `None` leaves the condition unchanged.
+    pub fn where_(self, expr: impl Into<Option<Expr>>) -> Self {
+        self.and(expr)
+    }
+
+    /// Add a condition to the WHERE clause with `AND`. `None` is ignored.
+    pub fn and(mut self, expr: impl Into<Option<Expr>>) -> Self {
+        if let Some(expr) = expr.into() {
+            self.filter = Some(match self.filter.take() {
+                Some(existing) => existing.and(expr),
+                None => expr,
+            });
+        }
+        self
+    }
+
+    /// Add a condition to the WHERE clause with `OR`. `None` is ignored.
+    ///
+    /// The new condition is OR-ed with everything added so far.
+    pub fn or(mut self, expr: impl Into<Option<Expr>>) -> Self {
+        if let Some(expr) = expr.into() {
+            self.filter = Some(match self.filter.take() {
+                Some(existing) => existing.or(expr),
+                None => expr,
+            });
+        }
+        self
+    }
+
+    /// Add a column to the GROUP BY clause.
+    pub fn group_by(mut self, column: impl ToIdent) -> Self {
+        let column = self.quote(column);
+        self.group_by.push(column);
+        self
+    }
+
+    /// Add a condition to the HAVING clause with `AND`. `None` is ignored.
+    pub fn having(mut self, expr: impl Into<Option<Expr>>) -> Self {
+        if let Some(expr) = expr.into() {
+            self.having = Some(match self.having.take() {
+                Some(existing) => existing.and(expr),
+                None => expr,
+            });
+        }
+        self
+    }
+
+    /// Add a column to the ORDER BY clause.
+    pub fn order_by(mut self, column: impl ToIdent, order: Order) -> Self {
+        let column = self.quote(column);
+        self.order_by.push(format!("{} {}", column, order.as_sql()));
+        self
+    }
+
+    /// Limit the number of returned rows.
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Skip the given number of rows.
+    ///
+    /// MySQL requires a LIMIT with OFFSET; if none is set, the maximum is used.
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Render the statement into a [`DynamicQuery`], failing with
+    /// [`Error::InvalidIdentifier`](crate::Error::InvalidIdentifier) if any
+    /// table or column name was invalid.
+    ///
+    /// Use this rather than [`build`](Self::build) when the SQL is read
+    /// before it runs, since an invalid name is left out of it.
+    pub fn try_build(self) -> Result<DynamicQuery> {
+        let query = self.build();
+        query.check()?;
+        Ok(query)
+    }
+
+    /// Render the statement into a [`DynamicQuery`] ready for execution.
+    ///
+    /// If any table or column name given to the builder or its expressions
+    /// was invalid, executing the query fails with
+    /// [`Error::InvalidIdentifier`](crate::Error::InvalidIdentifier), and
+    /// the name is missing from [`DynamicQuery::sql`].
+    pub fn build(self) -> DynamicQuery {
+        let invalid = self
+            .invalid
+            .iter()
+            .chain(self.joins.iter().filter_map(|(_, on)| on.invalid.as_ref()))
+            .chain(self.filter.iter().filter_map(|e| e.invalid.as_ref()))
+            .chain(self.having.iter().filter_map(|e| e.invalid.as_ref()))
+            .next()
+            .cloned();
+
+        let mut sql = String::from("SELECT ");
+        let mut params = Vec::new();
+
+        if self.distinct {
+            sql.push_str("DISTINCT ");
+        }
+        if self.columns.is_empty() {
+            sql.push('*');
+        } else {
+            sql.push_str(&self.columns.join(", "));
+        }
+
+        if let Some(from) = &self.from {
+            sql.push_str(" FROM ");
+            sql.push_str(from);
+        }
+
+        for (join, on) in self.joins {
+            sql.push(' ');
+            sql.push_str(&join);
+            sql.push_str(" ON ");
+            sql.push_str(&on.sql);
+            params.extend(on.params);
+        }
+
+        if let Some(filter) = self.filter {
+            sql.push_str(" WHERE ");
+            sql.push_str(&filter.sql);
+            params.extend(filter.params);
+        }
+
+        if !self.group_by.is_empty() {
+            sql.push_str(" GROUP BY ");
+            sql.push_str(&self.group_by.join(", "));
+        }
+
+        if let Some(having) = self.having {
+            sql.push_str(" HAVING ");
+            sql.push_str(&having.sql);
+            params.extend(having.params);
+        }
+
+        if !self.order_by.is_empty() {
+            sql.push_str(" ORDER BY ");
+            sql.push_str(&self.order_by.join(", "));
+        }
+
+        match (self.limit, self.offset) {
+            (Some(limit), Some(offset)) => {
+                sql.push_str(" LIMIT ? OFFSET ?");
+                params.push(Value::U64(limit));
+                params.push(Value::U64(offset));
+            }
+            (Some(limit), None) => {
+                sql.push_str(" LIMIT ?");
+                params.push(Value::U64(limit));
+            }
+            (None, Some(offset)) => {
+                sql.push_str(" LIMIT ? OFFSET ?");
+                params.push(Value::U64(u64::MAX));
+                params.push(Value::U64(offset));
+            }
+            (None, None) => {}
+        }
+
+        DynamicQuery::with_params(sql, params).with_invalid(invalid)
+    }
+}
+
+impl From<Select> for DynamicQuery {
+    fn from(select: Select) -> Self {
+        select.build()
+    }
+}
@@ -0,0 +1,546 @@
+//! Serde serialization of structs into query parameters
+//!
+//! Gives types that derive `serde::Serialize` a [`ToParams`] implementation
+//! through the [`SerdeParams`] adapter, so they work with the batch builders
+//! without `#[derive(ToParams)]`. Each struct field is a column, named as
+//! serde names it (honouring `#[serde(rename)]`), and `#[serde(skip)]`
+//! fields are left out.
+//!
+//! ```ignore
+//! #[derive(serde::Serialize)]
+//! struct NewOrder {
+//!     #[serde(rename = "customer_id")]
+//!     customer: i64,
+//!     total: rust_decimal::Decimal,
+//!     shipping: Address, // nested: bound as JSON
+//!     #[serde(skip)]
+//!     draft: bool,
+//! }
+//!
+//! let rows = SerdeParams::from_slice(&orders)?;
+//! BatchInsert::new("orders", &rows).execute(&pool).await?;
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use serde::ser::{self, Impossible, Serialize, SerializeStruct};
+
+use crate::error::{Error, Result};
+use crate::traits::ToParams;
+use crate::value::Value;
+
+/// Serialize a struct into its column names and values.
+///
+/// Primitive fields become the matching [`Value`] variant, unit enum
+/// variants their (renamed) name, and `None` becomes `NULL`. Nested structs,
+/// sequences, maps and enum variants with data are bound as JSON. Types with
+/// their own string form in serde, such as chrono dates, are bound as that
+/// string.
+pub fn to_params<T: Serialize + ?Sized>(value: &T) -> Result<(Vec<&'static str>, Vec<Value>)> {
+    let mut row = RowSerializer::default();
+    value.serialize(&mut row)?;
+    Ok((row.columns, row.values))
+}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::ParamEncode(msg.to_string())
+    }
+}
+
+/// A serde `Serialize` struct usable wherever [`ToParams`] is expected.
+///
+/// The values are serialized up front, and each value carries the columns it
+/// serialized. The rows of a batch must all have the same columns: fields
+/// skipped with `#[serde(skip_serializing_if)]` are rejected when they are
+/// skipped for some rows and not others.
+///
+/// All columns are inserted; skip an auto-increment key with
+/// `#[serde(skip_serializing)]`, or leave it `0` / `None` for MySQL to assign.
+pub struct SerdeParams<T> {
+    columns: Arc<[&'static str]>,
+    values: Vec<Value>,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T: Serialize> SerdeParams<T> {
+    /// Serialize a single value.
+    pub fn new(value: &T) -> Result<Self> {
+        let (columns, values) = to_params(value)?;
+        Ok(Self {
+            columns: columns.into(),
+            values,
+            _type: PhantomData,
+        })
+    }
+
+    /// Serialize a slice of values, e.g. for [`BatchInsert`](crate::BatchInsert).
+    ///
+    /// The columns are taken from the first value and shared by all rows; a
+    /// value that serializes different columns is an error.
+    pub fn from_slice(values: &[T]) -> Result<Vec<Self>> {
+        let mut rows: Vec<Self> = Vec::with_capacity(values.len());
+        for value in values {
+            let (columns, values) = to_params(value)?;
+            let columns = match rows.first() {
+                Some(first) if *first.columns == *columns => first.columns.clone(),
+                Some(first) => {
+                    return Err(Error::ParamEncode(format!(
+                        "{} serialized columns {:?}, but the first row had {:?}",
+                        std::any::type_name::<T>(),
+                        columns,
+                        first.columns
+                    )))
+                }
+                None => columns.into(),
+            };
+            rows.push(Self {
+                columns,
+                values,
+                _type: PhantomData,
+            });
+        }
+        Ok(rows)
+    }
+}
+
+impl<T> fmt::Debug for SerdeParams<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SerdeParams")
+            .field("columns", &self.columns)
+            .field("values", &self.values)
+            .finish()
+    }
+}
+
+impl<T> Clone for SerdeParams<T> {
+    fn clone(&self) -> Self {
+        Self {
+            columns: self.columns.clone(),
+            values: self.values.clone(),
+            _type: PhantomData,
+        }
+    }
+}
+
+/// The columns are only known per value, so the type-level column lists are
+/// empty; the batch builders read them from the rows instead.
+impl<T> ToParams for SerdeParams<T> {
+    fn insert_column_names() -> &'static [&'static str] {
+        &[]
+    }
+
+    fn insert_values(&self) -> Vec<Value> {
+        self.values.clone()
+    }
+
+    fn all_column_names() -> &'static [&'static str] {
+        &[]
+    }
+
+    fn all_values(&self) -> Vec<Value> {
+        self.values.clone()
+    }
+
+    fn insert_columns(&self) -> &[&'static str] {
+        &self.columns
+    }
+
+    fn all_columns(&self) -> &[&'static str] {
+        &self.columns
+    }
+}
+
+fn unsupported(kind: &str) -> Error {
+    Error::ParamEncode(format!("expected a struct, got {}", kind))
+}
+
+/// Collects a struct's fields as columns.
+#[derive(Default)]
+struct RowSerializer {
+    columns: Vec<&'static str>,
+    values: Vec<Value>,
+}
+
+impl ser::Serializer for &mut RowSerializer {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self> {
+        self.columns.reserve(len);
+        self.values.reserve(len);
+        Ok(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<()> {
+        Err(unsupported("bool"))
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<()> {
+        Err(unsupported("integer"))
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<()> {
+        Err(unsupported("integer"))
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<()> {
+        Err(unsupported("integer"))
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<()> {
+        Err(unsupported("integer"))
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<()> {
+        Err(unsupported("integer"))
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<()> {
+        Err(unsupported("integer"))
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<()> {
+        Err(unsupported("integer"))
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<()> {
+        Err(unsupported("integer"))
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<()> {
+        Err(unsupported("float"))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<()> {
+        Err(unsupported("float"))
+    }
+
+    fn serialize_char(self, _v: char) -> Result<()> {
+        Err(unsupported("char"))
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<()> {
+        Err(unsupported("string"))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<()> {
+        Err(unsupported("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        Err(unsupported("none"))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<()> {
+        Err(unsupported("option"))
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Err(unsupported("unit"))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        Err(unsupported("unit struct"))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+    ) -> Result<()> {
+        Err(unsupported("enum"))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<()> {
+        Err(unsupported("enum"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(unsupported("sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(unsupported("tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(unsupported("tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(unsupported("enum"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(unsupported("map"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(unsupported("enum"))
+    }
+}
+
+impl SerializeStruct for &mut RowSerializer {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        let value = match value.serialize(ValueSerializer) {
+            Ok(v) => v,
+            Err(FieldError::Compound) => serde_json::to_value(value)
+                .map(Value::Json)
+                .map_err(|e| Error::ParamEncode(format!("{} (field `{}`)", e, key)))?,
+            Err(FieldError::Custom(message)) => {
+                return Err(Error::ParamEncode(format!("{} (field `{}`)", message, key)))
+            }
+        };
+        self.columns.push(key);
+        self.values.push(value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Why a field could not be serialized as a single value.
+#[derive(Debug)]
+enum FieldError {
+    /// A nested struct, sequence, map or data-carrying variant, bound as JSON instead
+    Compound,
+    Custom(String),
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldError::Compound => f.write_str("compound value"),
+            FieldError::Custom(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+impl ser::Error for FieldError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        FieldError::Custom(msg.to_string())
+    }
+}
+
+/// Serializes one field into a [`Value`].
+struct ValueSerializer;
+
+type FieldResult = std::result::Result<Value, FieldError>;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = FieldError;
+    type SerializeSeq = Impossible<Value, FieldError>;
+    type SerializeTuple = Impossible<Value, FieldError>;
+    type SerializeTupleStruct = Impossible<Value, FieldError>;
+    type SerializeTupleVariant = Impossible<Value, FieldError>;
+    type SerializeMap = Impossible<Value, FieldError>;
+    type SerializeStruct = Impossible<Value, FieldError>;
+    type SerializeStructVariant = Impossible<Value, FieldError>;
+
+    fn serialize_bool(self, v: bool) -> FieldResult {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> FieldResult {
+        Ok(Value::I8(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> FieldResult {
+        Ok(Value::I16(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> FieldResult {
+        Ok(Value::I32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> FieldResult {
+        Ok(Value::I64(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> FieldResult {
+        Ok(Value::U8(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> FieldResult {
+        Ok(Value::U16(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> FieldResult {
+        Ok(Value::U32(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> FieldResult {
+        Ok(Value::U64(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> FieldResult {
+        Ok(Value::F32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> FieldResult {
+        Ok(Value::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> FieldResult {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> FieldResult {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> FieldResult {
+        Ok(Value::Bytes(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> FieldResult {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> FieldResult {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> FieldResult {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> FieldResult {
+        Ok(Value::Null)
+    }
+
+    // ENUM columns hold the variant name
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> FieldResult {
+        Ok(Value::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> FieldResult {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> FieldResult {
+        Err(FieldError::Compound)
+    }
+
+    fn serialize_seq(
+        self,
+        _len: Option<usize>,
+    ) -> std::result::Result<Self::SerializeSeq, FieldError> {
+        Err(FieldError::Compound)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> std::result::Result<Self::SerializeTuple, FieldError> {
+        Err(FieldError::Compound)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> std::result::Result<Self::SerializeTupleStruct, FieldError> {
+        Err(FieldError::Compound)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> std::result::Result<Self::SerializeTupleVariant, FieldError> {
+        Err(FieldError::Compound)
+    }
+
+    fn serialize_map(
+        self,
+        _len: Option<usize>,
+    ) -> std::result::Result<Self::SerializeMap, FieldError> {
+        Err(FieldError::Compound)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> std::result::Result<Self::SerializeStruct, FieldError> {
+        Err(FieldError::Compound)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> std::result::Result<Self::SerializeStructVariant, FieldError> {
+        Err(FieldError::Compound)
+    }
+}
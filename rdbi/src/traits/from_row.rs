@@ -1,6 +1,7 @@
 //! FromRow trait for mapping database rows to Rust structs
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::row::{Column, DynamicRow};
 use crate::value::Value;
 
 /// A database row that can be queried by column name.
@@ -13,14 +14,44 @@ pub trait Row {
     ///
     /// Returns an error if the column doesn't exist.
     fn get_value(&self, column: &str) -> Result<Value>;
+
+    /// Get the names of the row's columns, in select order.
+    ///
+    /// Used when a row is deserialized as a map or tuple rather than into
+    /// named fields. The default implementation reports no columns.
+    fn column_names(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    /// Copy the row into a [`DynamicRow`] with every column, in select order.
+    ///
+    /// The default implementation builds untyped columns from
+    /// `column_names`.
+    fn to_dynamic_row(&self) -> Result<DynamicRow> {
+        let names = self.column_names();
+        let mut values = Vec::with_capacity(names.len());
+        for name in &names {
+            values.push(self.get_value(name)?);
+        }
+        let columns: Vec<Column> = names.into_iter().map(Column::untyped).collect();
+        DynamicRow::new(columns, values)
+    }
 }
 
 /// Extension trait for typed access to row values.
 pub trait RowExt: Row {
     /// Get a typed value from the row by column name.
+    ///
+    /// Conversion errors name the column they came from.
     fn get<T: crate::FromValue>(&self, column: &str) -> Result<T> {
         let value = self.get_value(column)?;
-        T::from_value(value)
+        T::from_value(value).map_err(|e| match e {
+            Error::TypeConversion { expected, actual } => Error::TypeConversion {
+                expected,
+                actual: format!("{} (column `{}`)", actual, column),
+            },
+            other => other,
+        })
     }
 }
 
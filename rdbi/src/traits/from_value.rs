@@ -2,7 +2,7 @@
 
 use crate::error::{Error, Result};
 use crate::value::Value;
-use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use rust_decimal::Decimal;
 
 /// Trait for types that can be constructed from a database value.
@@ -285,6 +285,7 @@ impl FromValue for String {
                 expected: "utf8 string",
                 actual: format!("invalid utf8: {}", e),
             }),
+            Value::Decimal(v) => Ok(v.to_string()),
             _ => Err(Error::TypeConversion {
                 expected: "string",
                 actual: value.type_name().to_string(),
@@ -311,6 +312,7 @@ impl FromValue for NaiveDate {
         match value {
             Value::Date(v) => Ok(v),
             Value::DateTime(v) => Ok(v.date()),
+            Value::DateTimeTz(v) => Ok(v.date_naive()),
             _ => Err(Error::TypeConversion {
                 expected: "date",
                 actual: value.type_name().to_string(),
@@ -323,6 +325,7 @@ impl FromValue for NaiveDateTime {
     fn from_value(value: Value) -> Result<Self> {
         match value {
             Value::DateTime(v) => Ok(v),
+            Value::DateTimeTz(v) => Ok(v.naive_local()),
             Value::Date(v) => Ok(v.and_hms_opt(0, 0, 0).expect("00:00:00 is always valid")),
             _ => Err(Error::TypeConversion {
                 expected: "datetime",
@@ -332,11 +335,49 @@ impl FromValue for NaiveDateTime {
     }
 }
 
+/// Naive values are taken as UTC, which matches a pool without a configured
+/// session time zone (aware values are then written as UTC).
+impl FromValue for DateTime<Utc> {
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::DateTimeTz(v) => Ok(v.with_timezone(&Utc)),
+            Value::DateTime(v) => Ok(v.and_utc()),
+            _ => Err(Error::TypeConversion {
+                expected: "datetime",
+                actual: value.type_name().to_string(),
+            }),
+        }
+    }
+}
+
+/// Naive values are taken as UTC, like for `DateTime<Utc>`.
+impl FromValue for DateTime<FixedOffset> {
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::DateTimeTz(v) => Ok(v),
+            Value::DateTime(v) => Ok(v.and_utc().fixed_offset()),
+            _ => Err(Error::TypeConversion {
+                expected: "datetime",
+                actual: value.type_name().to_string(),
+            }),
+        }
+    }
+}
+
+/// Durations convert when they fall within a single day (00:00:00 to 23:59:59).
 impl FromValue for NaiveTime {
     fn from_value(value: Value) -> Result<Self> {
         match value {
             Value::Time(v) => Ok(v),
+            Value::Duration(v) if v >= Duration::zero() && v < Duration::days(1) => {
+                Ok(NaiveTime::MIN + v)
+            }
+            Value::Duration(v) => Err(Error::TypeConversion {
+                expected: "time (00:00:00 to 23:59:59)",
+                actual: format!("duration {}", v),
+            }),
             Value::DateTime(v) => Ok(v.time()),
+            Value::DateTimeTz(v) => Ok(v.time()),
             _ => Err(Error::TypeConversion {
                 expected: "time",
                 actual: value.type_name().to_string(),
@@ -345,6 +386,31 @@ impl FromValue for NaiveTime {
     }
 }
 
+/// A time of day converts to the interval since midnight.
+impl FromValue for Duration {
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Duration(v) => Ok(v),
+            Value::Time(v) => Ok(v - NaiveTime::MIN),
+            _ => Err(Error::TypeConversion {
+                expected: "duration",
+                actual: value.type_name().to_string(),
+            }),
+        }
+    }
+}
+
+/// Negative intervals are rejected.
+impl FromValue for std::time::Duration {
+    fn from_value(value: Value) -> Result<Self> {
+        let v = Duration::from_value(value)?;
+        v.to_std().map_err(|_| Error::TypeConversion {
+            expected: "non-negative duration",
+            actual: format!("duration {}", v),
+        })
+    }
+}
+
 impl FromValue for Decimal {
     fn from_value(value: Value) -> Result<Self> {
         match value {
@@ -379,6 +445,13 @@ impl FromValue for serde_json::Value {
     }
 }
 
+// Identity conversion, for inspecting the decoded value as-is
+impl FromValue for Value {
+    fn from_value(value: Value) -> Result<Self> {
+        Ok(value)
+    }
+}
+
 // Implement for Option<T>
 impl<T: FromValue> FromValue for Option<T> {
     fn from_value(value: Value) -> Result<Self> {
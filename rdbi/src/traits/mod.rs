@@ -9,7 +9,7 @@ mod transaction;
 
 pub use from_row::{FromRow, Row, RowExt};
 pub use from_value::FromValue;
-pub use pool::{ExecuteResult, Pool};
+pub use pool::{ExecuteMode, ExecuteResult, OnError, Pool, RowLimit, ServerVersion, Warning};
 pub use to_params::ToParams;
 pub use to_value::ToValue;
-pub use transaction::{IsolationLevel, Transaction, Transactional};
+pub use transaction::{AccessMode, IsolationLevel, Transaction, TransactionOptions, Transactional};
@@ -1,17 +1,183 @@
 //! Pool trait for database connection pools
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::query::DynamicQuery;
+use crate::row::{DynamicRow, RowSink};
 use crate::traits::FromRow;
 use crate::value::Value;
 use async_trait::async_trait;
 
 /// Result of a query execution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ExecuteResult {
     /// Number of rows affected by the query
+    ///
+    /// For an `UPDATE`, MySQL counts only rows whose values changed, unless
+    /// the pool is built with
+    /// [`client_found_rows`](crate::MySqlPoolBuilder::client_found_rows).
     pub rows_affected: u64,
     /// Last insert ID (for INSERT statements)
     pub last_insert_id: Option<u64>,
+    /// Rows matched by an `UPDATE`'s `WHERE` clause, whether changed or not
+    pub matched_rows: Option<u64>,
+    /// Rows an `UPDATE` actually changed
+    pub changed_rows: Option<u64>,
+    /// Number of warnings the statement raised
+    pub warnings: u16,
+    /// The server's summary, e.g. `Rows matched: 1  Changed: 0  Warnings: 0`
+    pub info: String,
+    /// The warnings themselves, when the pool is set to fetch them
+    pub warning_details: Vec<Warning>,
+}
+
+impl ExecuteResult {
+    /// Build a result from the server's report of a statement.
+    ///
+    /// Matched and changed rows are parsed from `info`.
+    pub fn from_server(
+        rows_affected: u64,
+        last_insert_id: Option<u64>,
+        warnings: u16,
+        info: &str,
+    ) -> Self {
+        Self {
+            rows_affected,
+            last_insert_id,
+            matched_rows: info_count(info, "Rows matched"),
+            changed_rows: info_count(info, "Changed"),
+            warnings,
+            info: info.to_string(),
+            warning_details: Vec::new(),
+        }
+    }
+
+    /// Add up the results of statements run as parts of one operation.
+    ///
+    /// Keeps the last insert ID and info of the latest statement that has one.
+    pub(crate) fn merge(&mut self, other: ExecuteResult) {
+        fn add(total: Option<u64>, part: Option<u64>) -> Option<u64> {
+            match (total, part) {
+                (Some(a), Some(b)) => Some(a + b),
+                (total, part) => total.or(part),
+            }
+        }
+        self.rows_affected += other.rows_affected;
+        self.last_insert_id = other.last_insert_id.or(self.last_insert_id);
+        self.matched_rows = add(self.matched_rows, other.matched_rows);
+        self.changed_rows = add(self.changed_rows, other.changed_rows);
+        self.warnings = self.warnings.saturating_add(other.warnings);
+        if !other.info.is_empty() {
+            self.info = other.info;
+        }
+        self.warning_details.extend(other.warning_details);
+    }
+}
+
+/// Read `key: count` from an info string such as
+/// `Rows matched: 2  Changed: 1  Warnings: 0`.
+fn info_count(info: &str, key: &str) -> Option<u64> {
+    let start = info.find(key)? + key.len();
+    let rest = info[start..].strip_prefix(':')?.trim_start();
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    rest[..end].parse().ok()
+}
+
+/// A warning raised by a statement, as reported by `SHOW WARNINGS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// `Note`, `Warning` or `Error`
+    pub level: String,
+    /// Server error code, e.g. 1265 for "Data truncated"
+    pub code: u16,
+    /// Human-readable message
+    pub message: String,
+}
+
+impl Warning {
+    /// Check if the warning reports data that was truncated or adjusted to fit
+    /// its column, such as an out-of-range number or a too-long string.
+    pub fn is_truncation(&self) -> bool {
+        // WARN_TOO_MANY_RECORDS, WARN_DATA_OUT_OF_RANGE, WARN_DATA_TRUNCATED,
+        // TRUNCATED_WRONG_VALUE, TRUNCATED_WRONG_VALUE_FOR_FIELD, DATA_TOO_LONG
+        matches!(self.code, 1262 | 1264 | 1265 | 1292 | 1366 | 1406)
+    }
+}
+
+/// Version of the database server a pool is connected to.
+///
+/// SQL builders use this to pick syntax the server understands, e.g. the
+/// row-alias form of `INSERT ... ON DUPLICATE KEY UPDATE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ServerVersion {
+    /// Major version number
+    pub major: u16,
+    /// Minor version number
+    pub minor: u16,
+    /// Patch version number
+    pub patch: u16,
+    /// Whether the server is MariaDB rather than MySQL
+    pub is_mariadb: bool,
+}
+
+impl ServerVersion {
+    /// Check if the server accepts `INSERT ... AS new ON DUPLICATE KEY UPDATE col = new.col`.
+    ///
+    /// Row aliases were added in MySQL 8.0.19; MariaDB does not support them.
+    pub fn supports_row_alias(&self) -> bool {
+        !self.is_mariadb && (self.major, self.minor, self.patch) >= (8, 0, 19)
+    }
+}
+
+/// Maximum number of rows a `fetch_all` may return.
+///
+/// Fetching more fails with [`Error::TooManyRows`](crate::Error::TooManyRows),
+/// guarding against accidentally loading a whole table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RowLimit {
+    /// Use the pool's limit, if it has one.
+    #[default]
+    Default,
+    /// Allow at most this many rows.
+    Max(u64),
+    /// Allow any number of rows, even if the pool has a limit.
+    Unlimited,
+}
+
+impl RowLimit {
+    /// Resolve against a pool's default limit.
+    pub fn or(self, default: Option<u64>) -> Option<u64> {
+        match self {
+            RowLimit::Default => default,
+            RowLimit::Max(max) => Some(max),
+            RowLimit::Unlimited => None,
+        }
+    }
+}
+
+/// Whether [`Pool::execute_many`] wraps its statements in a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecuteMode {
+    /// Run all statements in one transaction, committed at the end.
+    ///
+    /// Inside an existing transaction, a savepoint is used instead.
+    #[default]
+    Transaction,
+    /// Run each statement on its own, committing as it goes.
+    Autocommit,
+}
+
+/// What [`Pool::execute_many`] does when a statement fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnError {
+    /// Skip the remaining statements. In [`ExecuteMode::Transaction`], the
+    /// statements before the failing one are rolled back.
+    #[default]
+    Stop,
+    /// Run the remaining statements anyway. In [`ExecuteMode::Transaction`],
+    /// the statements that succeeded are committed.
+    Continue,
 }
 
 /// Trait for database connection pools.
@@ -26,6 +192,23 @@ pub trait Pool: Send + Sync {
     /// Fetch all rows matching the query.
     async fn fetch_all<T: FromRow + Send>(&self, sql: &str, params: Vec<Value>) -> Result<Vec<T>>;
 
+    /// Fetch all rows matching the query, with a row limit for this query.
+    ///
+    /// Backends that read rows incrementally stop as soon as the limit is
+    /// exceeded. The default implementation checks after fetching.
+    async fn fetch_all_limited<T: FromRow + Send>(
+        &self,
+        sql: &str,
+        params: Vec<Value>,
+        limit: RowLimit,
+    ) -> Result<Vec<T>> {
+        let rows: Vec<T> = self.fetch_all(sql, params).await?;
+        match limit.or(self.max_rows()) {
+            Some(max) if rows.len() as u64 > max => Err(Error::TooManyRows { max }),
+            _ => Ok(rows),
+        }
+    }
+
     /// Fetch a single optional row.
     async fn fetch_optional<T: FromRow + Send>(
         &self,
@@ -42,4 +225,104 @@ pub trait Pool: Send + Sync {
         sql: &str,
         params: Vec<Value>,
     ) -> Result<T>;
+
+    /// Stream the rows of a query into `sink`.
+    ///
+    /// Backends that can read a result incrementally hand each row over as it
+    /// arrives, so memory use does not grow with the result. The default
+    /// implementation buffers: it fetches all rows with
+    /// [`fetch_all`](Self::fetch_all) before handing any to `sink`.
+    async fn stream_rows(
+        &self,
+        sql: &str,
+        params: Vec<Value>,
+        sink: &mut (dyn RowSink + Send),
+    ) -> Result<()> {
+        let rows: Vec<DynamicRow> = self.fetch_all(sql, params).await?;
+        sink.columns(rows.first().map(DynamicRow::columns).unwrap_or(&[]))?;
+        for row in &rows {
+            sink.row(row)?;
+        }
+        Ok(())
+    }
+
+    /// Run a query without preparing it, using the text protocol.
+    ///
+    /// Parameters are inlined into the SQL as escaped literals, so statements
+    /// that cannot be prepared (e.g. `LOCK TABLES`) can still take values.
+    /// Rows of the first result set are handed to `sink`. The default
+    /// implementation returns an error.
+    async fn query_unprepared(
+        &self,
+        _sql: &str,
+        _params: Vec<Value>,
+        _sink: &mut (dyn RowSink + Send),
+    ) -> Result<ExecuteResult> {
+        Err(Error::Query(
+            "Unprepared queries are not supported by this pool".to_string(),
+        ))
+    }
+
+    /// Run a script of `;`-separated statements in one round trip.
+    ///
+    /// Returns one result per statement. Execution stops at the first
+    /// failing statement, whose error is returned. The default
+    /// implementation returns an error.
+    async fn execute_script(&self, _script: &str) -> Result<Vec<ExecuteResult>> {
+        Err(Error::Query(
+            "Scripts are not supported by this pool".to_string(),
+        ))
+    }
+
+    /// Run several statements on one connection.
+    ///
+    /// Returns one result per statement that ran, in order: with
+    /// [`OnError::Stop`], the last one is the error that stopped the batch.
+    /// The outer error is for failures of the batch as a whole, such as
+    /// checking out a connection or committing.
+    ///
+    /// Backends that support it send the statements together, saving a round
+    /// trip per statement; MySQL does so over the text protocol, with values
+    /// inlined as in [`Query::unprepared`](crate::Query::unprepared). The
+    /// default implementation runs them one by one with
+    /// [`execute`](Self::execute), and only supports
+    /// [`ExecuteMode::Autocommit`].
+    async fn execute_many(
+        &self,
+        queries: Vec<DynamicQuery>,
+        mode: ExecuteMode,
+        on_error: OnError,
+    ) -> Result<Vec<Result<ExecuteResult>>> {
+        if mode == ExecuteMode::Transaction {
+            return Err(Error::Query(
+                "Transactional execute_many is not supported by this pool".to_string(),
+            ));
+        }
+        let mut results = Vec::with_capacity(queries.len());
+        for query in queries {
+            let result = match query.check() {
+                Ok(()) => self.execute(query.sql(), query.params().to_vec()).await,
+                Err(err) => Err(err),
+            };
+            let failed = result.is_err();
+            results.push(result);
+            if failed && on_error == OnError::Stop {
+                break;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Default maximum number of rows for `fetch_all`, if the pool has one.
+    fn max_rows(&self) -> Option<u64> {
+        None
+    }
+
+    /// Get the version of the connected server.
+    ///
+    /// Returns `None` if the backend cannot report it, in which case SQL
+    /// builders fall back to the most widely supported syntax.
+    async fn server_version(&self) -> Result<Option<ServerVersion>> {
+        Ok(None)
+    }
 }
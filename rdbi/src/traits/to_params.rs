@@ -27,4 +27,20 @@ pub trait ToParams {
 
     /// Get all values.
     fn all_values(&self) -> Vec<Value>;
+
+    /// Get the column names for INSERT operations of this value.
+    ///
+    /// Defaults to [`insert_column_names`](Self::insert_column_names). Batch
+    /// builders take a batch's columns from its first row through this method,
+    /// so types whose columns are only known per value can override it.
+    fn insert_columns(&self) -> &[&'static str] {
+        Self::insert_column_names()
+    }
+
+    /// Get all column names of this value.
+    ///
+    /// Defaults to [`all_column_names`](Self::all_column_names).
+    fn all_columns(&self) -> &[&'static str] {
+        Self::all_column_names()
+    }
 }
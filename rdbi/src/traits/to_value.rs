@@ -1,7 +1,7 @@
 //! ToValue trait for converting Rust types to database values
 
 use crate::value::Value;
-use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use rust_decimal::Decimal;
 
 /// Trait for types that can be converted to a database value.
@@ -43,6 +43,9 @@ impl ToValue for i64 {
     }
 }
 
+// Unsigned integers bind to BIT(n) columns as they are: MySQL assigns a
+// number to a BIT column bit for bit, so no conversion to a bit string is
+// needed. Reading goes the other way, as BIT columns decode to `Value::U64`.
 impl ToValue for u8 {
     fn to_value(&self) -> Value {
         Value::U8(*self)
@@ -121,12 +124,36 @@ impl ToValue for NaiveDateTime {
     }
 }
 
+impl ToValue for DateTime<Utc> {
+    fn to_value(&self) -> Value {
+        Value::DateTimeTz(self.fixed_offset())
+    }
+}
+
+impl ToValue for DateTime<FixedOffset> {
+    fn to_value(&self) -> Value {
+        Value::DateTimeTz(*self)
+    }
+}
+
 impl ToValue for NaiveTime {
     fn to_value(&self) -> Value {
         Value::Time(*self)
     }
 }
 
+impl ToValue for Duration {
+    fn to_value(&self) -> Value {
+        Value::Duration(*self)
+    }
+}
+
+impl ToValue for std::time::Duration {
+    fn to_value(&self) -> Value {
+        Value::from(*self)
+    }
+}
+
 impl ToValue for Decimal {
     fn to_value(&self) -> Value {
         Value::Decimal(*self)
@@ -1,10 +1,11 @@
 //! Transaction traits for rdbi
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::traits::Pool;
 use std::future::Future;
 use std::pin::Pin;
 use std::result::Result as StdResult;
+use std::time::Duration;
 
 /// Transaction isolation level.
 ///
@@ -27,6 +28,116 @@ pub enum IsolationLevel {
     Serializable,
 }
 
+/// Transaction access mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessMode {
+    /// Reads and writes are allowed (`READ WRITE`).
+    ReadWrite,
+
+    /// Writes to non-temporary tables fail (`READ ONLY`). InnoDB can skip
+    /// assigning a transaction ID, which makes read-only transactions cheaper.
+    ReadOnly,
+}
+
+/// Options for starting a transaction.
+///
+/// Unset options fall back to the server's session defaults. An
+/// [`IsolationLevel`] converts into options with just that level set, so
+/// either can be passed to [`Transactional::in_transaction_with`].
+///
+/// # Example
+///
+/// ```ignore
+/// use std::time::Duration;
+///
+/// let options = TransactionOptions::new()
+///     .isolation_level(IsolationLevel::RepeatableRead)
+///     .read_only()
+///     .consistent_snapshot()
+///     .lock_wait_timeout(Duration::from_secs(5))
+///     .deadline(Duration::from_secs(30));
+/// let tx = pool.begin_with_options(options).await?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TransactionOptions {
+    /// Isolation level
+    pub isolation_level: Option<IsolationLevel>,
+    /// `READ ONLY` or `READ WRITE`
+    pub access_mode: Option<AccessMode>,
+    /// Start with `WITH CONSISTENT SNAPSHOT`
+    pub consistent_snapshot: bool,
+    /// `innodb_lock_wait_timeout` while the transaction is open
+    pub lock_wait_timeout: Option<Duration>,
+    /// Time from `begin` after which the transaction's statements and commit fail
+    pub deadline: Option<Duration>,
+}
+
+impl TransactionOptions {
+    /// Create options with everything left to the server defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the isolation level.
+    pub fn isolation_level(mut self, level: IsolationLevel) -> Self {
+        self.isolation_level = Some(level);
+        self
+    }
+
+    /// Set the access mode.
+    pub fn access_mode(mut self, mode: AccessMode) -> Self {
+        self.access_mode = Some(mode);
+        self
+    }
+
+    /// Start the transaction `READ ONLY`.
+    pub fn read_only(self) -> Self {
+        self.access_mode(AccessMode::ReadOnly)
+    }
+
+    /// Start the transaction `READ WRITE`.
+    pub fn read_write(self) -> Self {
+        self.access_mode(AccessMode::ReadWrite)
+    }
+
+    /// Take the transaction's snapshot at `begin` rather than at its first read.
+    ///
+    /// Only has an effect under [`IsolationLevel::RepeatableRead`].
+    pub fn consistent_snapshot(mut self) -> Self {
+        self.consistent_snapshot = true;
+        self
+    }
+
+    /// Set how long a statement waits for a row lock before failing.
+    ///
+    /// Applies `innodb_lock_wait_timeout`, in whole seconds (at least one),
+    /// for the transaction's duration; the previous value is restored on
+    /// commit or rollback, or when the transaction is dropped.
+    pub fn lock_wait_timeout(mut self, timeout: Duration) -> Self {
+        self.lock_wait_timeout = Some(timeout);
+        self
+    }
+
+    /// Set how long the transaction may stay open, measured from `begin`.
+    ///
+    /// Past the deadline, statements and `commit` fail with
+    /// [`Error::DeadlineExceeded`](crate::Error::DeadlineExceeded) and a
+    /// commit rolls back instead. A statement still running at the deadline
+    /// is killed on the server and fails the same way; the transaction is
+    /// then rolled back. A deadline too far off to represent, such as
+    /// `Duration::MAX`, is treated as none.
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+}
+
+impl From<IsolationLevel> for TransactionOptions {
+    fn from(level: IsolationLevel) -> Self {
+        Self::new().isolation_level(level)
+    }
+}
+
 /// A database transaction.
 ///
 /// Transaction implements `Pool`, so all query operations work unchanged within
@@ -72,13 +183,19 @@ pub trait Transaction: Pool {
 /// })).await?;
 /// ```
 ///
-/// ## With Isolation Level
+/// ## With Isolation Level or Options
 ///
 /// ```ignore
 /// pool.in_transaction_with(IsolationLevel::Serializable, |tx| Box::pin(async move {
 ///     // Critical section with serializable isolation
 ///     Ok(())
 /// })).await?;
+///
+/// let options = TransactionOptions::new().read_only().consistent_snapshot();
+/// pool.in_transaction_with(options, |tx| Box::pin(async move {
+///     // Reads see one snapshot
+///     Ok(())
+/// })).await?;
 /// ```
 ///
 /// ## Explicit Style
@@ -100,6 +217,33 @@ pub trait Transactional: Pool {
     /// Begin a new transaction with the specified isolation level.
     fn begin_with(&self, level: IsolationLevel) -> impl Future<Output = Result<Self::Tx>> + Send;
 
+    /// Begin a new transaction with the specified options.
+    ///
+    /// The default implementation only supports options that set nothing but
+    /// the isolation level, which it passes to [`begin_with`](Self::begin_with);
+    /// any other option fails with an error.
+    fn begin_with_options(
+        &self,
+        options: TransactionOptions,
+    ) -> impl Future<Output = Result<Self::Tx>> + Send {
+        async move {
+            let level_only = TransactionOptions {
+                isolation_level: options.isolation_level,
+                ..TransactionOptions::default()
+            };
+            if options != level_only {
+                return Err(Error::Query(
+                    "Transaction options other than the isolation level are not supported by this pool"
+                        .to_string(),
+                ));
+            }
+            match options.isolation_level {
+                Some(level) => self.begin_with(level).await,
+                None => self.begin().await,
+            }
+        }
+    }
+
     /// Execute a closure within a transaction.
     ///
     /// The transaction is automatically committed if the closure returns `Ok`,
@@ -146,7 +290,8 @@ pub trait Transactional: Pool {
                 -> Pin<Box<dyn Future<Output = StdResult<R, E>> + Send + 'a>>
             + Send;
 
-    /// Execute a closure within a transaction with the specified isolation level.
+    /// Execute a closure within a transaction with the specified isolation
+    /// level or [`TransactionOptions`].
     ///
     /// The transaction is automatically committed if the closure returns `Ok`,
     /// and rolled back if it returns `Err`. See [`in_transaction`](Self::in_transaction)
@@ -162,7 +307,7 @@ pub trait Transactional: Pool {
     /// ```
     fn in_transaction_with<R, E, F>(
         &self,
-        level: IsolationLevel,
+        options: impl Into<TransactionOptions> + Send,
         f: F,
     ) -> impl Future<Output = StdResult<R, E>> + Send
     where
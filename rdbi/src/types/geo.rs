@@ -0,0 +1,396 @@
+//! Spatial conversions for `geo-types` (`geo` feature)
+//!
+//! MySQL stores geometries in an internal format: a 4-byte little-endian
+//! SRID followed by the geometry's WKB (well-known binary). Values decode
+//! from and encode to that format; plain `geo-types` values use SRID 0, and
+//! [`Spatial`] carries an explicit SRID. Geographic SRIDs such as 4326 are
+//! stored longitude first, so `x` is longitude and `y` is latitude.
+
+use geo_types::{
+    Coord, Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon,
+    Point, Polygon,
+};
+
+use crate::error::{Error, Result};
+use crate::traits::{FromValue, ToValue};
+use crate::value::Value;
+
+const WKB_POINT: u32 = 1;
+const WKB_LINESTRING: u32 = 2;
+const WKB_POLYGON: u32 = 3;
+const WKB_MULTIPOINT: u32 = 4;
+const WKB_MULTILINESTRING: u32 = 5;
+const WKB_MULTIPOLYGON: u32 = 6;
+const WKB_GEOMETRYCOLLECTION: u32 = 7;
+
+/// A geometry with its spatial reference system identifier (SRID).
+///
+/// Use this for columns declared with an SRID, e.g. `POINT SRID 4326`,
+/// since MySQL rejects values whose SRID doesn't match the column.
+///
+/// # Example
+///
+/// ```ignore
+/// use geo_types::Point;
+/// use rdbi::{Query, Spatial};
+///
+/// Query::new("INSERT INTO stores (location) VALUES (?)")
+///     .bind(Spatial::new(4326, Point::new(13.4, 52.5)))
+///     .execute(pool)
+///     .await?;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spatial<T> {
+    /// Spatial reference system identifier, 0 for a plain Cartesian plane
+    pub srid: u32,
+    /// The geometry
+    pub geometry: T,
+}
+
+impl<T> Spatial<T> {
+    /// Create a geometry with the given SRID.
+    pub fn new(srid: u32, geometry: T) -> Self {
+        Self { srid, geometry }
+    }
+}
+
+impl<T: GeoKind> ToValue for Spatial<T> {
+    fn to_value(&self) -> Value {
+        Value::Bytes(encode(self.srid, &self.geometry.to_geometry()))
+    }
+}
+
+impl<T: GeoKind> FromValue for Spatial<T> {
+    fn from_value(value: Value) -> Result<Self> {
+        let (srid, geometry) = decode(value)?;
+        let geometry = T::from_geometry(geometry)?;
+        Ok(Self { srid, geometry })
+    }
+}
+
+/// A `geo-types` geometry that maps to a MySQL spatial type.
+pub trait GeoKind: Sized {
+    /// Convert to the generic geometry enum for encoding.
+    fn to_geometry(&self) -> Geometry<f64>;
+
+    /// Extract this kind from a decoded geometry.
+    fn from_geometry(geometry: Geometry<f64>) -> Result<Self>;
+}
+
+impl GeoKind for Geometry<f64> {
+    fn to_geometry(&self) -> Geometry<f64> {
+        self.clone()
+    }
+
+    fn from_geometry(geometry: Geometry<f64>) -> Result<Self> {
+        Ok(geometry)
+    }
+}
+
+macro_rules! impl_geo_kind {
+    ($($variant:ident => $expected:literal),* $(,)?) => {
+        $(
+            impl GeoKind for $variant<f64> {
+                fn to_geometry(&self) -> Geometry<f64> {
+                    Geometry::$variant(self.clone())
+                }
+
+                fn from_geometry(geometry: Geometry<f64>) -> Result<Self> {
+                    match geometry {
+                        Geometry::$variant(v) => Ok(v),
+                        other => Err(Error::TypeConversion {
+                            expected: $expected,
+                            actual: kind_name(&other).to_string(),
+                        }),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_geo_kind!(
+    Point => "point",
+    LineString => "linestring",
+    Polygon => "polygon",
+    MultiPoint => "multipoint",
+    MultiLineString => "multilinestring",
+    MultiPolygon => "multipolygon",
+    GeometryCollection => "geometrycollection",
+);
+
+macro_rules! impl_geo_value {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl ToValue for $ty {
+                fn to_value(&self) -> Value {
+                    Value::Bytes(encode(0, &self.to_geometry()))
+                }
+            }
+
+            /// The SRID is dropped; use [`Spatial`] to keep it.
+            impl FromValue for $ty {
+                fn from_value(value: Value) -> Result<Self> {
+                    let (_, geometry) = decode(value)?;
+                    Self::from_geometry(geometry)
+                }
+            }
+
+            impl From<$ty> for Value {
+                fn from(v: $ty) -> Self {
+                    v.to_value()
+                }
+            }
+        )*
+    };
+}
+
+impl_geo_value!(
+    Geometry<f64>,
+    Point<f64>,
+    LineString<f64>,
+    Polygon<f64>,
+    MultiPoint<f64>,
+    MultiLineString<f64>,
+    MultiPolygon<f64>,
+    GeometryCollection<f64>,
+);
+
+fn kind_name(geometry: &Geometry<f64>) -> &'static str {
+    match geometry {
+        Geometry::Point(_) => "point",
+        Geometry::Line(_) => "line",
+        Geometry::LineString(_) => "linestring",
+        Geometry::Polygon(_) => "polygon",
+        Geometry::MultiPoint(_) => "multipoint",
+        Geometry::MultiLineString(_) => "multilinestring",
+        Geometry::MultiPolygon(_) => "multipolygon",
+        Geometry::GeometryCollection(_) => "geometrycollection",
+        Geometry::Rect(_) => "rect",
+        Geometry::Triangle(_) => "triangle",
+    }
+}
+
+/// Decode MySQL's internal geometry format into its SRID and geometry.
+fn decode(value: Value) -> Result<(u32, Geometry<f64>)> {
+    let bytes = match value {
+        Value::Bytes(v) => v,
+        _ => {
+            return Err(Error::TypeConversion {
+                expected: "geometry",
+                actual: value.type_name().to_string(),
+            })
+        }
+    };
+    if bytes.len() < 4 {
+        return Err(invalid_wkb("missing SRID"));
+    }
+    let srid = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let mut reader = WkbReader {
+        bytes: &bytes[4..],
+        little_endian: true,
+    };
+    let geometry = reader.geometry()?;
+    if !reader.bytes.is_empty() {
+        return Err(invalid_wkb("trailing bytes"));
+    }
+    Ok((srid, geometry))
+}
+
+/// Encode a geometry in MySQL's internal format (little-endian WKB).
+fn encode(srid: u32, geometry: &Geometry<f64>) -> Vec<u8> {
+    let mut out = srid.to_le_bytes().to_vec();
+    write_geometry(&mut out, geometry);
+    out
+}
+
+fn invalid_wkb(reason: &str) -> Error {
+    Error::TypeConversion {
+        expected: "geometry",
+        actual: format!("invalid WKB: {}", reason),
+    }
+}
+
+struct WkbReader<'a> {
+    bytes: &'a [u8],
+    little_endian: bool,
+}
+
+impl WkbReader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        if self.bytes.len() < N {
+            return Err(invalid_wkb("unexpected end of data"));
+        }
+        let (head, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        Ok(head.try_into().expect("split at N"))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let b = self.take::<4>()?;
+        Ok(if self.little_endian {
+            u32::from_le_bytes(b)
+        } else {
+            u32::from_be_bytes(b)
+        })
+    }
+
+    fn f64(&mut self) -> Result<f64> {
+        let b = self.take::<8>()?;
+        Ok(if self.little_endian {
+            f64::from_le_bytes(b)
+        } else {
+            f64::from_be_bytes(b)
+        })
+    }
+
+    fn coord(&mut self) -> Result<Coord<f64>> {
+        Ok(Coord {
+            x: self.f64()?,
+            y: self.f64()?,
+        })
+    }
+
+    fn line_string(&mut self) -> Result<LineString<f64>> {
+        let n = self.u32()?;
+        (0..n).map(|_| self.coord()).collect()
+    }
+
+    fn polygon(&mut self) -> Result<Polygon<f64>> {
+        let n = self.u32()?;
+        let mut rings = (0..n)
+            .map(|_| self.line_string())
+            .collect::<Result<Vec<_>>>()?
+            .into_iter();
+        let exterior = rings.next().unwrap_or_else(|| LineString::new(Vec::new()));
+        Ok(Polygon::new(exterior, rings.collect()))
+    }
+
+    /// Read `n` nested geometries that must all be of one kind.
+    fn members<T>(&mut self, pick: fn(Geometry<f64>) -> Option<T>) -> Result<Vec<T>> {
+        let n = self.u32()?;
+        (0..n)
+            .map(|_| pick(self.geometry()?).ok_or_else(|| invalid_wkb("mixed multi-geometry")))
+            .collect()
+    }
+
+    fn geometry(&mut self) -> Result<Geometry<f64>> {
+        let outer = self.little_endian;
+        self.little_endian = match self.take::<1>()?[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(invalid_wkb("bad byte order")),
+        };
+        let geometry = match self.u32()? {
+            WKB_POINT => Geometry::Point(Point(self.coord()?)),
+            WKB_LINESTRING => Geometry::LineString(self.line_string()?),
+            WKB_POLYGON => Geometry::Polygon(self.polygon()?),
+            WKB_MULTIPOINT => Geometry::MultiPoint(MultiPoint(self.members(|g| match g {
+                Geometry::Point(v) => Some(v),
+                _ => None,
+            })?)),
+            WKB_MULTILINESTRING => {
+                Geometry::MultiLineString(MultiLineString(self.members(|g| match g {
+                    Geometry::LineString(v) => Some(v),
+                    _ => None,
+                })?))
+            }
+            WKB_MULTIPOLYGON => {
+                Geometry::MultiPolygon(MultiPolygon(self.members(|g| match g {
+                    Geometry::Polygon(v) => Some(v),
+                    _ => None,
+                })?))
+            }
+            WKB_GEOMETRYCOLLECTION => {
+                Geometry::GeometryCollection(GeometryCollection(self.members(Some)?))
+            }
+            other => return Err(invalid_wkb(&format!("unknown geometry type {}", other))),
+        };
+        self.little_endian = outer;
+        Ok(geometry)
+    }
+}
+
+fn write_header(out: &mut Vec<u8>, kind: u32) {
+    out.push(1); // little endian
+    out.extend_from_slice(&kind.to_le_bytes());
+}
+
+fn write_count(out: &mut Vec<u8>, n: usize) {
+    out.extend_from_slice(&(n as u32).to_le_bytes());
+}
+
+fn write_coord(out: &mut Vec<u8>, c: Coord<f64>) {
+    out.extend_from_slice(&c.x.to_le_bytes());
+    out.extend_from_slice(&c.y.to_le_bytes());
+}
+
+fn write_line_string(out: &mut Vec<u8>, line: &LineString<f64>) {
+    write_count(out, line.0.len());
+    for c in &line.0 {
+        write_coord(out, *c);
+    }
+}
+
+fn write_polygon(out: &mut Vec<u8>, polygon: &Polygon<f64>) {
+    write_count(out, 1 + polygon.interiors().len());
+    write_line_string(out, polygon.exterior());
+    for ring in polygon.interiors() {
+        write_line_string(out, ring);
+    }
+}
+
+fn write_geometry(out: &mut Vec<u8>, geometry: &Geometry<f64>) {
+    match geometry {
+        Geometry::Point(p) => {
+            write_header(out, WKB_POINT);
+            write_coord(out, p.0);
+        }
+        Geometry::Line(line) => {
+            write_header(out, WKB_LINESTRING);
+            write_line_string(out, &LineString::from(*line));
+        }
+        Geometry::LineString(line) => {
+            write_header(out, WKB_LINESTRING);
+            write_line_string(out, line);
+        }
+        Geometry::Polygon(polygon) => {
+            write_header(out, WKB_POLYGON);
+            write_polygon(out, polygon);
+        }
+        Geometry::Rect(rect) => write_geometry(out, &Geometry::Polygon(rect.to_polygon())),
+        Geometry::Triangle(triangle) => {
+            write_geometry(out, &Geometry::Polygon(triangle.to_polygon()))
+        }
+        Geometry::MultiPoint(points) => {
+            write_header(out, WKB_MULTIPOINT);
+            write_count(out, points.0.len());
+            for p in &points.0 {
+                write_geometry(out, &Geometry::Point(*p));
+            }
+        }
+        Geometry::MultiLineString(lines) => {
+            write_header(out, WKB_MULTILINESTRING);
+            write_count(out, lines.0.len());
+            for line in &lines.0 {
+                write_header(out, WKB_LINESTRING);
+                write_line_string(out, line);
+            }
+        }
+        Geometry::MultiPolygon(polygons) => {
+            write_header(out, WKB_MULTIPOLYGON);
+            write_count(out, polygons.0.len());
+            for polygon in &polygons.0 {
+                write_header(out, WKB_POLYGON);
+                write_polygon(out, polygon);
+            }
+        }
+        Geometry::GeometryCollection(collection) => {
+            write_header(out, WKB_GEOMETRYCOLLECTION);
+            write_count(out, collection.0.len());
+            for member in &collection.0 {
+                write_geometry(out, member);
+            }
+        }
+    }
+}
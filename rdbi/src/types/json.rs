@@ -0,0 +1,126 @@
+//! Typed JSON columns
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use serde::de::{DeserializeOwned, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::error::{Error, Result};
+use crate::traits::{FromValue, ToValue};
+use crate::value::Value;
+
+/// A JSON column decoded into `T` with serde.
+///
+/// Reading fails with [`Error::TypeConversion`] when the document does not
+/// match `T`; [`RowExt::get`](crate::RowExt::get) adds the column name.
+///
+/// # Example
+///
+/// ```ignore
+/// use rdbi::{Json, Query};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Settings {
+///     theme: String,
+/// }
+///
+/// let settings: Json<Settings> = Query::new("SELECT settings FROM users WHERE id = ?")
+///     .bind(id)
+///     .fetch_scalar(pool)
+///     .await?;
+/// println!("{}", settings.theme);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(transparent)]
+pub struct Json<T>(pub T);
+
+/// Newtype name `Json<T>` deserializes through, so [`crate::de`] can parse
+/// a JSON column's text; other formats see a transparent wrapper.
+pub(crate) const JSON_NEWTYPE: &str = "$rdbi::Json";
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Json<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct JsonVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for JsonVisitor<T> {
+            type Value = Json<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a JSON document")
+            }
+
+            fn visit_newtype_struct<D: Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> std::result::Result<Json<T>, D::Error> {
+                T::deserialize(deserializer).map(Json)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(JSON_NEWTYPE, JsonVisitor(PhantomData))
+    }
+}
+
+impl<T> Json<T> {
+    /// Unwrap the decoded value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Json<T> {
+    fn from(v: T) -> Self {
+        Self(v)
+    }
+}
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+/// If `T` fails to serialize, e.g. a map with non-string keys, this gives
+/// [`Value::Invalid`], and executing a statement with it bound fails with
+/// [`Error::TypeConversion`](crate::Error::TypeConversion).
+impl<T: Serialize> ToValue for Json<T> {
+    fn to_value(&self) -> Value {
+        match serde_json::to_value(&self.0) {
+            Ok(v) => Value::Json(v),
+            Err(e) => Value::Invalid {
+                expected: "json",
+                actual: format!("{} ({})", std::any::type_name::<T>(), e),
+            },
+        }
+    }
+}
+
+impl<T: DeserializeOwned> FromValue for Json<T> {
+    fn from_value(value: Value) -> Result<Self> {
+        let parsed = match value {
+            Value::Json(v) => serde_json::from_value(v),
+            Value::String(v) => serde_json::from_str(&v),
+            Value::Bytes(v) => serde_json::from_slice(&v),
+            _ => {
+                return Err(Error::TypeConversion {
+                    expected: "json",
+                    actual: value.type_name().to_string(),
+                })
+            }
+        };
+        parsed.map(Json).map_err(|e| Error::TypeConversion {
+            expected: "json",
+            actual: format!("invalid {}: {}", std::any::type_name::<T>(), e),
+        })
+    }
+}
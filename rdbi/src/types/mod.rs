@@ -0,0 +1,19 @@
+//! Column types beyond the built-in `Value` variants
+//!
+//! Types for optional crates are behind a cargo feature of the same name.
+//! All of them convert through the existing `Value` variants.
+
+#[cfg(feature = "geo")]
+mod geo;
+mod json;
+#[cfg(feature = "time")]
+mod time;
+#[cfg(feature = "uuid")]
+mod uuid;
+
+#[cfg(feature = "geo")]
+pub use self::geo::{GeoKind, Spatial};
+#[cfg(feature = "uuid")]
+pub use self::uuid::SwappedUuid;
+pub use json::Json;
+pub(crate) use json::JSON_NEWTYPE;
@@ -0,0 +1,152 @@
+//! `time` crate conversions (`time` feature)
+//!
+//! Values convert through the chrono-based `Value` variants, so the `time`
+//! types accept the same database values as their chrono counterparts.
+//! Dates beyond chrono's range (possible with `time`'s `large-dates`
+//! feature) are bound as text, which the server rejects.
+
+use ::time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+use chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone,
+    Timelike,
+};
+
+use crate::error::{Error, Result};
+use crate::traits::{FromValue, ToValue};
+use crate::value::Value;
+
+fn out_of_range(expected: &'static str, actual: impl ToString) -> Error {
+    Error::TypeConversion {
+        expected,
+        actual: actual.to_string(),
+    }
+}
+
+fn date_to_chrono(v: Date) -> Option<NaiveDate> {
+    NaiveDate::from_yo_opt(v.year(), v.ordinal() as u32)
+}
+
+fn date_from_chrono(v: NaiveDate) -> Result<Date> {
+    Date::from_ordinal_date(v.year(), v.ordinal() as u16).map_err(|_| out_of_range("time::Date", v))
+}
+
+fn time_to_chrono(v: Time) -> NaiveTime {
+    let (hour, minute, second, nanosecond) = v.as_hms_nano();
+    let seconds = hour as i64 * 3600 + minute as i64 * 60 + second as i64;
+    NaiveTime::default() + TimeDelta::seconds(seconds) + TimeDelta::nanoseconds(nanosecond as i64)
+}
+
+fn datetime_to_chrono(v: PrimitiveDateTime) -> Option<NaiveDateTime> {
+    Some(NaiveDateTime::new(
+        date_to_chrono(v.date())?,
+        time_to_chrono(v.time()),
+    ))
+}
+
+/// Offsets chrono cannot represent (24 hours or more) are normalized to UTC.
+fn offset_datetime_to_chrono(v: OffsetDateTime) -> Option<DateTime<FixedOffset>> {
+    let local = datetime_to_chrono(PrimitiveDateTime::new(v.date(), v.time()))?;
+    let seconds = v.offset().whole_seconds();
+    match FixedOffset::east_opt(seconds) {
+        Some(offset) => offset.from_local_datetime(&local).single(),
+        None => {
+            let utc = local.checked_sub_signed(TimeDelta::seconds(seconds as i64))?;
+            Some(utc.and_utc().fixed_offset())
+        }
+    }
+}
+
+fn time_from_chrono(v: NaiveTime) -> Result<Time> {
+    // chrono represents leap seconds as nanoseconds past 1_000_000_000
+    Time::from_hms_nano(
+        v.hour() as u8,
+        v.minute() as u8,
+        v.second() as u8,
+        v.nanosecond(),
+    )
+    .map_err(|_| out_of_range("time::Time", v))
+}
+
+fn datetime_from_chrono(v: NaiveDateTime) -> Result<PrimitiveDateTime> {
+    Ok(PrimitiveDateTime::new(
+        date_from_chrono(v.date())?,
+        time_from_chrono(v.time())?,
+    ))
+}
+
+impl ToValue for Date {
+    fn to_value(&self) -> Value {
+        date_to_chrono(*self).map_or_else(|| Value::String(self.to_string()), Value::Date)
+    }
+}
+
+impl FromValue for Date {
+    fn from_value(value: Value) -> Result<Self> {
+        date_from_chrono(NaiveDate::from_value(value)?)
+    }
+}
+
+impl ToValue for Time {
+    fn to_value(&self) -> Value {
+        Value::Time(time_to_chrono(*self))
+    }
+}
+
+impl FromValue for Time {
+    fn from_value(value: Value) -> Result<Self> {
+        time_from_chrono(NaiveTime::from_value(value)?)
+    }
+}
+
+impl ToValue for PrimitiveDateTime {
+    fn to_value(&self) -> Value {
+        datetime_to_chrono(*self).map_or_else(|| Value::String(self.to_string()), Value::DateTime)
+    }
+}
+
+impl FromValue for PrimitiveDateTime {
+    fn from_value(value: Value) -> Result<Self> {
+        datetime_from_chrono(NaiveDateTime::from_value(value)?)
+    }
+}
+
+impl ToValue for OffsetDateTime {
+    fn to_value(&self) -> Value {
+        offset_datetime_to_chrono(*self)
+            .map_or_else(|| Value::String(self.to_string()), Value::DateTimeTz)
+    }
+}
+
+/// Naive values are taken as UTC, like for `chrono::DateTime<Utc>`.
+impl FromValue for OffsetDateTime {
+    fn from_value(value: Value) -> Result<Self> {
+        let v = DateTime::<FixedOffset>::from_value(value)?;
+        let offset = UtcOffset::from_whole_seconds(v.offset().local_minus_utc())
+            .map_err(|_| out_of_range("time::OffsetDateTime", v))?;
+        Ok(datetime_from_chrono(v.naive_local())?.assume_offset(offset))
+    }
+}
+
+impl From<Date> for Value {
+    fn from(v: Date) -> Self {
+        v.to_value()
+    }
+}
+
+impl From<Time> for Value {
+    fn from(v: Time) -> Self {
+        v.to_value()
+    }
+}
+
+impl From<PrimitiveDateTime> for Value {
+    fn from(v: PrimitiveDateTime) -> Self {
+        v.to_value()
+    }
+}
+
+impl From<OffsetDateTime> for Value {
+    fn from(v: OffsetDateTime) -> Self {
+        v.to_value()
+    }
+}
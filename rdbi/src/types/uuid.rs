@@ -0,0 +1,124 @@
+//! UUID conversions (`uuid` feature)
+//!
+//! `Uuid` is written as 16 bytes for `BINARY(16)` columns and read back from
+//! either storage form: 16 raw bytes, or text such as a `CHAR(36)` column.
+//! Bind `uuid.hyphenated()` to write the text form.
+
+use ::uuid::fmt::Hyphenated;
+use ::uuid::Uuid;
+
+use crate::error::{Error, Result};
+use crate::traits::{FromValue, ToValue};
+use crate::value::Value;
+
+impl ToValue for Uuid {
+    fn to_value(&self) -> Value {
+        Value::Bytes(self.as_bytes().to_vec())
+    }
+}
+
+impl FromValue for Uuid {
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            // 16 raw bytes, or text from a column with a binary collation
+            Value::Bytes(v) => Uuid::from_slice(&v)
+                .or_else(|_| Uuid::try_parse_ascii(&v))
+                .map_err(|_| Error::TypeConversion {
+                    expected: "uuid",
+                    actual: format!("{} bytes", v.len()),
+                }),
+            Value::String(v) => Uuid::try_parse(&v).map_err(|_| Error::TypeConversion {
+                expected: "uuid",
+                actual: format!("invalid uuid string: {}", v),
+            }),
+            _ => Err(Error::TypeConversion {
+                expected: "uuid",
+                actual: value.type_name().to_string(),
+            }),
+        }
+    }
+}
+
+impl From<Uuid> for Value {
+    fn from(v: Uuid) -> Self {
+        v.to_value()
+    }
+}
+
+impl ToValue for Hyphenated {
+    fn to_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl FromValue for Hyphenated {
+    fn from_value(value: Value) -> Result<Self> {
+        Uuid::from_value(value).map(Uuid::hyphenated)
+    }
+}
+
+/// A UUID stored in `BINARY(16)` with MySQL's `UUID_TO_BIN(uuid, 1)` byte order.
+///
+/// The swapped order moves the time-high and time-mid fields before
+/// time-low, so time-based UUIDs are stored in creation order and index well.
+///
+/// # Example
+///
+/// ```ignore
+/// use rdbi::{Query, SwappedUuid};
+///
+/// Query::new("INSERT INTO events (event_uuid) VALUES (?)")
+///     .bind(SwappedUuid(id))
+///     .execute(pool)
+///     .await?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SwappedUuid(pub Uuid);
+
+impl SwappedUuid {
+    /// Get the UUID in its standard byte order.
+    pub fn into_inner(self) -> Uuid {
+        self.0
+    }
+}
+
+impl From<Uuid> for SwappedUuid {
+    fn from(v: Uuid) -> Self {
+        Self(v)
+    }
+}
+
+impl From<SwappedUuid> for Uuid {
+    fn from(v: SwappedUuid) -> Self {
+        v.0
+    }
+}
+
+impl ToValue for SwappedUuid {
+    fn to_value(&self) -> Value {
+        let b = self.0.as_bytes();
+        let mut swapped = [0u8; 16];
+        swapped[..2].copy_from_slice(&b[6..8]);
+        swapped[2..4].copy_from_slice(&b[4..6]);
+        swapped[4..8].copy_from_slice(&b[..4]);
+        swapped[8..].copy_from_slice(&b[8..]);
+        Value::Bytes(swapped.to_vec())
+    }
+}
+
+impl FromValue for SwappedUuid {
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Bytes(s) if s.len() == 16 => {
+                let mut b = [0u8; 16];
+                b[..4].copy_from_slice(&s[4..8]);
+                b[4..6].copy_from_slice(&s[2..4]);
+                b[6..8].copy_from_slice(&s[..2]);
+                b[8..].copy_from_slice(&s[8..]);
+                Ok(Self(Uuid::from_bytes(b)))
+            }
+            // Text has no byte order to undo
+            _ => Uuid::from_value(value).map(Self),
+        }
+    }
+}
@@ -1,12 +1,26 @@
 //! Dynamic Value type for database values
 
-use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use std::fmt;
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use rust_decimal::Decimal;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
 
 /// A dynamic database value that can represent any MySQL column type.
 ///
 /// This enum provides a type-safe way to pass values to queries and
 /// convert between Rust types and MySQL types.
+///
+/// `Display` renders the value as a MySQL literal (`'it\'s'`, `X'00ff'`,
+/// `NULL`), for logging and [`Query::to_debug_sql`](crate::Query::to_debug_sql).
+/// It is not a substitute for binding parameters.
+///
+/// With serde, values map to their natural JSON form: numbers, strings,
+/// `null`, and embedded documents for `Json`. Dates and times serialize as
+/// ISO 8601 strings, durations as MySQL `TIME` text (`-01:30:00`), decimals
+/// as strings and bytes as a byte array. Deserializing is lossy: these all
+/// come back as `String`, `Bytes` or `Json`.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     /// SQL NULL value
@@ -41,12 +55,34 @@ pub enum Value {
     Date(NaiveDate),
     /// DateTime/Timestamp value
     DateTime(NaiveDateTime),
-    /// Time value
+    /// DateTime/Timestamp value with a known UTC offset
+    ///
+    /// Written in the pool's session time zone, and refused by pools without
+    /// one; DATETIME and TIMESTAMP columns decode to this variant when a
+    /// session time zone is configured.
+    DateTimeTz(DateTime<FixedOffset>),
+    /// Time of day value
     Time(NaiveTime),
+    /// Signed time interval
+    ///
+    /// MySQL TIME columns decode to this variant, since they range over
+    /// -838:59:59 to 838:59:59 rather than a single day.
+    Duration(Duration),
     /// Decimal value
     Decimal(Decimal),
     /// JSON value
     Json(serde_json::Value),
+    /// A value that failed to convert, e.g. a [`Json`](crate::Json) whose
+    /// contents did not serialize
+    ///
+    /// Executing a statement with it bound fails with
+    /// [`Error::TypeConversion`](crate::Error::TypeConversion).
+    Invalid {
+        /// What the conversion was producing
+        expected: &'static str,
+        /// Why it failed
+        actual: String,
+    },
 }
 
 impl Value {
@@ -74,13 +110,200 @@ impl Value {
             Value::Bytes(_) => "bytes",
             Value::Date(_) => "date",
             Value::DateTime(_) => "datetime",
+            Value::DateTimeTz(_) => "datetime with time zone",
             Value::Time(_) => "time",
+            Value::Duration(_) => "duration",
             Value::Decimal(_) => "decimal",
             Value::Json(_) => "json",
+            Value::Invalid { .. } => "invalid",
+        }
+    }
+}
+
+/// Displays a duration as MySQL TIME text, e.g. `-838:59:59` or `12:00:00.250000`.
+pub(crate) struct DurationText<'a>(pub &'a Duration);
+
+impl fmt::Display for DurationText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if *self.0 < Duration::zero() { "-" } else { "" };
+        let abs = self.0.abs();
+        let secs = abs.num_seconds();
+        write!(
+            f,
+            "{}{:02}:{:02}:{:02}",
+            sign,
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )?;
+        match abs.subsec_nanos() / 1000 {
+            0 => Ok(()),
+            micros => write!(f, ".{:06}", micros),
+        }
+    }
+}
+
+/// Write a string as a quoted MySQL literal, escaping as `mysql_real_escape_string` does.
+fn write_quoted(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str("'")?;
+    for c in s.chars() {
+        match c {
+            '\0' => f.write_str("\\0")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\x1a' => f.write_str("\\Z")?,
+            '\'' => f.write_str("\\'")?,
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("'")
+}
+
+/// Renders the value as a MySQL literal.
+///
+/// Strings are escaped for the default SQL mode; with `NO_BACKSLASH_ESCAPES`
+/// the output is not valid SQL for strings containing quotes or backslashes.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => f.write_str("NULL"),
+            Value::Bool(v) => f.write_str(if *v { "TRUE" } else { "FALSE" }),
+            Value::I8(v) => write!(f, "{}", v),
+            Value::I16(v) => write!(f, "{}", v),
+            Value::I32(v) => write!(f, "{}", v),
+            Value::I64(v) => write!(f, "{}", v),
+            Value::U8(v) => write!(f, "{}", v),
+            Value::U16(v) => write!(f, "{}", v),
+            Value::U32(v) => write!(f, "{}", v),
+            Value::U64(v) => write!(f, "{}", v),
+            // MySQL has no literal for NaN or infinity
+            Value::F32(v) if !v.is_finite() => write!(f, "'{}'", v),
+            Value::F64(v) if !v.is_finite() => write!(f, "'{}'", v),
+            Value::F32(v) => write!(f, "{:?}", v),
+            Value::F64(v) => write!(f, "{:?}", v),
+            Value::String(v) => write_quoted(f, v),
+            Value::Bytes(v) => {
+                f.write_str("X'")?;
+                for b in v {
+                    write!(f, "{:02x}", b)?;
+                }
+                f.write_str("'")
+            }
+            Value::Date(v) => write!(f, "'{}'", v.format("%Y-%m-%d")),
+            Value::DateTime(v) => write!(f, "'{}'", v.format("%Y-%m-%d %H:%M:%S%.f")),
+            Value::DateTimeTz(v) => write!(f, "'{}'", v.format("%Y-%m-%d %H:%M:%S%.f%:z")),
+            Value::Time(v) => write!(f, "'{}'", v.format("%H:%M:%S%.f")),
+            Value::Duration(v) => write!(f, "'{}'", DurationText(v)),
+            Value::Decimal(v) => write!(f, "{}", v),
+            Value::Json(v) => write_quoted(f, &v.to_string()),
+            Value::Invalid { expected, .. } => write!(f, "NULL /* invalid {} */", expected),
         }
     }
 }
 
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_none(),
+            Value::Bool(v) => serializer.serialize_bool(*v),
+            Value::I8(v) => serializer.serialize_i8(*v),
+            Value::I16(v) => serializer.serialize_i16(*v),
+            Value::I32(v) => serializer.serialize_i32(*v),
+            Value::I64(v) => serializer.serialize_i64(*v),
+            Value::U8(v) => serializer.serialize_u8(*v),
+            Value::U16(v) => serializer.serialize_u16(*v),
+            Value::U32(v) => serializer.serialize_u32(*v),
+            Value::U64(v) => serializer.serialize_u64(*v),
+            Value::F32(v) => serializer.serialize_f32(*v),
+            Value::F64(v) => serializer.serialize_f64(*v),
+            Value::String(v) => serializer.serialize_str(v),
+            Value::Bytes(v) => serializer.serialize_bytes(v),
+            Value::Date(v) => v.serialize(serializer),
+            Value::DateTime(v) => v.serialize(serializer),
+            Value::DateTimeTz(v) => v.serialize(serializer),
+            Value::Time(v) => v.serialize(serializer),
+            Value::Duration(v) => serializer.collect_str(&DurationText(v)),
+            Value::Decimal(v) => serializer.collect_str(v),
+            Value::Json(v) => v.serialize(serializer),
+            Value::Invalid { expected, actual } => Err(serde::ser::Error::custom(format!(
+                "invalid {}: {}",
+                expected, actual
+            ))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a database value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> std::result::Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> std::result::Result<Value, E> {
+        Ok(Value::I64(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> std::result::Result<Value, E> {
+        Ok(Value::U64(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> std::result::Result<Value, E> {
+        Ok(Value::F64(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> std::result::Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> std::result::Result<Value, E> {
+        Ok(Value::Bytes(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> std::result::Result<Value, E> {
+        Ok(Value::Bytes(v))
+    }
+
+    fn visit_none<E: de::Error>(self) -> std::result::Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> std::result::Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, d: D) -> std::result::Result<Value, D::Error> {
+        Value::deserialize(d)
+    }
+
+    // Arrays and objects can only have come from a JSON document
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> std::result::Result<Value, A::Error> {
+        serde_json::Value::deserialize(de::value::SeqAccessDeserializer::new(seq)).map(Value::Json)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> std::result::Result<Value, A::Error> {
+        serde_json::Value::deserialize(de::value::MapAccessDeserializer::new(map)).map(Value::Json)
+    }
+}
+
 // Implement From for common types
 impl From<bool> for Value {
     fn from(v: bool) -> Self {
@@ -184,12 +407,37 @@ impl From<NaiveDateTime> for Value {
     }
 }
 
+impl From<DateTime<Utc>> for Value {
+    fn from(v: DateTime<Utc>) -> Self {
+        Value::DateTimeTz(v.fixed_offset())
+    }
+}
+
+impl From<DateTime<FixedOffset>> for Value {
+    fn from(v: DateTime<FixedOffset>) -> Self {
+        Value::DateTimeTz(v)
+    }
+}
+
 impl From<NaiveTime> for Value {
     fn from(v: NaiveTime) -> Self {
         Value::Time(v)
     }
 }
 
+impl From<Duration> for Value {
+    fn from(v: Duration) -> Self {
+        Value::Duration(v)
+    }
+}
+
+impl From<std::time::Duration> for Value {
+    fn from(v: std::time::Duration) -> Self {
+        // Anything this long is far outside the TIME range and rejected by the server
+        Value::Duration(Duration::from_std(v).unwrap_or(Duration::MAX))
+    }
+}
+
 impl From<Decimal> for Value {
     fn from(v: Decimal) -> Self {
         Value::Decimal(v)